@@ -0,0 +1,16 @@
+//! Parsing any bytes gives shortcuts or an error, and never panics.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use steam_shortcuts_util::{parse_shortcut_views, parse_shortcuts};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_shortcuts(data);
+    // Views only check the texts that are read
+    if let Ok(views) = parse_shortcut_views(data) {
+        for view in views {
+            let _ = view.to_shortcut();
+            let _ = view.tags().count();
+        }
+    }
+});
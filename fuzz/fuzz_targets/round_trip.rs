@@ -0,0 +1,21 @@
+//! Shortcuts that are written and parsed again are the shortcuts that were written.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use steam_shortcuts_util::{parse_shortcuts, shortcuts_to_bytes};
+
+fuzz_target!(|data: &[u8]| {
+    let shortcuts = match parse_shortcuts(data) {
+        Ok(shortcuts) => shortcuts,
+        Err(_) => return,
+    };
+    let written = shortcuts_to_bytes(&shortcuts);
+    let parsed = parse_shortcuts(&written).unwrap();
+    assert_eq!(shortcuts, parsed);
+    assert_eq!(written, shortcuts_to_bytes(&parsed));
+
+    // The crate writes valid UTF-8, so the unchecked parser gives the same shortcuts
+    let trusted =
+        unsafe { steam_shortcuts_util::shortcuts_parser::parse_shortcuts_trusted(&written) };
+    assert_eq!(parsed, trusted.unwrap());
+});
@@ -0,0 +1,106 @@
+#ifndef STEAM_SHORTCUTS_UTIL_H
+#define STEAM_SHORTCUTS_UTIL_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ * A shortcut, with strings as null terminated UTF-8.
+ */
+typedef struct SsuShortcut {
+  uint32_t app_id;
+  char *app_name;
+  char *exe;
+  char *start_dir;
+  char *icon;
+  char *shortcut_path;
+  char *launch_options;
+  bool is_hidden;
+  bool allow_desktop_config;
+  bool allow_overlay;
+  uint32_t open_vr;
+  uint32_t dev_kit;
+  char *dev_kit_game_id;
+  uint32_t dev_kit_override_app_id;
+  uint32_t last_play_time;
+  char *flatpak_app_id;
+  char **tags;
+  uintptr_t tags_len;
+} SsuShortcut;
+
+/*
+ * A list of shortcuts, made by [ssu_parse_shortcuts].
+ */
+typedef struct SsuShortcutList {
+  struct SsuShortcut *shortcuts;
+  uintptr_t len;
+} SsuShortcutList;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ * Parse the content of a shortcuts.vdf file.
+ *
+ * Returns 0 and fills `out` on success, which must be freed with [ssu_free_shortcuts].
+ * Returns -1 if the content could not be parsed, an argument is null, or on a panic.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, and `out` must point to writable memory.
+ */
+int32_t ssu_parse_shortcuts(const uint8_t *data, uintptr_t len, struct SsuShortcutList *out);
+
+/*
+ * Free a list made by [ssu_parse_shortcuts], and set it to an empty list.
+ *
+ * # Safety
+ *
+ * `list` must be null or a list filled by [ssu_parse_shortcuts] that was not freed yet.
+ */
+void ssu_free_shortcuts(struct SsuShortcutList *list);
+
+/*
+ * Serialize shortcuts to the content of a shortcuts.vdf file.
+ *
+ * Returns the bytes and writes their length to `out_len`, free them with [ssu_free_bytes].
+ * Returns null if a string is not valid UTF-8, an argument is null, or on a panic.
+ *
+ * # Safety
+ *
+ * `shortcuts` must point to `len` shortcuts whose strings are null terminated
+ * (null strings are read as empty), and `out_len` must point to writable memory.
+ */
+uint8_t *ssu_shortcuts_to_bytes(const struct SsuShortcut *shortcuts,
+                                uintptr_t len,
+                                uintptr_t *out_len);
+
+/*
+ * Free bytes made by [ssu_shortcuts_to_bytes].
+ *
+ * # Safety
+ *
+ * `bytes` must be null or returned by [ssu_shortcuts_to_bytes] with this `len`,
+ * and not freed yet.
+ */
+void ssu_free_bytes(uint8_t *bytes, uintptr_t len);
+
+/*
+ * Calculate the app id steam uses for a shortcut with this exe and app name.
+ *
+ * Returns 0 if an argument is null or not valid UTF-8, or on a panic.
+ *
+ * # Safety
+ *
+ * `exe` and `app_name` must be null or null terminated strings.
+ */
+uint32_t ssu_calculate_app_id(const char *exe, const char *app_name);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* STEAM_SHORTCUTS_UTIL_H */
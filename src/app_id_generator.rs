@@ -1,6 +1,8 @@
+use alloc::vec;
+use alloc::vec::Vec;
 use crc32fast::Hasher;
 
-use crate::shortcut::Shortcut;
+use crate::shortcut::{Shortcut, ShortcutOwned};
 
 /// Calculate an app id for a shortcut.
 ///
@@ -15,9 +17,203 @@ pub fn calculate_app_id_for_shortcut(shortcut: &Shortcut) -> u32 {
 /// The app id is a 32-bit hash of the shortcut exe path and its app_name.
 /// It is used to identify custom images for the shortcut.
 pub fn calculate_app_id(exe: &str, app_name: &str) -> u32 {
+    calculate_app_id_bytes(exe.as_bytes(), app_name.as_bytes())
+}
+
+/// Calculate an app id for a exe and app_name given as raw bytes.
+///
+/// Use this when the exe path is not valid UTF-8 (which is allowed on Linux).
+/// For UTF-8 input it gives the same result as [calculate_app_id].
+pub fn calculate_app_id_bytes(exe: &[u8], app_name: &[u8]) -> u32 {
     let mut hasher = Hasher::new();
-    let combined = format!("{}{}", exe, app_name);
-    hasher.update(combined.as_bytes());
+    hasher.update(exe);
+    hasher.update(app_name);
     let checksum = hasher.finalize();
     checksum | 0x80000000
 }
+
+/// Convert an app id to the signed form that steam sometimes displays.
+///
+/// Shortcut app ids always have the highest bit set, so read as an `i32` they are negative.
+/// The bits are kept as they are, e.g. `2365067149` becomes `-1929900147`.
+/// Use [from_signed] to convert back.
+pub fn as_signed(app_id: u32) -> i32 {
+    app_id as i32
+}
+
+/// Convert a signed app id (as steam sometimes displays it) back to the `u32` app id.
+///
+/// The bits are kept as they are, e.g. `-1929900147` becomes `2365067149`.
+/// This is the inverse of [as_signed].
+pub fn from_signed(app_id: i32) -> u32 {
+    app_id as u32
+}
+
+/// Calculate the 64-bit game id of a shortcut from its app id.
+///
+/// This is the id steam uses to launch a shortcut with a `steam://rungameid/<game id>` url.
+pub fn calculate_game_id(app_id: u32) -> u64 {
+    ((app_id as u64) << 32) | 0x02000000
+}
+
+/// Find the shortcuts whose stored app id differs from the calculated one.
+///
+/// This usually happens when a tool has changed the exe or app_name of a shortcut
+/// without recalculating its app id, which breaks the lookup of custom images.
+pub fn find_stale_app_ids<'a, 'b>(shortcuts: &'b [Shortcut<'a>]) -> Vec<&'b Shortcut<'a>> {
+    shortcuts
+        .iter()
+        .filter(|shortcut| !shortcut.app_id_matches_calculation())
+        .collect()
+}
+
+/// Find app ids that are shared by more than one shortcut.
+///
+/// Shortcuts with the same app id will share custom images in steam.
+/// The collisions are returned in the order their app id first appears in the list.
+pub fn find_app_id_collisions<'a, 'b>(
+    shortcuts: &'b [Shortcut<'a>],
+) -> Vec<(u32, Vec<&'b Shortcut<'a>>)> {
+    let mut grouped: Vec<(u32, Vec<&'b Shortcut<'a>>)> = vec![];
+    for shortcut in shortcuts {
+        match grouped
+            .iter_mut()
+            .find(|(app_id, _)| *app_id == shortcut.app_id)
+        {
+            Some((_, group)) => group.push(shortcut),
+            None => grouped.push((shortcut.app_id, vec![shortcut])),
+        }
+    }
+    grouped.retain(|(_, group)| group.len() > 1);
+    grouped
+}
+
+/// A change of app id made by [recalculate_app_ids].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIdChange {
+    /// Index of the shortcut in the list that was recalculated
+    pub index: usize,
+    /// The app id the shortcut had before
+    pub old_app_id: u32,
+    /// The app id the shortcut has now
+    pub new_app_id: u32,
+}
+
+/// Recalculate the app id of every shortcut from its exe and app_name.
+///
+/// Returns the shortcuts whose app id changed, so custom images stored under
+/// the old app id can be moved to the new one.
+pub fn recalculate_app_ids(shortcuts: &mut [ShortcutOwned]) -> Vec<AppIdChange> {
+    shortcuts
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(index, shortcut)| {
+            let new_app_id = calculate_app_id(&shortcut.exe, &shortcut.app_name);
+            let old_app_id = shortcut.app_id;
+            shortcut.app_id = new_app_id;
+            if old_app_id == new_app_id {
+                None
+            } else {
+                Some(AppIdChange {
+                    index,
+                    old_app_id,
+                    new_app_id,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn new_shortcut_matches_calculation() {
+        let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        assert!(shortcut.app_id_matches_calculation());
+    }
+
+    #[test]
+    fn renamed_shortcut_is_stale() {
+        let mut renamed = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        renamed.app_name = "Celeste Deluxe";
+        let untouched = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "");
+        let shortcuts = vec![renamed, untouched];
+        let stale = find_stale_app_ids(&shortcuts);
+        assert_eq!(1, stale.len());
+        assert_eq!("Celeste Deluxe", stale[0].app_name);
+    }
+
+    #[test]
+    fn finds_app_id_collisions() {
+        let celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        let spore = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "");
+        let mut copy = Shortcut::new("2", "Celeste copy", "celeste.exe", "", "", "", "");
+        copy.app_id = celeste.app_id;
+        let shortcuts = vec![celeste, spore, copy];
+        let collisions = find_app_id_collisions(&shortcuts);
+        assert_eq!(1, collisions.len());
+        let (app_id, colliding) = &collisions[0];
+        assert_eq!(shortcuts[0].app_id, *app_id);
+        assert_eq!(2, colliding.len());
+        assert_eq!("Celeste copy", colliding[1].app_name);
+    }
+
+    #[test]
+    fn no_collisions_in_test_file() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(content.as_slice()).unwrap();
+        assert!(find_app_id_collisions(&shortcuts).is_empty());
+    }
+
+    #[test]
+    fn bytes_variant_matches_str_variant() {
+        let exe = "\"C:\\MySmallPrograms\\epic_launcher.exe\"";
+        assert_eq!(
+            calculate_app_id(exe, "Celeste"),
+            calculate_app_id_bytes(exe.as_bytes(), b"Celeste")
+        );
+    }
+
+    #[test]
+    fn bytes_variant_accepts_non_utf8() {
+        let exe: &[u8] = b"/games/\xff\xfe/run.sh";
+        assert_ne!(0, calculate_app_id_bytes(exe, b"Game") & 0x80000000);
+    }
+
+    #[test]
+    fn signed_conversion_round_trips() {
+        assert_eq!(-1929900147, as_signed(2365067149));
+        assert_eq!(2365067149, from_signed(-1929900147));
+        assert_eq!(1234, from_signed(as_signed(1234)));
+    }
+
+    #[test]
+    fn game_id_from_app_id() {
+        assert_eq!(10157886057832513536, calculate_game_id(2365067149));
+    }
+
+    #[test]
+    fn recalculate_reports_changed_app_ids() {
+        let mut renamed = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+        let old_app_id = renamed.app_id;
+        renamed.app_name = "Celeste Deluxe".to_string();
+        let untouched = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "").to_owned();
+        let mut shortcuts = vec![renamed, untouched];
+
+        let changes = recalculate_app_ids(&mut shortcuts);
+
+        let new_app_id = calculate_app_id("celeste.exe", "Celeste Deluxe");
+        assert_eq!(
+            vec![AppIdChange {
+                index: 0,
+                old_app_id,
+                new_app_id
+            }],
+            changes
+        );
+        assert_eq!(new_app_id, shortcuts[0].app_id);
+    }
+}
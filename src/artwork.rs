@@ -0,0 +1,165 @@
+//! Custom artwork for shortcuts, stored in the `userdata/<user id>/config/grid` folder.
+//!
+//! Steam finds the images for a shortcut by its app id, so each kind of image
+//! has its own file name, e.g. `<app id>p.png` for the grid and `<app id>_hero.jpg` for the hero.
+
+use std::path::{Path, PathBuf};
+
+use crate::shortcut::Shortcut;
+
+/// The image file extensions steam accepts for artwork.
+pub const ARTWORK_EXTENSIONS: [&str; 2] = ["png", "jpg"];
+
+/// The kinds of artwork steam shows for a shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtworkKind {
+    /// The portrait image shown in the library grid
+    Grid,
+    /// The wide image shown for recently played games
+    WideGrid,
+    /// The big banner at the top of the game page
+    Hero,
+    /// The logo shown on top of the hero
+    Logo,
+    /// The small icon shown in the library list
+    Icon,
+}
+
+impl ArtworkKind {
+    /// All kinds of artwork.
+    pub const ALL: [ArtworkKind; 5] = [
+        ArtworkKind::Grid,
+        ArtworkKind::WideGrid,
+        ArtworkKind::Hero,
+        ArtworkKind::Logo,
+        ArtworkKind::Icon,
+    ];
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            ArtworkKind::Grid => "p",
+            ArtworkKind::WideGrid => "",
+            ArtworkKind::Hero => "_hero",
+            ArtworkKind::Logo => "_logo",
+            ArtworkKind::Icon => "_icon",
+        }
+    }
+}
+
+/// The grid folder of a user in a steam installation.
+pub fn grid_dir(steam_root: &Path, user_id: &str) -> PathBuf {
+    steam_root
+        .join("userdata")
+        .join(user_id)
+        .join("config")
+        .join("grid")
+}
+
+/// The file name steam looks for, for the given app id, kind of artwork and extension.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::artwork::{artwork_file_name, ArtworkKind};
+///
+/// assert_eq!("2365067149p.png", artwork_file_name(2365067149, ArtworkKind::Grid, "png"));
+/// assert_eq!("2365067149_hero.jpg", artwork_file_name(2365067149, ArtworkKind::Hero, "jpg"));
+/// ```
+pub fn artwork_file_name(app_id: u32, kind: ArtworkKind, extension: &str) -> String {
+    format!("{}{}.{}", app_id, kind.suffix(), extension)
+}
+
+/// Find the existing image of the given kind for the shortcut.
+pub fn get_artwork(grid_dir: &Path, shortcut: &Shortcut, kind: ArtworkKind) -> Option<PathBuf> {
+    existing_files(grid_dir, shortcut.app_id, kind)
+        .into_iter()
+        .next()
+}
+
+/// Copy an image into the grid folder as the artwork of the given kind for the shortcut.
+///
+/// Existing images of the same kind (also with other extensions) are replaced.
+/// The image must be a png or a jpg, the path of the copied file is returned.
+pub fn set_artwork(
+    grid_dir: &Path,
+    shortcut: &Shortcut,
+    kind: ArtworkKind,
+    image: &Path,
+) -> std::io::Result<PathBuf> {
+    let extension = image
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .map(|e| if e == "jpeg" { "jpg".to_string() } else { e })
+        .filter(|e| ARTWORK_EXTENSIONS.contains(&e.as_str()))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is not a png or jpg image", image.display()),
+            )
+        })?;
+    std::fs::create_dir_all(grid_dir)?;
+    remove_artwork(grid_dir, shortcut, kind)?;
+    let target = grid_dir.join(artwork_file_name(shortcut.app_id, kind, &extension));
+    std::fs::copy(image, &target)?;
+    Ok(target)
+}
+
+/// Remove the images of the given kind for the shortcut, returning if any were removed.
+pub fn remove_artwork(
+    grid_dir: &Path,
+    shortcut: &Shortcut,
+    kind: ArtworkKind,
+) -> std::io::Result<bool> {
+    let existing = existing_files(grid_dir, shortcut.app_id, kind);
+    for file in &existing {
+        std::fs::remove_file(file)?;
+    }
+    Ok(!existing.is_empty())
+}
+
+fn existing_files(grid_dir: &Path, app_id: u32, kind: ArtworkKind) -> Vec<PathBuf> {
+    ARTWORK_EXTENSIONS
+        .iter()
+        .map(|extension| grid_dir.join(artwork_file_name(app_id, kind, extension)))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn set_replace_remove() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let grid = dir.join("grid");
+        let png = dir.join("hero.png");
+        let jpg = dir.join("hero.JPEG");
+        std::fs::write(&png, b"png").unwrap();
+        std::fs::write(&jpg, b"jpg").unwrap();
+        let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+
+        let first = set_artwork(&grid, &shortcut, ArtworkKind::Hero, &png).unwrap();
+        let second = set_artwork(&grid, &shortcut, ArtworkKind::Hero, &jpg).unwrap();
+        let found = get_artwork(&grid, &shortcut, ArtworkKind::Hero);
+        let first_exists = first.exists();
+        let logo = get_artwork(&grid, &shortcut, ArtworkKind::Logo);
+        let removed = remove_artwork(&grid, &shortcut, ArtworkKind::Hero).unwrap();
+        let after_remove = get_artwork(&grid, &shortcut, ArtworkKind::Hero);
+        let not_image = set_artwork(&grid, &shortcut, ArtworkKind::Hero, dir);
+
+        assert_eq!(
+            format!("{}_hero.jpg", shortcut.app_id),
+            second.file_name().unwrap().to_str().unwrap()
+        );
+        assert!(!first_exists);
+        assert_eq!(Some(second), found);
+        assert_eq!(None, logo);
+        assert!(removed);
+        assert_eq!(None, after_remove);
+        assert!(not_image.is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Reading and writing shortcuts.vdf with tokio (requires the `tokio` feature).
+//!
+//! These do the file access on tokio's blocking pool, so they can be awaited from async code
+//! without blocking the runtime. Parsing and serializing happen in the calling task.
+
+use std::path::Path;
+
+use crate::shortcut::ShortcutOwned;
+use crate::{parse_shortcuts, shortcuts_to_bytes, ShortcutsFileError};
+
+/// Read and parse the shortcuts file at the given path.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::async_io::{parse_shortcuts_from_path_async, save_shortcuts_async};
+///
+/// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut shortcuts = parse_shortcuts_from_path_async("path/to/shortcuts.vdf").await?;
+///     shortcuts.retain(|shortcut| !shortcut.is_hidden);
+///     save_shortcuts_async("path/to/shortcuts.vdf", &shortcuts).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn parse_shortcuts_from_path_async<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<ShortcutOwned>, ShortcutsFileError> {
+    let content = tokio::fs::read(path).await?;
+    let shortcuts = parse_shortcuts(content.as_slice()).map_err(ShortcutsFileError::Parse)?;
+    Ok(shortcuts
+        .iter()
+        .map(|shortcut| shortcut.to_owned())
+        .collect())
+}
+
+/// Write shortcuts to the given path.
+///
+/// The parent folder is created if it does not exist yet.
+pub async fn save_shortcuts_async<P: AsRef<Path>>(
+    path: P,
+    shortcuts: &[ShortcutOwned],
+) -> Result<(), ShortcutsFileError> {
+    let path = path.as_ref();
+    let borrowed = shortcuts.iter().map(|s| s.borrow()).collect();
+    let bytes = shortcuts_to_bytes(&borrowed);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+    tokio::fs::write(path, bytes).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[tokio::test]
+    async fn read_and_save() {
+        let shortcuts = parse_shortcuts_from_path_async("src/testdata/shortcuts.vdf")
+            .await
+            .unwrap();
+        assert_eq!("Celeste", shortcuts[0].app_name);
+
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("config").join("shortcuts.vdf");
+        save_shortcuts_async(&path, &shortcuts).await.unwrap();
+        let saved = parse_shortcuts_from_path_async(&path).await;
+        assert_eq!(shortcuts, saved.unwrap());
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_io_error() {
+        let res = parse_shortcuts_from_path_async("src/testdata/missing.vdf").await;
+        assert!(matches!(res, Err(ShortcutsFileError::Io(_))));
+    }
+}
@@ -0,0 +1,127 @@
+//! Timestamped backups of a shortcuts file.
+//!
+//! Backups of `shortcuts.vdf` are stored next to it in the folder `shortcuts.vdf.backups/`,
+//! named after the time (in milliseconds since the unix epoch) they were taken.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A backup of a shortcuts file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// When the backup was taken, in milliseconds since the unix epoch
+    pub timestamp: u64,
+    /// The path of the backup file
+    pub path: PathBuf,
+}
+
+/// The folder the backups of a file are stored in.
+pub fn backup_dir(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".backups");
+    path.with_file_name(name)
+}
+
+/// Copy the file to a new timestamped backup.
+pub fn backup(path: &Path) -> std::io::Result<Backup> {
+    let dir = backup_dir(path);
+    std::fs::create_dir_all(&dir)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let latest = list(path)?.last().map(|b| b.timestamp).unwrap_or_default();
+    let timestamp = now.max(latest + 1);
+    let backup_path = dir.join(format!("{}.vdf", timestamp));
+    std::fs::copy(path, &backup_path)?;
+    Ok(Backup {
+        timestamp,
+        path: backup_path,
+    })
+}
+
+/// The backups of the file, oldest first.
+pub fn list(path: &Path) -> std::io::Result<Vec<Backup>> {
+    let dir = backup_dir(path);
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut backups = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let backup_path = entry?.path();
+        let timestamp = backup_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".vdf"))
+            .and_then(|name| name.parse().ok());
+        if let Some(timestamp) = timestamp {
+            backups.push(Backup {
+                timestamp,
+                path: backup_path,
+            });
+        }
+    }
+    backups.sort_by_key(|b| b.timestamp);
+    Ok(backups)
+}
+
+/// Delete all but the newest `keep_n` backups of the file, returning the deleted backups.
+pub fn prune(path: &Path, keep_n: usize) -> std::io::Result<Vec<Backup>> {
+    let backups = list(path)?;
+    let remove_count = backups.len().saturating_sub(keep_n);
+    let removed: Vec<Backup> = backups.into_iter().take(remove_count).collect();
+    for backup in &removed {
+        std::fs::remove_file(&backup.path)?;
+    }
+    Ok(removed)
+}
+
+/// Overwrite the file with the backup taken at the given timestamp.
+pub fn restore(path: &Path, timestamp: u64) -> std::io::Result<()> {
+    let backup = list(path)?
+        .into_iter()
+        .find(|b| b.timestamp == timestamp)
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No backup with timestamp {}", timestamp),
+            )
+        })?;
+    std::fs::copy(backup.path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn backup_prune_restore() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("shortcuts.vdf");
+
+        std::fs::write(&path, b"first").unwrap();
+        let first = backup(&path).unwrap();
+        std::fs::write(&path, b"second").unwrap();
+        backup(&path).unwrap();
+        std::fs::write(&path, b"third").unwrap();
+        let third = backup(&path).unwrap();
+        assert_eq!(dir.join("shortcuts.vdf.backups"), backup_dir(&path));
+        assert_eq!(3, list(&path).unwrap().len());
+
+        restore(&path, first.timestamp).unwrap();
+        let restored = std::fs::read(&path).unwrap();
+
+        let removed = prune(&path, 1).unwrap();
+        let remaining = list(&path).unwrap();
+        let missing = restore(&path, first.timestamp);
+
+        assert_eq!(b"first".to_vec(), restored);
+        assert_eq!(2, removed.len());
+        assert_eq!(vec![third], remaining);
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! Command line tool for editing a shortcuts.vdf file (requires the `cli` feature).
+
+use std::path::Path;
+
+use steam_shortcuts_util::app_id_generator::calculate_app_id;
+use steam_shortcuts_util::shortcut::{ShortcutOwned, Tags};
+use steam_shortcuts_util::{from_json, to_json, Shortcut, ShortcutsFile};
+
+const USAGE: &str = "Usage: steam-shortcuts <command> <shortcuts.vdf> [arguments]
+
+Commands:
+  list                               List the shortcuts
+  add --name <name> --exe <exe> [--start-dir <dir>] [--icon <icon>]
+      [--launch-options <options>] [--tag <tag>]...
+                                     Add a shortcut
+  remove <app id or name>            Remove a shortcut
+  set <app id or name> <field> <value>
+                                     Change a field of a shortcut, one of: app_name, exe,
+                                     start_dir, icon, launch_options, hidden, tags, app_id
+                                     (tags are separated by commas, the app id is
+                                     recalculated when app_name or exe changes)
+  export-json [file]                 Write the shortcuts as JSON to the file or stdout
+  import-json <file>                 Replace the shortcuts with the ones in a JSON file
+";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => print!("{}", output),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Run a command, returning what to print.
+fn run(args: &[String]) -> Result<String, String> {
+    let (command, path, rest) = match args {
+        [command, path, rest @ ..] => (command.as_str(), Path::new(path), rest),
+        _ => return Err(USAGE.to_string()),
+    };
+    match command {
+        "list" => Ok(list(&load(path)?)),
+        "export-json" => {
+            let file = load(path)?;
+            let borrowed: Vec<Shortcut> = file.shortcuts().iter().map(|s| s.borrow()).collect();
+            let json = to_json(&borrowed);
+            match rest {
+                [] => Ok(format!("{}\n", json)),
+                [out] => {
+                    std::fs::write(out, json)
+                        .map_err(|e| format!("Could not write {}: {}", out, e))?;
+                    Ok(String::new())
+                }
+                _ => Err(USAGE.to_string()),
+            }
+        }
+        "import-json" => {
+            let json_path = match rest {
+                [json_path] => json_path,
+                _ => return Err(USAGE.to_string()),
+            };
+            let json = std::fs::read_to_string(json_path)
+                .map_err(|e| format!("Could not read {}: {}", json_path, e))?;
+            let mut file = load_or_new(path)?;
+            *file.shortcuts_mut() = from_json(&json)?;
+            save(&mut file)?;
+            Ok(format!("Imported {} shortcuts\n", file.shortcuts().len()))
+        }
+        "add" => {
+            let shortcut = parse_add(rest)?;
+            let mut file = load_or_new(path)?;
+            let output = format!("Added {} ({})\n", shortcut.app_name, shortcut.app_id);
+            file.shortcuts_mut().push(shortcut);
+            save(&mut file)?;
+            Ok(output)
+        }
+        "remove" => {
+            let query = match rest {
+                [query] => query,
+                _ => return Err(USAGE.to_string()),
+            };
+            let mut file = load(path)?;
+            let index = find(&file, query)?;
+            let removed = file.shortcuts_mut().remove(index);
+            save(&mut file)?;
+            Ok(format!(
+                "Removed {} ({})\n",
+                removed.app_name, removed.app_id
+            ))
+        }
+        "set" => {
+            let (query, field, value) = match rest {
+                [query, field, value] => (query, field.as_str(), value),
+                _ => return Err(USAGE.to_string()),
+            };
+            let mut file = load(path)?;
+            let index = find(&file, query)?;
+            let shortcut = &mut file.shortcuts_mut()[index];
+            set_field(shortcut, field, value)?;
+            let output = format!("Changed {} of {}\n", field, shortcut.app_name);
+            save(&mut file)?;
+            Ok(output)
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn load(path: &Path) -> Result<ShortcutsFile, String> {
+    ShortcutsFile::load(path).map_err(|e| e.to_string())
+}
+
+fn load_or_new(path: &Path) -> Result<ShortcutsFile, String> {
+    if path.exists() {
+        load(path)
+    } else {
+        Ok(ShortcutsFile::new(path))
+    }
+}
+
+fn save(file: &mut ShortcutsFile) -> Result<(), String> {
+    for (index, shortcut) in file.shortcuts_mut().iter_mut().enumerate() {
+        shortcut.order = index.to_string();
+    }
+    file.save().map_err(|e| e.to_string())
+}
+
+fn list(file: &ShortcutsFile) -> String {
+    file.shortcuts()
+        .iter()
+        .map(|shortcut| {
+            format!(
+                "{}\t{}\t{} {}\n",
+                shortcut.app_id, shortcut.app_name, shortcut.exe, shortcut.launch_options
+            )
+        })
+        .collect()
+}
+
+/// Find a shortcut by app id, or else by name (ignoring case).
+fn find(file: &ShortcutsFile, query: &str) -> Result<usize, String> {
+    let shortcuts = file.shortcuts();
+    let by_id = query
+        .parse::<u32>()
+        .ok()
+        .and_then(|app_id| shortcuts.iter().position(|s| s.app_id == app_id));
+    by_id
+        .or_else(|| {
+            shortcuts
+                .iter()
+                .position(|s| s.app_name.eq_ignore_ascii_case(query))
+        })
+        .ok_or_else(|| format!("No shortcut found for '{}'", query))
+}
+
+fn parse_add(args: &[String]) -> Result<ShortcutOwned, String> {
+    let mut name = None;
+    let mut exe = None;
+    let mut start_dir = "";
+    let mut icon = "";
+    let mut launch_options = "";
+    let mut tags = Tags::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?;
+        match flag.as_str() {
+            "--name" => name = Some(value.as_str()),
+            "--exe" => exe = Some(value.as_str()),
+            "--start-dir" => start_dir = value,
+            "--icon" => icon = value,
+            "--launch-options" => launch_options = value,
+            "--tag" => tags.push(value.as_str()),
+            _ => return Err(format!("Unknown option {}\n\n{}", flag, USAGE)),
+        }
+    }
+    let name = name.ok_or("Missing --name")?;
+    let exe = exe.ok_or("Missing --exe")?;
+    let mut shortcut = Shortcut::new("0", name, exe, start_dir, icon, "", launch_options);
+    shortcut.tags = tags;
+    Ok(shortcut.to_owned())
+}
+
+/// Change a field, steam finds a shortcut by the app id of its exe and name so that is
+/// recalculated when they change.
+fn set_field(shortcut: &mut ShortcutOwned, field: &str, value: &str) -> Result<(), String> {
+    match field {
+        "app_name" => {
+            shortcut.app_name = value.to_string();
+            shortcut.app_id = calculate_app_id(&shortcut.exe, &shortcut.app_name);
+        }
+        "exe" => {
+            shortcut.exe = value.to_string();
+            shortcut.app_id = calculate_app_id(&shortcut.exe, &shortcut.app_name);
+        }
+        "app_id" => {
+            shortcut.app_id = value
+                .parse()
+                .map_err(|_| format!("app_id must be a number, not '{}'", value))?
+        }
+        "start_dir" => shortcut.start_dir = value.to_string(),
+        "icon" => shortcut.icon = value.to_string(),
+        "launch_options" => shortcut.launch_options = value.to_string(),
+        "hidden" => {
+            shortcut.is_hidden = value
+                .parse()
+                .map_err(|_| format!("hidden must be true or false, not '{}'", value))?
+        }
+        "tags" => {
+            shortcut.tags = value
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+        _ => return Err(format!("Unknown field '{}'\n\n{}", field, USAGE)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "../test_util.rs"]
+mod test_util;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use test_util::temp_dir;
+
+    fn run_with(args: &[&str]) -> Result<String, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        run(&args)
+    }
+
+    #[test]
+    fn add_set_export_remove() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let vdf = dir.join("shortcuts.vdf");
+        let json = dir.join("shortcuts.json");
+        let vdf = vdf.to_str().unwrap();
+        let json = json.to_str().unwrap();
+
+        run_with(&[
+            "add", vdf, "--name", "Celeste", "--exe", "celeste", "--tag", "Indie",
+        ])
+        .unwrap();
+        run_with(&["set", vdf, "celeste", "launch_options", "-windowed"]).unwrap();
+        assert!(run_with(&["list", vdf])
+            .unwrap()
+            .contains("Celeste\tceleste -windowed"));
+
+        run_with(&["export-json", vdf, json]).unwrap();
+        run_with(&["remove", vdf, "Celeste"]).unwrap();
+        assert_eq!("", run_with(&["list", vdf]).unwrap());
+        run_with(&["import-json", vdf, json]).unwrap();
+        let listed = run_with(&["list", vdf]);
+
+        assert!(listed.unwrap().contains("Celeste"));
+    }
+
+    #[test]
+    fn set_recalculates_app_id() {
+        let mut shortcut = Shortcut::new("0", "Celeste", "celeste", "", "", "", "").to_owned();
+        set_field(&mut shortcut, "exe", "\"celeste.exe\"").unwrap();
+        assert_eq!(
+            calculate_app_id("\"celeste.exe\"", "Celeste"),
+            shortcut.app_id
+        );
+        set_field(&mut shortcut, "app_name", "Celeste 64").unwrap();
+        assert_eq!(
+            calculate_app_id("\"celeste.exe\"", "Celeste 64"),
+            shortcut.app_id
+        );
+        set_field(&mut shortcut, "launch_options", "-windowed").unwrap();
+        set_field(&mut shortcut, "app_id", "3000000000").unwrap();
+        set_field(&mut shortcut, "icon", "celeste.ico").unwrap();
+        assert_eq!(3_000_000_000, shortcut.app_id);
+        assert!(set_field(&mut shortcut, "app_id", "-1").is_err());
+    }
+
+    #[test]
+    fn bad_arguments() {
+        assert!(run_with(&[]).is_err());
+        assert!(run_with(&["add", "missing.vdf", "--name", "Celeste"]).is_err());
+        assert!(run_with(&["unknown", "missing.vdf"]).is_err());
+    }
+}
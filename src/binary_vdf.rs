@@ -0,0 +1,890 @@
+//! A serde data format for Valve's binary KeyValues (requires the `serde` feature).
+//!
+//! This is the format of shortcuts.vdf, and of other binary steam files like appinfo entries.
+//! Any struct or map can be written with [to_bytes] and read with [from_bytes]:
+//!
+//! * structs and maps are written as objects, with their fields as keys
+//! * lists are written as objects with the keys `"0"`, `"1"`, ...
+//! * text, chars and unit enum variants are written as strings
+//! * integers up to 32 bits and bools are written as 32 bit integers, larger integers as 64 bit
+//! * floats are written as 32 bit floats
+//! * `None` fields are left out
+//!
+//! The root value must be a struct or map, as binary KeyValues files are a list of keys.
+//!
+//! ### Examples
+//! ```
+//! use steam_shortcuts_util::binary_vdf;
+//! use steam_shortcuts_util::shortcut::ShortcutOwned;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct ShortcutsVdf {
+//!     shortcuts: Vec<ShortcutOwned>,
+//! }
+//!
+//! let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+//! let file: ShortcutsVdf = binary_vdf::from_bytes(&content).unwrap();
+//! assert_eq!("Celeste", file.shortcuts[0].app_name);
+//! let bytes = binary_vdf::to_bytes(&file).unwrap();
+//! ```
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+
+const OBJECT: u8 = 0x00;
+const STRING: u8 = 0x01;
+const INT32: u8 = 0x02;
+const FLOAT32: u8 = 0x03;
+const UINT64: u8 = 0x07;
+const END: u8 = 0x08;
+
+/// Error from reading or writing binary KeyValues.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// Serialize a struct or map to binary KeyValues.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut output = vec![];
+    value.serialize(ValueSerializer {
+        output: &mut output,
+        key: None,
+    })?;
+    Ok(output)
+}
+
+/// Deserialize a struct or map from binary KeyValues.
+///
+/// Strings are borrowed from the input where the type allows it.
+/// Returns an error if there are bytes left after the root object.
+pub fn from_bytes<'de, T: de::Deserialize<'de>>(input: &'de [u8]) -> Result<T, Error> {
+    let mut deserializer = Deserializer { input };
+    let value = T::deserialize(ValueDeserializer {
+        de: &mut deserializer,
+        kind: OBJECT,
+    })?;
+    if deserializer.input.is_empty() {
+        Ok(value)
+    } else {
+        Err(Error(format!(
+            "{} unexpected bytes after the root object",
+            deserializer.input.len()
+        )))
+    }
+}
+
+/// Serializes one value, written under `key` (or as the root object when there is no key).
+struct ValueSerializer<'o> {
+    output: &'o mut Vec<u8>,
+    key: Option<&'o str>,
+}
+
+impl<'o> ValueSerializer<'o> {
+    fn header(&mut self, kind: u8) -> Result<(), Error> {
+        let key = self
+            .key
+            .ok_or_else(|| Error("The root value must be a struct or map".to_string()))?;
+        if key.as_bytes().contains(&0) {
+            return Err(Error(format!("Key '{}' contains a null byte", key)));
+        }
+        self.output.push(kind);
+        self.output.extend_from_slice(key.as_bytes());
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn int32(mut self, value: i32) -> Result<(), Error> {
+        self.header(INT32)?;
+        self.output.extend_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    fn object(mut self) -> Result<Compound<'o>, Error> {
+        if self.key.is_some() {
+            self.header(OBJECT)?;
+        }
+        Ok(Compound {
+            output: self.output,
+            next_key: None,
+            index: 0,
+        })
+    }
+}
+
+impl<'o> ser::Serializer for ValueSerializer<'o> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'o>;
+    type SerializeTuple = Compound<'o>;
+    type SerializeTupleStruct = Compound<'o>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Compound<'o>;
+    type SerializeStruct = Compound<'o>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.int32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.int32(v as i32)
+    }
+
+    fn serialize_u64(mut self, v: u64) -> Result<(), Error> {
+        self.header(UINT64)?;
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(mut self, v: f32) -> Result<(), Error> {
+        self.header(FLOAT32)?;
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.serialize_f32(v as f32)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(mut self, v: &str) -> Result<(), Error> {
+        if v.as_bytes().contains(&0) {
+            return Err(Error(format!("Text '{}' contains a null byte", v)));
+        }
+        self.header(STRING)?;
+        self.output.extend_from_slice(v.as_bytes());
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Err(Error("Bytes can not be written as KeyValues".to_string()))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let mut object = self.object()?;
+        ser::SerializeStruct::serialize_field(&mut object, variant, value)?;
+        ser::SerializeStruct::end(object)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'o>, Error> {
+        self.object()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'o>, Error> {
+        self.object()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'o>, Error> {
+        self.object()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error(format!("Variant '{}' can not be written", variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'o>, Error> {
+        self.object()
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'o>, Error> {
+        self.object()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error(format!("Variant '{}' can not be written", variant)))
+    }
+}
+
+/// Serializes the entries of an object, ending it when done.
+struct Compound<'o> {
+    output: &'o mut Vec<u8>,
+    next_key: Option<String>,
+    index: usize,
+}
+
+impl<'o> Compound<'o> {
+    fn entry<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        value.serialize(ValueSerializer {
+            output: self.output,
+            key: Some(key),
+        })
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self.index.to_string();
+        self.index += 1;
+        self.entry(&key, value)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        self.output.push(END);
+        Ok(())
+    }
+}
+
+impl<'o> ser::SerializeSeq for Compound<'o> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'o> ser::SerializeTuple for Compound<'o> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'o> ser::SerializeTupleStruct for Compound<'o> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'o> ser::SerializeMap for Compound<'o> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error("Map value without a key".to_string()))?;
+        self.entry(&key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'o> ser::SerializeStruct for Compound<'o> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entry(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+/// Turns map keys into the text they are written as.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported(kind: &str) -> Error {
+        Error(format!("A {} can not be used as a key", kind))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, Error> {
+        Ok((v as u8).to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(Self::unsupported("float"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(Self::unsupported("float"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(Self::unsupported("byte list"))
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(Self::unsupported("None"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(Self::unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String, Error> {
+        Err(Self::unsupported(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(Self::unsupported(variant))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Self::unsupported("list"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Self::unsupported("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Self::unsupported(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Self::unsupported(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Self::unsupported("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Self::unsupported(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Self::unsupported(variant))
+    }
+}
+
+/// The bytes that are left to read.
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if self.input.len() < len {
+            return Err(Error("Unexpected end of input".to_string()));
+        }
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn c_str(&mut self) -> Result<&'de str, Error> {
+        let len = self
+            .input
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| Error("Unterminated string".to_string()))?;
+        let bytes = self.take(len)?;
+        self.take(1)?;
+        std::str::from_utf8(bytes).map_err(|e| Error(format!("Invalid UTF-8 in string: {}", e)))
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut res = [0; N];
+        res.copy_from_slice(self.take(N)?);
+        Ok(res)
+    }
+
+    /// Read the type and key of the next entry of an object, `None` at the end of the object.
+    fn next_entry(&mut self) -> Result<Option<(u8, &'de str)>, Error> {
+        match self.byte()? {
+            END => Ok(None),
+            kind => Ok(Some((kind, self.c_str()?))),
+        }
+    }
+
+    fn skip(&mut self, kind: u8) -> Result<(), Error> {
+        match kind {
+            OBJECT => {
+                while let Some((kind, _)) = self.next_entry()? {
+                    self.skip(kind)?;
+                }
+            }
+            STRING => {
+                self.c_str()?;
+            }
+            INT32 | FLOAT32 => {
+                self.take(4)?;
+            }
+            UINT64 => {
+                self.take(8)?;
+            }
+            kind => return Err(unknown_type(kind)),
+        }
+        Ok(())
+    }
+}
+
+fn unknown_type(kind: u8) -> Error {
+    Error(format!("Unknown value type 0x{:02x}", kind))
+}
+
+/// Deserializes one value, whose type byte and key were already read.
+struct ValueDeserializer<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    kind: u8,
+}
+
+impl<'a, 'de> ValueDeserializer<'a, 'de> {
+    fn expected(&self, expected: &str) -> Error {
+        Error(format!(
+            "Expected {} but found value type 0x{:02x}",
+            expected, self.kind
+        ))
+    }
+
+    fn int32(self) -> Result<i32, Error> {
+        match self.kind {
+            INT32 => Ok(i32::from_le_bytes(self.de.array()?)),
+            _ => Err(self.expected("a 32 bit integer")),
+        }
+    }
+}
+
+impl<'a, 'de> de::Deserializer<'de> for ValueDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.kind {
+            OBJECT => visitor.visit_map(ObjectAccess {
+                de: self.de,
+                kind: None,
+            }),
+            STRING => visitor.visit_borrowed_str(self.de.c_str()?),
+            INT32 => visitor.visit_u32(u32::from_le_bytes(self.de.array()?)),
+            FLOAT32 => visitor.visit_f32(f32::from_le_bytes(self.de.array()?)),
+            UINT64 => visitor.visit_u64(u64::from_le_bytes(self.de.array()?)),
+            kind => Err(unknown_type(kind)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.int32()? != 0)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.int32()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.int32()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_i32(self.int32()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.kind {
+            UINT64 => visitor.visit_i64(i64::from_le_bytes(self.de.array()?)),
+            _ => visitor.visit_i32(self.int32()?),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.kind {
+            OBJECT => visitor.visit_seq(ObjectAccess {
+                de: self.de,
+                kind: None,
+            }),
+            _ => Err(self.expected("an object")),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.kind {
+            STRING => visitor.visit_enum(self.de.c_str()?.into_deserializer()),
+            _ => Err(self.expected("a string")),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.de.skip(self.kind)?;
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        u8 u16 u32 u64 u128 i128 f32 f64 char str string bytes byte_buf unit unit_struct
+        map struct identifier
+    }
+}
+
+/// Reads the entries of an object, as a map or as a list (ignoring the keys).
+struct ObjectAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    kind: Option<u8>,
+}
+
+impl<'a, 'de> MapAccess<'de> for ObjectAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.de.next_entry()? {
+            None => Ok(None),
+            Some((kind, key)) => {
+                self.kind = Some(kind);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let kind = self
+            .kind
+            .take()
+            .ok_or_else(|| Error("Value read before its key".to_string()))?;
+        seed.deserialize(ValueDeserializer { de: self.de, kind })
+    }
+}
+
+impl<'a, 'de> SeqAccess<'de> for ObjectAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.de.next_entry()? {
+            None => Ok(None),
+            Some((kind, _)) => seed
+                .deserialize(ValueDeserializer { de: self.de, kind })
+                .map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::shortcut::{Shortcut, ShortcutOwned};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct ShortcutsVdf<'a> {
+        #[serde(borrow)]
+        shortcuts: Vec<Shortcut<'a>>,
+    }
+
+    #[test]
+    fn read_shortcuts_file() {
+        let content = std::fs::read("src/testdata/shortcuts2.vdf").unwrap();
+        let file: ShortcutsVdf = from_bytes(&content).unwrap();
+        let parsed = crate::parse_shortcuts(&content).unwrap();
+        assert_eq!(parsed.len(), file.shortcuts.len());
+        for (parsed, read) in parsed.iter().zip(&file.shortcuts) {
+            assert_eq!(parsed.app_name, read.app_name);
+            assert_eq!(parsed.exe, read.exe);
+            assert_eq!(parsed.launch_options, read.launch_options);
+            assert_eq!(parsed.tags, read.tags);
+        }
+    }
+
+    #[test]
+    fn write_and_read_back() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let file: ShortcutsVdf = from_bytes(&content).unwrap();
+        let bytes = to_bytes(&file).unwrap();
+        let again: ShortcutsVdf = from_bytes(&bytes).unwrap();
+        for (a, b) in file.shortcuts.iter().zip(&again.shortcuts) {
+            assert!(a.semantically_eq(b));
+        }
+        let owned: BTreeMap<String, Vec<ShortcutOwned>> = from_bytes(&bytes).unwrap();
+        assert_eq!(file.shortcuts.len(), owned["shortcuts"].len());
+    }
+
+    #[test]
+    fn user_struct_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Settings {
+            name: String,
+            volume: f32,
+            size: u64,
+            offset: i32,
+            enabled: bool,
+            missing: Option<u32>,
+            list: Vec<String>,
+        }
+        let settings = Settings {
+            name: "Deck".to_string(),
+            volume: 0.5,
+            size: u64::MAX,
+            offset: -3,
+            enabled: true,
+            missing: None,
+            list: vec!["a".to_string(), "b".to_string()],
+        };
+        let bytes = to_bytes(&settings).unwrap();
+        assert_eq!(settings, from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        assert!(to_bytes(&5u32).is_err());
+        assert!(from_bytes::<BTreeMap<String, String>>(&[STRING, b'a', 0, b'b']).is_err());
+        assert!(from_bytes::<BTreeMap<String, String>>(&[0x09, b'a', 0, END]).is_err());
+        assert!(from_bytes::<BTreeMap<String, String>>(&[END, END]).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! The ways of reading bytes the parsers are made of.
+//!
+//! With the `nom` feature (on by default) these are nom parsers, as they have always been.
+//! Without it they are written by hand, so the crate can be built without parser dependencies.
+//! Both give the same results, only the errors differ.
+
+/// Ends a key or text
+pub(crate) const NULL: u8 = 0x00;
+/// Starts a text line or a tag
+pub(crate) const SOH: u8 = 0x01;
+/// Starts a number line
+pub(crate) const STX: u8 = 0x02;
+/// Ends a list of lines
+pub(crate) const BS: u8 = 0x08;
+
+/// The rest of the input and what was read, or an error.
+#[cfg(feature = "nom")]
+pub(crate) type PResult<'a, T> = nom::IResult<&'a [u8], T>;
+
+/// The rest of the input and what was read, or an error.
+#[cfg(not(feature = "nom"))]
+pub(crate) type PResult<'a, T> = Result<(&'a [u8], T), Error>;
+
+/// Error from reading bytes without nom.
+#[cfg(not(feature = "nom"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Error {
+    /// The number of bytes from where reading failed to the end of the input
+    remaining: usize,
+}
+
+#[cfg(not(feature = "nom"))]
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Parsing Error: unexpected bytes {} bytes before the end",
+            self.remaining
+        )
+    }
+}
+
+/// Fail at `i`, e.g. because its bytes are not valid UTF-8.
+#[cfg(feature = "nom")]
+pub(crate) fn fail<T>(i: &[u8]) -> PResult<'_, T> {
+    Err(nom::Err::Error(nom::error::Error::new(
+        i,
+        nom::error::ErrorKind::Verify,
+    )))
+}
+
+/// Fail at `i`, e.g. because its bytes are not valid UTF-8.
+#[cfg(not(feature = "nom"))]
+pub(crate) fn fail<T>(i: &[u8]) -> PResult<'_, T> {
+    Err(Error { remaining: i.len() })
+}
+
+/// Consume `expected`, which must be the start of `i`.
+#[cfg(feature = "nom")]
+pub(crate) fn tag<'a>(expected: &[u8], i: &'a [u8]) -> PResult<'a, ()> {
+    let (i, _) = nom::bytes::complete::tag(expected)(i)?;
+    Ok((i, ()))
+}
+
+/// Consume `expected`, which must be the start of `i`.
+#[cfg(not(feature = "nom"))]
+pub(crate) fn tag<'a>(expected: &[u8], i: &'a [u8]) -> PResult<'a, ()> {
+    match i.strip_prefix(expected) {
+        Some(rest) => Ok((rest, ())),
+        None => fail(i),
+    }
+}
+
+/// Take the first `count` bytes.
+#[cfg(feature = "nom")]
+pub(crate) fn take(count: usize, i: &[u8]) -> PResult<'_, &[u8]> {
+    nom::bytes::complete::take(count)(i)
+}
+
+/// Take the first `count` bytes.
+#[cfg(not(feature = "nom"))]
+pub(crate) fn take(count: usize, i: &[u8]) -> PResult<'_, &[u8]> {
+    if i.len() < count {
+        return fail(i);
+    }
+    let (taken, rest) = i.split_at(count);
+    Ok((rest, taken))
+}
+
+/// Take the bytes before the first `delimiter`, and consume the delimiter.
+pub(crate) fn take_through(delimiter: u8, i: &[u8]) -> PResult<'_, &[u8]> {
+    match memchr::memchr(delimiter, i) {
+        Some(end) => Ok((&i[end + 1..], &i[..end])),
+        None => fail(&i[i.len()..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn reads_bytes() {
+        assert_eq!(Ok((&b"cd"[..], ())), tag(b"ab", b"abcd"));
+        assert!(tag(b"ab", b"a").is_err());
+        assert_eq!(Ok((&b"c"[..], &b"ab"[..])), take(2, b"abc"));
+        assert!(take(4, b"abc").is_err());
+        assert_eq!(Ok((&b"c"[..], &b"ab"[..])), take_through(NULL, b"ab\0c"));
+        assert!(take_through(NULL, b"abc").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! C bindings for parsing and writing shortcuts (requires the `capi` feature).
+//!
+//! The header for these functions is `include/steam_shortcuts_util.h`. It is generated with
+//! [cbindgen](https://github.com/mozilla/cbindgen) from this module:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/steam_shortcuts_util.h
+//! ```
+//!
+//! Build a library to link against with
+//! `cargo rustc --release --features capi --crate-type cdylib` (or `staticlib`).
+//!
+//! Everything this library allocates must be freed with the matching `ssu_free_*` function.
+//! A panic never unwinds into C, every function catches it and returns its error value.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A shortcut, with strings as null terminated UTF-8.
+#[repr(C)]
+pub struct SsuShortcut {
+    pub app_id: u32,
+    pub app_name: *mut c_char,
+    pub exe: *mut c_char,
+    pub start_dir: *mut c_char,
+    pub icon: *mut c_char,
+    pub shortcut_path: *mut c_char,
+    pub launch_options: *mut c_char,
+    pub is_hidden: bool,
+    pub allow_desktop_config: bool,
+    pub allow_overlay: bool,
+    pub open_vr: u32,
+    pub dev_kit: u32,
+    pub dev_kit_game_id: *mut c_char,
+    pub dev_kit_override_app_id: u32,
+    pub last_play_time: u32,
+    pub flatpak_app_id: *mut c_char,
+    pub tags: *mut *mut c_char,
+    pub tags_len: usize,
+}
+
+/// A list of shortcuts, made by [ssu_parse_shortcuts].
+#[repr(C)]
+pub struct SsuShortcutList {
+    pub shortcuts: *mut SsuShortcut,
+    pub len: usize,
+}
+
+/// Parse the content of a shortcuts.vdf file.
+///
+/// Returns 0 and fills `out` on success, which must be freed with [ssu_free_shortcuts].
+/// Returns -1 if the content could not be parsed, an argument is null, or on a panic.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, and `out` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn ssu_parse_shortcuts(
+    data: *const u8,
+    len: usize,
+    out: *mut SsuShortcutList,
+) -> i32 {
+    catch_panic(-1, || {
+        if data.is_null() || out.is_null() {
+            return -1;
+        }
+        let bytes = std::slice::from_raw_parts(data, len);
+        let shortcuts = match crate::parse_shortcuts(bytes) {
+            Ok(shortcuts) => shortcuts,
+            Err(_) => return -1,
+        };
+        let mut list: Vec<SsuShortcut> = shortcuts.iter().map(to_c_shortcut).collect();
+        list.shrink_to_fit();
+        let len = list.len();
+        let shortcuts = Box::into_raw(list.into_boxed_slice()) as *mut SsuShortcut;
+        *out = SsuShortcutList { shortcuts, len };
+        0
+    })
+}
+
+/// Free a list made by [ssu_parse_shortcuts], and set it to an empty list.
+///
+/// # Safety
+///
+/// `list` must be null or a list filled by [ssu_parse_shortcuts] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ssu_free_shortcuts(list: *mut SsuShortcutList) {
+    catch_panic((), || {
+        if list.is_null() || (*list).shortcuts.is_null() {
+            return;
+        }
+        let slice = ptr::slice_from_raw_parts_mut((*list).shortcuts, (*list).len);
+        let shortcuts = Box::from_raw(slice);
+        for shortcut in shortcuts.iter() {
+            free_c_shortcut(shortcut);
+        }
+        (*list).shortcuts = ptr::null_mut();
+        (*list).len = 0;
+    })
+}
+
+/// Serialize shortcuts to the content of a shortcuts.vdf file.
+///
+/// Returns the bytes and writes their length to `out_len`, free them with [ssu_free_bytes].
+/// Returns null if a string is not valid UTF-8, an argument is null, or on a panic.
+///
+/// # Safety
+///
+/// `shortcuts` must point to `len` shortcuts whose strings are null terminated
+/// (null strings are read as empty), and `out_len` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn ssu_shortcuts_to_bytes(
+    shortcuts: *const SsuShortcut,
+    len: usize,
+    out_len: *mut usize,
+) -> *mut u8 {
+    catch_panic(ptr::null_mut(), || {
+        if out_len.is_null() || (shortcuts.is_null() && len > 0) {
+            return ptr::null_mut();
+        }
+        let c_shortcuts = if len == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(shortcuts, len)
+        };
+        let owned: Option<Vec<ShortcutOwned>> = c_shortcuts
+            .iter()
+            .enumerate()
+            .map(|(index, shortcut)| from_c_shortcut(index, shortcut))
+            .collect();
+        let owned = match owned {
+            Some(owned) => owned,
+            None => return ptr::null_mut(),
+        };
+        let borrowed: Vec<Shortcut> = owned.iter().map(|s| s.borrow()).collect();
+        let bytes = crate::shortcuts_to_bytes(&borrowed).into_boxed_slice();
+        *out_len = bytes.len();
+        Box::into_raw(bytes) as *mut u8
+    })
+}
+
+/// Free bytes made by [ssu_shortcuts_to_bytes].
+///
+/// # Safety
+///
+/// `bytes` must be null or returned by [ssu_shortcuts_to_bytes] with this `len`,
+/// and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ssu_free_bytes(bytes: *mut u8, len: usize) {
+    catch_panic((), || {
+        if !bytes.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(bytes, len)));
+        }
+    })
+}
+
+/// Calculate the app id steam uses for a shortcut with this exe and app name.
+///
+/// Returns 0 if an argument is null or not valid UTF-8, or on a panic.
+///
+/// # Safety
+///
+/// `exe` and `app_name` must be null or null terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn ssu_calculate_app_id(exe: *const c_char, app_name: *const c_char) -> u32 {
+    catch_panic(0, || {
+        if exe.is_null() || app_name.is_null() {
+            return 0;
+        }
+        let exe = CStr::from_ptr(exe).to_bytes();
+        let app_name = CStr::from_ptr(app_name).to_bytes();
+        crate::app_id_generator::calculate_app_id_bytes(exe, app_name)
+    })
+}
+
+/// Run `f`, and return `on_panic` if it panics instead of unwinding into C.
+fn catch_panic<T, F: FnOnce() -> T>(on_panic: T, f: F) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
+
+fn to_c_string(text: &str) -> *mut c_char {
+    // Strings in shortcuts.vdf are null terminated, so they can not contain a null byte
+    CString::new(text).unwrap_or_default().into_raw()
+}
+
+unsafe fn free_c_string(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+unsafe fn from_c_string<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return Some("");
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+fn to_c_shortcut(shortcut: &Shortcut) -> SsuShortcut {
+    let tags: Box<[*mut c_char]> = shortcut.tags.iter().map(|tag| to_c_string(tag)).collect();
+    let tags_len = tags.len();
+    SsuShortcut {
+        app_id: shortcut.app_id,
+        app_name: to_c_string(shortcut.app_name),
+        exe: to_c_string(shortcut.exe),
+        start_dir: to_c_string(shortcut.start_dir),
+        icon: to_c_string(shortcut.icon),
+        shortcut_path: to_c_string(shortcut.shortcut_path),
+        launch_options: to_c_string(shortcut.launch_options),
+        is_hidden: shortcut.is_hidden,
+        allow_desktop_config: shortcut.allow_desktop_config,
+        allow_overlay: shortcut.allow_overlay,
+        open_vr: shortcut.open_vr,
+        dev_kit: shortcut.dev_kit,
+        dev_kit_game_id: to_c_string(shortcut.dev_kit_game_id),
+        dev_kit_override_app_id: shortcut.dev_kit_overrite_app_id,
+        last_play_time: shortcut.last_play_time,
+        flatpak_app_id: to_c_string(shortcut.flatpak_app_id),
+        tags: Box::into_raw(tags) as *mut *mut c_char,
+        tags_len,
+    }
+}
+
+unsafe fn free_c_shortcut(shortcut: &SsuShortcut) {
+    free_c_string(shortcut.app_name);
+    free_c_string(shortcut.exe);
+    free_c_string(shortcut.start_dir);
+    free_c_string(shortcut.icon);
+    free_c_string(shortcut.shortcut_path);
+    free_c_string(shortcut.launch_options);
+    free_c_string(shortcut.dev_kit_game_id);
+    free_c_string(shortcut.flatpak_app_id);
+    if !shortcut.tags.is_null() {
+        let tags = Box::from_raw(ptr::slice_from_raw_parts_mut(
+            shortcut.tags,
+            shortcut.tags_len,
+        ));
+        for tag in tags.iter() {
+            free_c_string(*tag);
+        }
+    }
+}
+
+unsafe fn from_c_shortcut(index: usize, shortcut: &SsuShortcut) -> Option<ShortcutOwned> {
+    let order = index.to_string();
+    let mut res = Shortcut::new(
+        &order,
+        from_c_string(shortcut.app_name)?,
+        from_c_string(shortcut.exe)?,
+        from_c_string(shortcut.start_dir)?,
+        from_c_string(shortcut.icon)?,
+        from_c_string(shortcut.shortcut_path)?,
+        from_c_string(shortcut.launch_options)?,
+    );
+    res.app_id = shortcut.app_id;
+    res.is_hidden = shortcut.is_hidden;
+    res.allow_desktop_config = shortcut.allow_desktop_config;
+    res.allow_overlay = shortcut.allow_overlay;
+    res.open_vr = shortcut.open_vr;
+    res.dev_kit = shortcut.dev_kit;
+    res.dev_kit_game_id = from_c_string(shortcut.dev_kit_game_id)?;
+    res.dev_kit_overrite_app_id = shortcut.dev_kit_override_app_id;
+    res.last_play_time = shortcut.last_play_time;
+    res.flatpak_app_id = from_c_string(shortcut.flatpak_app_id)?;
+    if !shortcut.tags.is_null() {
+        for tag in std::slice::from_raw_parts(shortcut.tags, shortcut.tags_len) {
+            res.tags.push(from_c_string(*tag)?);
+        }
+    }
+    Some(res.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_and_write_through_c_api() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let mut list = SsuShortcutList {
+            shortcuts: ptr::null_mut(),
+            len: 0,
+        };
+        unsafe {
+            assert_eq!(
+                0,
+                ssu_parse_shortcuts(content.as_ptr(), content.len(), &mut list)
+            );
+            assert_eq!(42, list.len);
+            let first = &*list.shortcuts;
+            assert_eq!("Celeste", CStr::from_ptr(first.app_name).to_str().unwrap());
+            assert_eq!(3, first.tags_len);
+
+            let mut len = 0;
+            let bytes = ssu_shortcuts_to_bytes(list.shortcuts, list.len, &mut len);
+            let written = std::slice::from_raw_parts(bytes, len);
+            assert_eq!(
+                crate::parse_shortcuts(&content).unwrap(),
+                crate::parse_shortcuts(written).unwrap()
+            );
+            ssu_free_bytes(bytes, len);
+            ssu_free_shortcuts(&mut list);
+        }
+        assert!(list.shortcuts.is_null());
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut list = SsuShortcutList {
+            shortcuts: ptr::null_mut(),
+            len: 0,
+        };
+        unsafe {
+            assert_eq!(-1, ssu_parse_shortcuts(b"nope".as_ptr(), 4, &mut list));
+            assert_eq!(0, ssu_calculate_app_id(ptr::null(), ptr::null()));
+        }
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert_eq!(-1, catch_panic(-1, || panic!("in a C call")));
+        assert_eq!(0, catch_panic(-1, || 0));
+    }
+
+    #[test]
+    fn app_id_matches_rust() {
+        let exe = CString::new("\"C:\\Games\\Celeste.exe\"").unwrap();
+        let name = CString::new("Celeste").unwrap();
+        let id = unsafe { ssu_calculate_app_id(exe.as_ptr(), name.as_ptr()) };
+        assert_eq!(
+            crate::app_id_generator::calculate_app_id("\"C:\\Games\\Celeste.exe\"", "Celeste"),
+            id
+        );
+    }
+}
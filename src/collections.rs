@@ -0,0 +1,318 @@
+//! Keeping steam's collections in sync with the tags of shortcuts (requires the `json` feature).
+//!
+//! Steam used to show the `tags` of a shortcut as categories, newer clients show
+//! collections instead. The collections of an account are stored in
+//! `userdata/<account id>/config/cloudstorage/cloud-storage-namespace-1.json`, as a list of
+//! `user-collections.<id>` entries whose value is the collection as a JSON string.
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+const COLLECTION_PREFIX: &str = "user-collections.";
+
+/// A steam collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// The id of the collection, e.g. `uc-2a4e9c1f03b8`
+    pub id: String,
+    /// The name the collection is shown with
+    pub name: String,
+    /// The app ids added to the collection
+    pub added: Vec<u32>,
+    /// The app ids removed from the collection
+    pub removed: Vec<u32>,
+}
+
+impl Collection {
+    /// Does the collection contain the app?
+    pub fn contains(&self, app_id: u32) -> bool {
+        self.added.contains(&app_id) && !self.removed.contains(&app_id)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "added": self.added,
+            "removed": self.removed,
+        })
+    }
+}
+
+/// The path of the collections store of a steam account.
+pub fn collections_path(steam_root: &Path, user_id: &str) -> PathBuf {
+    steam_root
+        .join("userdata")
+        .join(user_id)
+        .join("config")
+        .join("cloudstorage")
+        .join("cloud-storage-namespace-1.json")
+}
+
+/// The collections store of a steam account, loaded to be edited.
+///
+/// Entries that are not collections are kept as they are. Steam overwrites the store
+/// while it runs, so save changes while steam is closed.
+#[derive(Debug, Clone)]
+pub struct CollectionsStore {
+    path: PathBuf,
+    entries: Vec<Value>,
+}
+
+impl CollectionsStore {
+    /// Load the collections store at the given path.
+    ///
+    /// A missing file is treated as a store without collections, which is created when saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => match json::parse(&content).map_err(invalid_data)? {
+                Value::Array(entries) => entries,
+                _ => return Err(invalid_data("Collections store is not a list".to_string())),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err),
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Load the collections store of a steam account.
+    pub fn load_for_user(steam_root: &Path, user_id: &str) -> std::io::Result<Self> {
+        Self::load(collections_path(steam_root, user_id))
+    }
+
+    /// The collections in the store, skipping deleted ones.
+    pub fn collections(&self) -> Vec<Collection> {
+        self.entries.iter().filter_map(parse_collection).collect()
+    }
+
+    /// The collection with the given name, if there is one.
+    pub fn get_by_name(&self, name: &str) -> Option<Collection> {
+        self.collections().into_iter().find(|c| c.name == name)
+    }
+
+    /// Add the collection to the store, or update the collection with the same id.
+    pub fn set_collection(&mut self, collection: &Collection) {
+        let key = format!("{}{}", COLLECTION_PREFIX, collection.id);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let existing = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry_key(entry) == Some(&key));
+        let version = existing
+            .as_ref()
+            .and_then(|entry| json::as_text(&entry[1]["version"]))
+            .and_then(|version| version.parse::<u64>().ok())
+            .map(|version| version + 1)
+            .unwrap_or(1);
+        let meta = json!({
+            "key": key,
+            "timestamp": timestamp,
+            "value": collection.to_json().to_string(),
+            "version": version.to_string(),
+            "conflictResolutionMethod": "custom",
+            "strMethodId": "union-collections",
+        });
+        let entry = json!([key, meta]);
+        match existing {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Make the collections mirror the tags of the shortcuts.
+    ///
+    /// Every shortcut is added to the collections named like its tags (creating missing
+    /// collections) and removed from the other collections. Apps that are not in
+    /// `shortcuts` are left as they are.
+    pub fn sync_from_tags(&mut self, shortcuts: &[Shortcut]) {
+        let mut collections = self.collections();
+        let mut changed = vec![false; collections.len()];
+        for shortcut in shortcuts {
+            for tag in shortcut.tags.iter() {
+                if !collections.iter().any(|c| c.name == *tag) {
+                    collections.push(Collection {
+                        id: new_collection_id(tag, collections.len()),
+                        name: tag.to_string(),
+                        added: vec![],
+                        removed: vec![],
+                    });
+                    changed.push(true);
+                }
+            }
+            for (collection, changed) in collections.iter_mut().zip(changed.iter_mut()) {
+                let tagged = shortcut.tags.contains(&collection.name.as_str());
+                if tagged && !collection.contains(shortcut.app_id) {
+                    collection.removed.retain(|id| *id != shortcut.app_id);
+                    collection.added.push(shortcut.app_id);
+                    *changed = true;
+                } else if !tagged && collection.contains(shortcut.app_id) {
+                    collection.added.retain(|id| *id != shortcut.app_id);
+                    *changed = true;
+                }
+            }
+        }
+        for (collection, changed) in collections.iter().zip(changed) {
+            if changed {
+                self.set_collection(collection);
+            }
+        }
+    }
+
+    /// Set the tags of the shortcuts to the names of the collections they are in.
+    pub fn sync_to_tags(&self, shortcuts: &mut [ShortcutOwned]) {
+        let collections = self.collections();
+        for shortcut in shortcuts.iter_mut() {
+            shortcut.tags = collections
+                .iter()
+                .filter(|c| c.contains(shortcut.app_id))
+                .map(|c| c.name.clone())
+                .collect();
+        }
+    }
+
+    /// Serialize the store to the text that [save](Self::save) would write.
+    pub fn to_text(&self) -> String {
+        Value::Array(self.entries.clone()).to_string()
+    }
+
+    /// Write the store back to its path.
+    pub fn save(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(&self.path, self.to_text())
+    }
+}
+
+/// Make the collections of a steam account mirror the tags of the shortcuts, and save them.
+///
+/// See [CollectionsStore::sync_from_tags].
+pub fn sync_collections(
+    steam_root: &Path,
+    user_id: &str,
+    shortcuts: &[Shortcut],
+) -> std::io::Result<()> {
+    let mut store = CollectionsStore::load_for_user(steam_root, user_id)?;
+    store.sync_from_tags(shortcuts);
+    store.save()
+}
+
+fn entry_key(entry: &Value) -> Option<&str> {
+    entry[0].as_str()
+}
+
+fn parse_collection(entry: &Value) -> Option<Collection> {
+    if !entry_key(entry)?.starts_with(COLLECTION_PREFIX) {
+        return None;
+    }
+    let meta = entry.get(1)?;
+    if meta["is_deleted"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    let value = json::parse(meta["value"].as_str()?).ok()?;
+    let ids = |key: &str| -> Vec<u32> {
+        value[key]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| json::as_text(id)?.parse().ok())
+            .collect()
+    };
+    Some(Collection {
+        id: value["id"].as_str()?.to_string(),
+        name: value["name"].as_str()?.to_string(),
+        added: ids("added"),
+        removed: ids("removed"),
+    })
+}
+
+/// Steam uses `uc-` followed by 12 random characters.
+fn new_collection_id(name: &str, salt: usize) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(name.as_bytes());
+    hasher.update(&salt.to_le_bytes());
+    hasher.update(&nanos.to_le_bytes());
+    format!("uc-{:08x}{:04x}", hasher.finalize(), (nanos as u16))
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const STORE: &str = r#"[["user-collections.uc-aaaaaaaaaaaa",{"key":"user-collections.uc-aaaaaaaaaaaa","timestamp":1700000000,"value":"{\"id\":\"uc-aaaaaaaaaaaa\",\"name\":\"Platformers\",\"added\":[2365067149,400],\"removed\":[]}","version":"7","conflictResolutionMethod":"custom","strMethodId":"union-collections"}],["user-collections.uc-bbbbbbbbbbbb",{"key":"user-collections.uc-bbbbbbbbbbbb","timestamp":1700000000,"is_deleted":true,"version":"3"}],["showcases.1",{"key":"showcases.1","timestamp":1,"value":"{}","version":"1"}]]"#;
+
+    fn store() -> CollectionsStore {
+        CollectionsStore {
+            path: PathBuf::new(),
+            entries: serde_json::from_str(STORE).unwrap(),
+        }
+    }
+
+    #[test]
+    fn read_collections() {
+        let collections = store().collections();
+        assert_eq!(1, collections.len());
+        assert_eq!("Platformers", collections[0].name);
+        assert!(collections[0].contains(400));
+    }
+
+    #[test]
+    fn tags_to_collections_and_back() {
+        let mut celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        celeste.app_id = 2365067149;
+        celeste.tags.push("Indie");
+        let mut store = store();
+        store.sync_from_tags(&[celeste.clone()]);
+
+        let platformers = store.get_by_name("Platformers").unwrap();
+        assert!(!platformers.contains(2365067149));
+        assert!(platformers.contains(400));
+        let indie = store.get_by_name("Indie").unwrap();
+        assert_eq!(vec![2365067149], indie.added);
+        assert!(indie.id.starts_with("uc-"));
+        assert_eq!(15, indie.id.len());
+        assert_eq!("8", store.entries[0][1]["version"]);
+        // Other entries are kept
+        assert_eq!(4, store.entries.len());
+
+        let mut shortcuts = vec![celeste.to_owned()];
+        shortcuts[0].tags.clear();
+        store.sync_to_tags(&mut shortcuts);
+        assert_eq!(vec!["Indie"], &shortcuts[0].tags[..]);
+    }
+
+    #[test]
+    fn save_and_reload() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let mut celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        celeste.tags.push("Favorites");
+        sync_collections(dir, "12345", &[celeste.clone()]).unwrap();
+        let reloaded = CollectionsStore::load_for_user(dir, "12345");
+        let collections = reloaded.unwrap().collections();
+        assert_eq!(1, collections.len());
+        assert!(collections[0].contains(celeste.app_id));
+    }
+}
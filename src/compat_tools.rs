@@ -0,0 +1,250 @@
+//! Reading and changing the compatibility tool (e.g. Proton) steam uses for an app.
+//!
+//! Steam keeps the mapping from app ids to compatibility tools in the `CompatToolMapping`
+//! section of `config/config.vdf`. For shortcuts the key is the app id of the shortcut.
+
+use std::path::{Path, PathBuf};
+
+use crate::text_vdf::{self, Section, Value};
+
+const MAPPING_PATH: [&str; 5] = [
+    "InstallConfigStore",
+    "Software",
+    "Valve",
+    "Steam",
+    "CompatToolMapping",
+];
+
+/// The priority steam gives a compatibility tool the user chose for an app.
+pub const USER_PRIORITY: u32 = 250;
+
+/// A compatibility tool assigned to an app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatTool {
+    /// The internal name of the tool, e.g. `proton_9` or `GE-Proton9-20`
+    pub name: String,
+    /// Extra configuration for the tool, usually empty
+    pub config: String,
+    /// How the mapping was made, [USER_PRIORITY] when the user chose the tool
+    pub priority: u32,
+}
+
+impl CompatTool {
+    /// A tool the user chose, with no extra configuration.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            config: String::new(),
+            priority: USER_PRIORITY,
+        }
+    }
+}
+
+/// The `config/config.vdf` file of a steam installation, loaded to edit its compatibility tools.
+///
+/// Steam overwrites config.vdf when it exits, so save changes while steam is closed.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::compat_tools::{CompatTool, CompatToolMapping};
+/// use steam_shortcuts_util::Shortcut;
+/// use std::path::Path;
+///
+/// fn example() -> std::io::Result<()> {
+///     let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+///     let mut mapping = CompatToolMapping::load_for_steam_root(Path::new("/home/deck/.steam/steam"))?;
+///     mapping.set(shortcut.app_id, &CompatTool::new("proton_9"));
+///     mapping.save()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompatToolMapping {
+    path: PathBuf,
+    root: Section,
+}
+
+impl CompatToolMapping {
+    /// The path of config.vdf in a steam installation.
+    pub fn config_path(steam_root: &Path) -> PathBuf {
+        steam_root.join("config").join("config.vdf")
+    }
+
+    /// Load the config.vdf at the given path.
+    ///
+    /// A missing file is treated as an empty config, which is created when saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let root = text_vdf::read_file(&path)?;
+        Ok(Self { path, root })
+    }
+
+    /// Load the config.vdf of a steam installation.
+    pub fn load_for_steam_root(steam_root: &Path) -> std::io::Result<Self> {
+        Self::load(Self::config_path(steam_root))
+    }
+
+    /// The path this config was loaded from and will be saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The compatibility tool assigned to the app, if any.
+    pub fn get(&self, app_id: u32) -> Option<CompatTool> {
+        let tool = self
+            .root
+            .get_path(&MAPPING_PATH)?
+            .get_section(&app_id.to_string())?;
+        parse_tool(tool)
+    }
+
+    /// All apps with a compatibility tool, in the order they are in the file.
+    pub fn all(&self) -> Vec<(u32, CompatTool)> {
+        let mapping = match self.root.get_path(&MAPPING_PATH) {
+            Some(mapping) => mapping,
+            None => return vec![],
+        };
+        mapping
+            .entries
+            .iter()
+            .filter_map(|(key, value)| {
+                let app_id = key.parse().ok()?;
+                Some((app_id, parse_tool(value.as_section()?)?))
+            })
+            .collect()
+    }
+
+    /// Assign a compatibility tool to the app, replacing the one it had.
+    pub fn set(&mut self, app_id: u32, tool: &CompatTool) {
+        let mut section = Section::new();
+        section.set_text("name", &tool.name);
+        section.set_text("config", &tool.config);
+        section.set_text("priority", &tool.priority.to_string());
+        self.root
+            .path_or_insert(&MAPPING_PATH)
+            .set_section(&app_id.to_string(), section);
+    }
+
+    /// Remove the compatibility tool of the app, returning it if there was one.
+    pub fn remove(&mut self, app_id: u32) -> Option<CompatTool> {
+        let removed = self
+            .root
+            .get_path_mut(&MAPPING_PATH)?
+            .remove(&app_id.to_string())?;
+        match removed {
+            Value::Section(section) => parse_tool(&section),
+            Value::Text(_) => None,
+        }
+    }
+
+    /// Serialize the config to the text that [save](Self::save) would write.
+    pub fn to_text(&self) -> String {
+        self.root.to_text()
+    }
+
+    /// Write the config back to its path.
+    pub fn save(&self) -> std::io::Result<()> {
+        text_vdf::write_file(&self.path, &self.root)
+    }
+}
+
+fn parse_tool(section: &Section) -> Option<CompatTool> {
+    Some(CompatTool {
+        name: section.get_text("name")?.to_string(),
+        config: section.get_text("config").unwrap_or_default().to_string(),
+        priority: section
+            .get_text("priority")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(USER_PRIORITY),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const CONFIG: &str = r#""InstallConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"AutoUpdateWindowEnabled"		"0"
+				"CompatToolMapping"
+				{
+					"0"
+					{
+						"name"		"proton_experimental"
+						"config"		""
+						"priority"		"75"
+					}
+					"2365067149"
+					{
+						"name"		"GE-Proton9-20"
+						"config"		""
+						"priority"		"250"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn read_mapping() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config").join("config.vdf"), CONFIG).unwrap();
+        let mapping = CompatToolMapping::load_for_steam_root(dir);
+        let mapping = mapping.unwrap();
+
+        assert_eq!(
+            Some(CompatTool::new("GE-Proton9-20")),
+            mapping.get(2365067149)
+        );
+        assert_eq!(2, mapping.all().len());
+        assert_eq!(None, mapping.get(1));
+    }
+
+    #[test]
+    fn set_remove_and_save() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("config").join("config.vdf");
+
+        let mut mapping = CompatToolMapping::load(&path).unwrap();
+        mapping.set(3000000000, &CompatTool::new("proton_9"));
+        mapping.set(3000000001, &CompatTool::new("proton_8"));
+        assert_eq!(
+            Some(CompatTool::new("proton_8")),
+            mapping.remove(3000000001)
+        );
+        mapping.save().unwrap();
+
+        let reloaded = CompatToolMapping::load(&path);
+        let reloaded = reloaded.unwrap();
+        assert_eq!(
+            vec![(3000000000, CompatTool::new("proton_9"))],
+            reloaded.all()
+        );
+    }
+
+    #[test]
+    fn keeps_other_settings() {
+        let mut root = text_vdf::parse(CONFIG).unwrap();
+        let mut mapping = CompatToolMapping {
+            path: PathBuf::new(),
+            root: root.clone(),
+        };
+        mapping.set(2365067149, &CompatTool::new("proton_9"));
+        root = text_vdf::parse(&mapping.to_text()).unwrap();
+        let steam = root.get_path(&MAPPING_PATH[..4]).unwrap();
+        assert_eq!(Some("0"), steam.get_text("AutoUpdateWindowEnabled"));
+        assert_eq!(Some(CompatTool::new("proton_9")), mapping.get(2365067149));
+    }
+}
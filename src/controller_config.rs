@@ -0,0 +1,214 @@
+//! Reading and changing the controller layout steam uses for a shortcut.
+//!
+//! Steam Input keeps the chosen layout of every game per controller type, in
+//! `steamapps/common/Steam Controller Configs/<account id>/config/configset_<controller>.vdf`.
+//! Shortcuts are keyed by their app id there; older steam versions used the lower case
+//! app name, which is still read as a fallback.
+
+use std::path::{Path, PathBuf};
+
+use crate::shortcut::Shortcut;
+use crate::text_vdf::{self, Section};
+
+/// The types of controller steam keeps separate layouts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControllerType {
+    /// The built-in controls of the Steam Deck
+    SteamDeck,
+    /// The Steam Controller
+    SteamController,
+    /// Xbox 360 controllers
+    Xbox360,
+    /// Xbox One and Series controllers
+    XboxOne,
+    /// PlayStation 4 controllers
+    Ps4,
+    /// PlayStation 5 controllers
+    Ps5,
+    /// Nintendo Switch Pro controllers
+    SwitchPro,
+}
+
+impl ControllerType {
+    /// The name steam uses for the controller type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControllerType::SteamDeck => "controller_neptune",
+            ControllerType::SteamController => "controller_steamcontroller_gordon",
+            ControllerType::Xbox360 => "controller_xbox360",
+            ControllerType::XboxOne => "controller_xboxone",
+            ControllerType::Ps4 => "controller_ps4",
+            ControllerType::Ps5 => "controller_ps5",
+            ControllerType::SwitchPro => "controller_switch_pro",
+        }
+    }
+
+    /// The name of the file the layouts for this controller type are kept in.
+    pub fn file_name(&self) -> String {
+        format!("configset_{}.vdf", self.name())
+    }
+}
+
+/// The layout chosen for a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControllerLayout {
+    /// A template that ships with steam, e.g. `controller_neptune_gamepad+mouse.vdf`
+    Template(String),
+    /// A layout from the workshop, by its id
+    Workshop(String),
+}
+
+/// The folder with the controller configsets of a steam account.
+pub fn configs_dir(steam_root: &Path, user_id: &str) -> PathBuf {
+    steam_root
+        .join("steamapps")
+        .join("common")
+        .join("Steam Controller Configs")
+        .join(user_id)
+        .join("config")
+}
+
+/// A configset file, loaded to edit the layouts chosen for shortcuts.
+///
+/// Steam may overwrite the file while it runs, so save changes while steam is closed.
+#[derive(Debug, Clone)]
+pub struct ControllerConfigSet {
+    path: PathBuf,
+    root: Section,
+}
+
+impl ControllerConfigSet {
+    /// Load the configset file at the given path.
+    ///
+    /// A missing file is treated as an empty configset, which is created when saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let root = text_vdf::read_file(&path)?;
+        Ok(Self { path, root })
+    }
+
+    /// Load the configset of a controller type for a steam account.
+    pub fn load_for_user(
+        steam_root: &Path,
+        user_id: &str,
+        controller: ControllerType,
+    ) -> std::io::Result<Self> {
+        Self::load(configs_dir(steam_root, user_id).join(controller.file_name()))
+    }
+
+    /// The path this configset was loaded from and will be saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The layout chosen for the shortcut, if any.
+    pub fn get(&self, shortcut: &Shortcut) -> Option<ControllerLayout> {
+        let configs = self.root.get_section("controller_config")?;
+        let game = configs
+            .get_section(&shortcut.app_id.to_string())
+            .or_else(|| configs.get_section(&shortcut.app_name.to_lowercase()))?;
+        if let Some(workshop) = game.get_text("workshop") {
+            return Some(ControllerLayout::Workshop(workshop.to_string()));
+        }
+        game.get_text("template")
+            .map(|template| ControllerLayout::Template(template.to_string()))
+    }
+
+    /// Choose the layout for the shortcut, replacing the one it had.
+    pub fn set(&mut self, shortcut: &Shortcut, layout: &ControllerLayout) {
+        let configs = self.root.section_or_insert("controller_config");
+        configs.remove(&shortcut.app_name.to_lowercase());
+        let mut game = Section::new();
+        match layout {
+            ControllerLayout::Template(template) => game.set_text("template", template),
+            ControllerLayout::Workshop(id) => game.set_text("workshop", id),
+        }
+        configs.set_section(&shortcut.app_id.to_string(), game);
+    }
+
+    /// Remove the layout chosen for the shortcut, returning if there was one.
+    pub fn remove(&mut self, shortcut: &Shortcut) -> bool {
+        match self.root.get_section_mut("controller_config") {
+            Some(configs) => {
+                let by_app_id = configs.remove(&shortcut.app_id.to_string()).is_some();
+                let by_name = configs.remove(&shortcut.app_name.to_lowercase()).is_some();
+                by_app_id || by_name
+            }
+            None => false,
+        }
+    }
+
+    /// Serialize the configset to the text that [save](Self::save) would write.
+    pub fn to_text(&self) -> String {
+        self.root.to_text()
+    }
+
+    /// Write the configset back to its path.
+    pub fn save(&self) -> std::io::Result<()> {
+        text_vdf::write_file(&self.path, &self.root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const CONFIGSET: &str = r#""controller_config"
+{
+	"celeste"
+	{
+		"workshop"		"1234567"
+	}
+	"spore"
+	{
+		"template"		"controller_neptune_gamepad+mouse.vdf"
+	}
+}
+"#;
+
+    #[test]
+    fn read_layouts_by_name() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = configs_dir(dir, "12345").join(ControllerType::SteamDeck.file_name());
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, CONFIGSET).unwrap();
+        let configset = ControllerConfigSet::load_for_user(dir, "12345", ControllerType::SteamDeck);
+        let configset = configset.unwrap();
+
+        let celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        let hades = Shortcut::new("1", "Hades", "hades.exe", "", "", "", "");
+        assert_eq!(
+            Some(ControllerLayout::Workshop("1234567".to_string())),
+            configset.get(&celeste)
+        );
+        assert_eq!(None, configset.get(&hades));
+    }
+
+    #[test]
+    fn set_and_remove() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("configset_controller_neptune.vdf");
+        std::fs::write(&path, CONFIGSET).unwrap();
+
+        let celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        let spore = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "");
+        let layout = ControllerLayout::Template("controller_neptune_gamepad_fps.vdf".to_string());
+        let mut configset = ControllerConfigSet::load(&path).unwrap();
+        configset.set(&celeste, &layout);
+        assert!(configset.remove(&spore));
+        assert!(!configset.remove(&spore));
+        configset.save().unwrap();
+
+        let reloaded = ControllerConfigSet::load(&path);
+        let reloaded = reloaded.unwrap();
+        assert_eq!(Some(layout), reloaded.get(&celeste));
+        assert_eq!(None, reloaded.get(&spore));
+        let configs = reloaded.root.get_section("controller_config").unwrap();
+        assert_eq!(1, configs.entries.len());
+        assert_eq!(celeste.app_id.to_string(), configs.entries[0].0);
+    }
+}
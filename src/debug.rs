@@ -0,0 +1,216 @@
+//! Explaining the bytes of a shortcuts.vdf file, to find out why it does not parse.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::bytes::{tag, take_through, BS, NULL, SOH, STX};
+use crate::shortcuts_parser::{get_order, parse_a_line, shotcut_content, take_tag, LineType};
+
+/// The most bytes shown for one part of the file.
+const SHOWN_BYTES: usize = 16;
+
+/// Walk through a shortcuts.vdf file the way the parser does, and describe every part of it.
+///
+/// Every line has the offset of a part in hex, what the parser reads it as and its first bytes.
+/// The dump ends where the parser stops, with the reason it stops there. Bytes after that are
+/// not read by the parser, so a shortcut there is lost.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::debug::explain;
+///
+/// let mut bytes = b"\0shortcuts\0\x000\0\x01AppName\0Celeste\0".to_vec();
+/// bytes.push(0x42);
+/// let explained = explain(&bytes);
+/// assert!(explained.contains("0000000e    text AppName = \"Celeste\""));
+/// assert!(explained.contains("stopped: unexpected byte 0x42"));
+/// ```
+pub fn explain(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut dump = Dump {
+        bytes,
+        out: &mut out,
+    };
+    let i = match shotcut_content(bytes) {
+        Ok((i, _)) => {
+            dump.part(bytes, i, "header \"shortcuts\"");
+            i
+        }
+        Err(_) => {
+            dump.stop(bytes, "the file does not start with \\0shortcuts\\0");
+            return out;
+        }
+    };
+    dump.shortcuts(i);
+    out
+}
+
+struct Dump<'a, 'o> {
+    bytes: &'a [u8],
+    out: &'o mut String,
+}
+
+impl<'a> Dump<'a, '_> {
+    fn offset(&self, i: &[u8]) -> usize {
+        self.bytes.len() - i.len()
+    }
+
+    /// Describe the bytes from `start` to `end`.
+    fn part(&mut self, start: &[u8], end: &[u8], description: &str) {
+        let len = start.len() - end.len();
+        self.line(start, len, description);
+    }
+
+    /// Describe where the parser stops.
+    fn stop(&mut self, i: &[u8], reason: &str) {
+        self.line(i, i.len(), &format!("stopped: {}", reason));
+    }
+
+    fn line(&mut self, i: &[u8], len: usize, description: &str) {
+        let offset = self.offset(i);
+        let _ = write!(self.out, "{:08x}  {:<48}", offset, description);
+        for byte in &i[..len.min(SHOWN_BYTES)] {
+            let _ = write!(self.out, " {:02x}", byte);
+        }
+        if len > SHOWN_BYTES {
+            let _ = write!(self.out, " ... ({} bytes)", len);
+        }
+        self.out.push('\n');
+    }
+
+    fn shortcuts(&mut self, mut i: &'a [u8]) {
+        loop {
+            if i.first() == Some(&BS) {
+                let rest = &i[1..];
+                self.part(i, rest, "end of the shortcuts");
+                self.end_of_file(rest);
+                return;
+            }
+            let (rest, order) = match get_order(i) {
+                Ok(order) => order,
+                Err(_) => {
+                    let reason = match i.first() {
+                        None => String::from("the file ends before the end of the shortcuts"),
+                        Some(&NULL) => String::from("the order of a shortcut is not UTF-8"),
+                        Some(byte) => format!(
+                            "unexpected byte 0x{:02x}, expected a shortcut (0x00) or the end of the shortcuts (0x08)",
+                            byte
+                        ),
+                    };
+                    self.stop(i, &reason);
+                    return;
+                }
+            };
+            self.part(i, rest, &format!("shortcut {:?}", order));
+            match self.shortcut(rest) {
+                Some(rest) => i = rest,
+                None => return,
+            }
+        }
+    }
+
+    /// Describe the keys and tags of a shortcut, returns what follows it.
+    fn shortcut(&mut self, i: &'a [u8]) -> Option<&'a [u8]> {
+        let mut i = self.lines(i);
+        if let Ok((rest, _)) = tag(b"\0tags\0", i) {
+            self.part(i, rest, "  tags");
+            i = self.tags(rest)?;
+            i = self.lines(i);
+        }
+        if i.first() == Some(&BS) {
+            let rest = &i[1..];
+            self.part(i, rest, "end of the shortcut");
+            return Some(rest);
+        }
+        let reason = match i.first() {
+            None => String::from("the file ends in a shortcut"),
+            Some(&SOH) => String::from("a text line without two null terminated UTF-8 texts"),
+            Some(&STX) => String::from("a number line without a null terminated UTF-8 key and a number"),
+            Some(byte) => format!(
+                "unexpected byte 0x{:02x}, expected a key (0x01 or 0x02), the tags or the end of the shortcut (0x08)",
+                byte
+            ),
+        };
+        self.stop(i, &reason);
+        None
+    }
+
+    fn lines(&mut self, mut i: &'a [u8]) -> &'a [u8] {
+        while let Ok((rest, line)) = parse_a_line(i) {
+            let description = match line {
+                LineType::Text { name, value } => format!("  text {} = {:?}", name, value),
+                LineType::Numeric { name, value } => format!("  number {} = {}", name, value),
+            };
+            self.part(i, rest, &description);
+            i = rest;
+        }
+        i
+    }
+
+    fn tags(&mut self, i: &'a [u8]) -> Option<&'a [u8]> {
+        let (after, mut tags) = match take_through(BS, i) {
+            Ok(tags) => tags,
+            Err(_) => {
+                self.stop(i, "the tags do not end (0x08)");
+                return None;
+            }
+        };
+        // The tags are read from a copy of their bytes, this finds those bytes in the file
+        let tags_len = i.len() - after.len() - 1;
+        while let Ok((rest, name)) = take_tag(tags) {
+            let start = &i[tags_len - tags.len()..];
+            self.part(
+                start,
+                &start[tags.len() - rest.len()..],
+                &format!("    tag {:?}", name),
+            );
+            tags = rest;
+        }
+        let end = &i[tags_len - tags.len()..];
+        if !tags.is_empty() {
+            self.part(end, &end[tags.len()..], "    bytes the tags ignore");
+        }
+        self.part(&end[tags.len()..], after, "  end of the tags");
+        Some(after)
+    }
+
+    fn end_of_file(&mut self, i: &[u8]) {
+        let mut rest = i;
+        if rest.first() == Some(&BS) {
+            self.part(rest, &rest[1..], "end of the file");
+            rest = &rest[1..];
+        }
+        if !rest.is_empty() {
+            self.line(
+                rest,
+                rest.len(),
+                "bytes after the end, the parser ignores them",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn explains_a_whole_file() {
+        let content = include_bytes!("testdata/shortcutsfirefox.vdf");
+        let explained = explain(content);
+        assert!(!explained.contains("stopped"));
+        assert!(explained.contains("shortcut \"0\""));
+        assert!(explained.contains("end of the file"));
+    }
+
+    #[test]
+    fn explains_where_parsing_stops() {
+        let explained = explain(b"\0shortcuts\0\x000\0\x02LastPlayTime\0\x01\x02");
+        let last = explained.lines().last().unwrap();
+        assert!(last.starts_with("0000000e  stopped: a number line"));
+        assert!(last.ends_with(" 02 4c 61 73 74 50 6c 61 79 54 69 6d 65 00 01 02"));
+        assert!(explain(b"\0shortcut").contains("stopped: the file does not start"));
+    }
+}
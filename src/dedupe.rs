@@ -0,0 +1,112 @@
+//! Removing duplicate shortcuts, e.g. after importing the same game from several sources.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::matching::MatchBy;
+use crate::shortcut::ShortcutOwned;
+
+/// A shortcut removed by [dedupe].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedDuplicate {
+    /// The removed shortcut
+    pub removed: ShortcutOwned,
+    /// The index (after deduplication) of the shortcut it duplicated, which was kept
+    pub kept_index: usize,
+}
+
+/// Remove the shortcuts that are duplicates of an earlier shortcut in the list.
+///
+/// The first shortcut of each set of duplicates is kept. The order of the remaining
+/// shortcuts is renumbered from 0. Returns the removed shortcuts.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{dedupe, MatchBy, Shortcut};
+///
+/// let mut shortcuts = vec![
+///     Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned(),
+///     Shortcut::new("1", "CELESTE™", "other.exe", "", "", "", "").to_owned(),
+/// ];
+/// let removed = dedupe(&mut shortcuts, MatchBy::NormalizedName);
+/// assert_eq!(1, shortcuts.len());
+/// assert_eq!("CELESTE™", removed[0].removed.app_name);
+/// ```
+pub fn dedupe(shortcuts: &mut Vec<ShortcutOwned>, key: MatchBy) -> Vec<RemovedDuplicate> {
+    // The index each shortcut would have after deduplication, for the shortcuts that are kept
+    let mut kept: BTreeMap<_, usize> = BTreeMap::new();
+    let duplicate_of: Vec<Option<usize>> = shortcuts
+        .iter()
+        .map(|shortcut| {
+            let next = kept.len();
+            let kept_index = *kept.entry(key.key(shortcut)).or_insert(next);
+            Some(kept_index).filter(|&kept_index| kept_index != next)
+        })
+        .collect();
+    drop(kept);
+    let mut removed = vec![];
+    for (shortcut, duplicate_of) in core::mem::take(shortcuts).into_iter().zip(duplicate_of) {
+        match duplicate_of {
+            Some(kept_index) => removed.push(RemovedDuplicate {
+                removed: shortcut,
+                kept_index,
+            }),
+            None => shortcuts.push(shortcut),
+        }
+    }
+    for (index, shortcut) in shortcuts.iter_mut().enumerate() {
+        shortcut.order = index.to_string();
+    }
+    removed
+}
+
+/// Normalize a name for comparing, keeping only lower case letters and digits.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+
+    fn shortcuts() -> Vec<ShortcutOwned> {
+        vec![
+            Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned(),
+            Shortcut::new("1", "Spore", "spore.exe", "", "", "", "").to_owned(),
+            Shortcut::new("2", "Celeste", "celeste.exe", "", "", "", "-windowed").to_owned(),
+            Shortcut::new("3", "celeste: the game", "other.exe", "", "", "", "").to_owned(),
+            Shortcut::new("4", "Spore", "other.exe", "", "", "", "").to_owned(),
+        ]
+    }
+
+    #[test]
+    fn dedupe_by_exe_and_name() {
+        let mut list = shortcuts();
+        let removed = dedupe(&mut list, MatchBy::ExeAndName);
+        assert_eq!(4, list.len());
+        assert_eq!(1, removed.len());
+        assert_eq!("-windowed", removed[0].removed.launch_options);
+        assert_eq!(0, removed[0].kept_index);
+        let orders: Vec<&str> = list.iter().map(|s| s.order.as_str()).collect();
+        assert_eq!(vec!["0", "1", "2", "3"], orders);
+    }
+
+    #[test]
+    fn dedupe_by_app_id_and_normalized_name() {
+        let mut by_app_id = shortcuts();
+        assert_eq!(1, dedupe(&mut by_app_id, MatchBy::AppId).len());
+
+        let mut by_name = shortcuts();
+        let removed = dedupe(&mut by_name, MatchBy::NormalizedName);
+        assert_eq!(2, removed.len());
+        assert_eq!(1, removed[1].kept_index);
+        assert_eq!("celestethegame", normalize_name("Celeste: The Game™"));
+    }
+}
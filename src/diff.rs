@@ -0,0 +1,236 @@
+//! Comparing two lists of shortcuts, e.g. to show what saving would change.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::matching::{MatchBy, Matcher};
+use crate::shortcut::Shortcut;
+
+/// How [diff] and [merge](crate::merge) find a shortcut in the other list: by app id, or else
+/// by exe and app_name.
+pub(crate) const MATCH_BY: [MatchBy; 2] = [MatchBy::AppId, MatchBy::ExeAndName];
+
+/// A field that differs between two versions of a shortcut.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// The name of the field, as it is written in shortcuts.vdf (e.g. `LaunchOptions`)
+    pub field: &'static str,
+    /// The old value, as text
+    pub old: String,
+    /// The new value, as text
+    pub new: String,
+}
+
+/// A shortcut that is in both lists, but with different fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedShortcut<'s, 'a> {
+    /// The shortcut in the old list
+    pub old: &'s Shortcut<'a>,
+    /// The shortcut in the new list
+    pub new: &'s Shortcut<'a>,
+    /// The fields that differ
+    pub changes: Vec<FieldChange>,
+}
+
+/// The differences between two lists of shortcuts, made by [diff].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ShortcutDiff<'s, 'a> {
+    /// Shortcuts that are only in the new list
+    pub added: Vec<&'s Shortcut<'a>>,
+    /// Shortcuts that are only in the old list
+    pub removed: Vec<&'s Shortcut<'a>>,
+    /// Shortcuts that are in both lists with different fields
+    pub changed: Vec<ChangedShortcut<'s, 'a>>,
+}
+
+impl ShortcutDiff<'_, '_> {
+    /// Are the lists the same (ignoring the order of the shortcuts)?
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Find the shortcuts that were added, removed or changed from `old` to `new`.
+///
+/// Shortcuts are matched by app id, or else by exe and app_name (so a shortcut with a
+/// recalculated app id shows up as changed). The order of the shortcuts is not compared, and
+/// shortcuts that are [semantically equal](Shortcut::semantically_eq) are not reported as changed.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{diff, Shortcut};
+///
+/// let old = vec![Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "")];
+/// let mut changed = old[0].clone();
+/// changed.launch_options = "-windowed";
+/// let new = vec![changed];
+///
+/// let diff = diff(&old, &new);
+/// assert_eq!("LaunchOptions", diff.changed[0].changes[0].field);
+/// ```
+pub fn diff<'s, 'a>(old: &'s [Shortcut<'a>], new: &'s [Shortcut<'a>]) -> ShortcutDiff<'s, 'a> {
+    let mut res = ShortcutDiff::default();
+    let mut matcher = Matcher::new(old, &MATCH_BY);
+    for new_shortcut in new {
+        match matcher.take(new_shortcut) {
+            Some(index) => {
+                let changes = field_changes(&old[index], new_shortcut);
+                if !changes.is_empty() && !old[index].semantically_eq(new_shortcut) {
+                    res.changed.push(ChangedShortcut {
+                        old: &old[index],
+                        new: new_shortcut,
+                        changes,
+                    });
+                }
+            }
+            None => res.added.push(new_shortcut),
+        }
+    }
+    res.removed = old
+        .iter()
+        .zip(matcher.into_matched())
+        .filter(|(_, matched)| !matched)
+        .map(|(shortcut, _)| shortcut)
+        .collect();
+    res
+}
+
+/// The fields that differ between two shortcuts, in the order they are written to shortcuts.vdf.
+///
+/// The order of the shortcuts is not compared.
+pub fn field_changes(old: &Shortcut, new: &Shortcut) -> Vec<FieldChange> {
+    let fields: Vec<(&'static str, String, String)> = vec![
+        ("appid", old.app_id.to_string(), new.app_id.to_string()),
+        (
+            "AppName",
+            old.app_name.to_string(),
+            new.app_name.to_string(),
+        ),
+        ("Exe", old.exe.to_string(), new.exe.to_string()),
+        (
+            "StartDir",
+            old.start_dir.to_string(),
+            new.start_dir.to_string(),
+        ),
+        ("icon", old.icon.to_string(), new.icon.to_string()),
+        (
+            "ShortcutPath",
+            old.shortcut_path.to_string(),
+            new.shortcut_path.to_string(),
+        ),
+        (
+            "LaunchOptions",
+            old.launch_options.to_string(),
+            new.launch_options.to_string(),
+        ),
+        (
+            "IsHidden",
+            old.is_hidden.to_string(),
+            new.is_hidden.to_string(),
+        ),
+        (
+            "AllowDesktopConfig",
+            old.allow_desktop_config.to_string(),
+            new.allow_desktop_config.to_string(),
+        ),
+        (
+            "AllowOverlay",
+            old.allow_overlay.to_string(),
+            new.allow_overlay.to_string(),
+        ),
+        ("openvr", old.open_vr.to_string(), new.open_vr.to_string()),
+        ("Devkit", old.dev_kit.to_string(), new.dev_kit.to_string()),
+        (
+            "DevkitGameID",
+            old.dev_kit_game_id.to_string(),
+            new.dev_kit_game_id.to_string(),
+        ),
+        (
+            "DevkitOverrideAppID",
+            old.dev_kit_overrite_app_id.to_string(),
+            new.dev_kit_overrite_app_id.to_string(),
+        ),
+        (
+            "LastPlayTime",
+            old.last_play_time.to_string(),
+            new.last_play_time.to_string(),
+        ),
+        (
+            "FlatpakAppID",
+            old.flatpak_app_id.to_string(),
+            new.flatpak_app_id.to_string(),
+        ),
+        ("tags", old.tags.join(", "), new.tags.join(", ")),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange { field, old, new })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn added_removed_changed() {
+        let celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        let spore = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "");
+        let hades = Shortcut::new("1", "Hades", "hades.exe", "", "", "", "");
+        let mut new_celeste = celeste.clone();
+        new_celeste.order = "5";
+        new_celeste.tags = vec!["Favorite"];
+        new_celeste.is_hidden = true;
+
+        let old = vec![celeste, spore];
+        let new = vec![new_celeste, hades];
+        let diff = diff(&old, &new);
+
+        assert_eq!(vec!["Hades"], names(&diff.added));
+        assert_eq!(vec!["Spore"], names(&diff.removed));
+        assert_eq!(1, diff.changed.len());
+        assert_eq!(
+            vec![
+                FieldChange {
+                    field: "IsHidden",
+                    old: "false".to_string(),
+                    new: "true".to_string()
+                },
+                FieldChange {
+                    field: "tags",
+                    old: "".to_string(),
+                    new: "Favorite".to_string()
+                },
+            ],
+            diff.changed[0].changes
+        );
+    }
+
+    #[test]
+    fn same_file_has_no_diff() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(content.as_slice()).unwrap();
+        let mut reversed = shortcuts.clone();
+        reversed.reverse();
+        assert!(diff(&shortcuts, &reversed).is_empty());
+    }
+
+    #[test]
+    fn cosmetic_changes_are_ignored() {
+        let mut celeste = Shortcut::new("0", "Celeste", "\"celeste.exe\"", "", "", "", "");
+        celeste.tags = vec!["Installed", "favorite"];
+        let mut new_celeste = celeste.clone();
+        new_celeste.exe = "celeste.exe";
+        new_celeste.tags = vec!["favorite", "Installed"];
+        let old = vec![celeste];
+        let new = vec![new_celeste];
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    fn names<'a>(shortcuts: &[&Shortcut<'a>]) -> Vec<&'a str> {
+        shortcuts.iter().map(|s| s.app_name).collect()
+    }
+}
@@ -0,0 +1,275 @@
+//! Extracting the icon from a Windows executable (requires the `exe_icon` feature).
+//!
+//! The first icon group in the resources of the `.exe` is written out as a `.ico` file,
+//! which steam can show for the `icon` field of a shortcut. The executable is read as bytes,
+//! so this also works for Windows games installed on Linux (e.g. for use with Proton).
+
+use std::path::Path;
+
+const RT_ICON: u32 = 3;
+const RT_GROUP_ICON: u32 = 14;
+
+/// Extract the first icon of a Windows executable as the bytes of a `.ico` file.
+///
+/// Returns `None` if the file is not an executable or has no icon.
+pub fn extract_icon(exe: &Path) -> std::io::Result<Option<Vec<u8>>> {
+    let bytes = std::fs::read(exe)?;
+    Ok(icon_from_executable(&bytes))
+}
+
+/// Extract the first icon of a Windows executable and write it to the target `.ico` file.
+///
+/// Returns false (and writes nothing) if the executable has no icon.
+pub fn extract_icon_to_file(exe: &Path, target: &Path) -> std::io::Result<bool> {
+    match extract_icon(exe)? {
+        Some(icon) => {
+            if let Some(parent) = target.parent() {
+                if !parent.as_os_str().is_empty() {
+                    std::fs::create_dir_all(parent)?;
+                }
+            }
+            std::fs::write(target, icon)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Extract the first icon from the bytes of a Windows executable as the bytes of a `.ico` file.
+pub fn icon_from_executable(bytes: &[u8]) -> Option<Vec<u8>> {
+    let resources = Resources::find(bytes)?;
+    let group = resources.first_data(RT_GROUP_ICON, None)?;
+    let count = read_u16(group, 4)? as usize;
+
+    let mut images = vec![];
+    for index in 0..count {
+        let entry = group.get(6 + index * 14..6 + (index + 1) * 14)?;
+        let id = read_u16(entry, 12)? as u32;
+        let image = resources.first_data(RT_ICON, Some(id))?;
+        images.push((entry, image));
+    }
+
+    let mut ico = vec![];
+    ico.extend_from_slice(&0u16.to_le_bytes());
+    ico.extend_from_slice(&1u16.to_le_bytes());
+    ico.extend_from_slice(&(images.len() as u16).to_le_bytes());
+    let mut offset = 6 + images.len() * 16;
+    for (entry, image) in &images {
+        // Width, height, colors, reserved, planes and bit count are the same as in the group
+        ico.extend_from_slice(&entry[0..8]);
+        ico.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += image.len();
+    }
+    for (_, image) in &images {
+        ico.extend_from_slice(image);
+    }
+    Some(ico)
+}
+
+struct Resources<'a> {
+    file: &'a [u8],
+    sections: Vec<(u32, u32, u32)>,
+    start: usize,
+}
+
+impl<'a> Resources<'a> {
+    fn find(file: &'a [u8]) -> Option<Self> {
+        if file.get(0..2)? != b"MZ" {
+            return None;
+        }
+        let pe = read_u32(file, 0x3C)? as usize;
+        if file.get(pe..pe + 4)? != b"PE\0\0" {
+            return None;
+        }
+        let section_count = read_u16(file, pe + 6)? as usize;
+        let optional_header_size = read_u16(file, pe + 20)? as usize;
+        let optional_header = pe + 24;
+        let data_directories = match read_u16(file, optional_header)? {
+            0x10b => optional_header + 96,
+            0x20b => optional_header + 112,
+            _ => return None,
+        };
+        let resources_rva = read_u32(file, data_directories + 2 * 8)?;
+        if resources_rva == 0 {
+            return None;
+        }
+
+        let section_table = optional_header + optional_header_size;
+        let sections = (0..section_count)
+            .map(|index| {
+                let header = section_table + index * 40;
+                Some((
+                    read_u32(file, header + 12)?,
+                    read_u32(file, header + 16)?.max(read_u32(file, header + 8)?),
+                    read_u32(file, header + 20)?,
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut resources = Self {
+            file,
+            sections,
+            start: 0,
+        };
+        resources.start = resources.rva_to_offset(resources_rva)?;
+        Some(resources)
+    }
+
+    /// The file offset of an address, sections whose numbers overflow are malformed and skipped.
+    fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        self.sections.iter().find_map(|&(address, size, raw)| {
+            let end = address.checked_add(size)?;
+            if rva < address || rva >= end {
+                return None;
+            }
+            (rva - address)
+                .checked_add(raw)
+                .map(|offset| offset as usize)
+        })
+    }
+
+    /// The entries of the resource directory at the offset, as (id, offset) pairs.
+    fn directory(&self, offset: usize) -> Option<Vec<(u32, u32)>> {
+        let directory = self.start + offset;
+        let count = read_u16(self.file, directory + 12)? as usize
+            + read_u16(self.file, directory + 14)? as usize;
+        (0..count)
+            .map(|index| {
+                let entry = directory + 16 + index * 8;
+                Some((read_u32(self.file, entry)?, read_u32(self.file, entry + 4)?))
+            })
+            .collect()
+    }
+
+    fn first_data(&self, resource_type: u32, id: Option<u32>) -> Option<&'a [u8]> {
+        let subdirectory = |entry: &(u32, u32)| (entry.1 & 0x7FFF_FFFF) as usize;
+        let types = self.directory(0)?;
+        let of_type = types.iter().find(|(t, _)| *t == resource_type)?;
+        let names = self.directory(subdirectory(of_type))?;
+        let name = match id {
+            Some(id) => names.iter().find(|(n, _)| *n == id)?,
+            None => names.first()?,
+        };
+        let languages = self.directory(subdirectory(name))?;
+        let (_, data_entry) = languages.first()?;
+        let data_entry = self.start + *data_entry as usize;
+        let rva = read_u32(self.file, data_entry)?;
+        let size = read_u32(self.file, data_entry + 4)? as usize;
+        let offset = self.rva_to_offset(rva)?;
+        self.file.get(offset..offset + size)
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SECTION_RVA: u32 = 0x1000;
+    const SECTION_OFFSET: usize = 0x200;
+
+    fn directory(res: &mut Vec<u8>, entries: &[(u32, u32)]) {
+        res.extend_from_slice(&[0; 12]);
+        res.extend_from_slice(&0u16.to_le_bytes());
+        res.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (id, offset) in entries {
+            res.extend_from_slice(&id.to_le_bytes());
+            res.extend_from_slice(&offset.to_le_bytes());
+        }
+    }
+
+    fn data_entry(res: &mut Vec<u8>, offset: usize, size: usize) {
+        res.extend_from_slice(&(SECTION_RVA + offset as u32).to_le_bytes());
+        res.extend_from_slice(&(size as u32).to_le_bytes());
+        res.extend_from_slice(&[0; 8]);
+    }
+
+    /// A minimal PE32 file with one icon group holding one icon.
+    fn executable_with_icon(image: &[u8]) -> Vec<u8> {
+        let mut group = vec![0, 0, 1, 0, 1, 0];
+        group.extend_from_slice(&[32, 32, 0, 0, 1, 0, 32, 0]);
+        group.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        group.extend_from_slice(&7u16.to_le_bytes());
+
+        let dir = 0x8000_0000;
+        let mut res = vec![];
+        directory(&mut res, &[(RT_ICON, dir | 32), (RT_GROUP_ICON, dir | 80)]);
+        directory(&mut res, &[(7, dir | 56)]);
+        directory(&mut res, &[(0x409, 128)]);
+        directory(&mut res, &[(1, dir | 104)]);
+        directory(&mut res, &[(0x409, 144)]);
+        data_entry(&mut res, 160, image.len());
+        data_entry(&mut res, 160 + image.len(), group.len());
+        res.extend_from_slice(image);
+        res.extend_from_slice(&group);
+
+        let mut file = vec![0; SECTION_OFFSET];
+        file[0..2].copy_from_slice(b"MZ");
+        file[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        file[0x40..0x44].copy_from_slice(b"PE\0\0");
+        file[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+        file[0x54..0x56].copy_from_slice(&224u16.to_le_bytes());
+        let optional_header = 0x58;
+        file[optional_header..optional_header + 2].copy_from_slice(&0x10bu16.to_le_bytes());
+        let resource_directory = optional_header + 96 + 16;
+        file[resource_directory..resource_directory + 4]
+            .copy_from_slice(&SECTION_RVA.to_le_bytes());
+        let section = optional_header + 224;
+        file[section + 8..section + 12].copy_from_slice(&(res.len() as u32).to_le_bytes());
+        file[section + 12..section + 16].copy_from_slice(&SECTION_RVA.to_le_bytes());
+        file[section + 16..section + 20].copy_from_slice(&(res.len() as u32).to_le_bytes());
+        file[section + 20..section + 24].copy_from_slice(&(SECTION_OFFSET as u32).to_le_bytes());
+        file.extend_from_slice(&res);
+        file
+    }
+
+    #[test]
+    fn extracts_icon_group_as_ico() {
+        let image = b"not really a bitmap";
+        let ico = icon_from_executable(&executable_with_icon(image)).unwrap();
+
+        assert_eq!(&[0, 0, 1, 0, 1, 0], &ico[0..6]);
+        assert_eq!(&[32, 32, 0, 0, 1, 0, 32, 0], &ico[6..14]);
+        assert_eq!(Some(image.len() as u32), read_u32(&ico, 14));
+        assert_eq!(Some(22), read_u32(&ico, 18));
+        assert_eq!(&image[..], &ico[22..]);
+    }
+
+    #[test]
+    fn malformed_sections_are_skipped() {
+        let resources = Resources {
+            file: &[],
+            sections: vec![
+                (0xFFFF_F000, 0x2000, 0),
+                (0x1000, 0x100, 0xFFFF_FFF0),
+                (0x2000, 0x100, 0x400),
+            ],
+            start: 0,
+        };
+        assert_eq!(None, resources.rva_to_offset(0xFFFF_F800));
+        assert_eq!(None, resources.rva_to_offset(0x1080));
+        assert_eq!(Some(0x410), resources.rva_to_offset(0x2010));
+
+        let mut file = executable_with_icon(b"image");
+        let size = 0x58 + 224 + 16;
+        file[size..size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(None, icon_from_executable(&file));
+    }
+
+    #[test]
+    fn no_icon_in_other_files() {
+        assert_eq!(None, icon_from_executable(b"MZ"));
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        assert_eq!(None, icon_from_executable(&content));
+    }
+}
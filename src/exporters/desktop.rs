@@ -0,0 +1,186 @@
+//! Exporting shortcuts as freedesktop `.desktop` launchers, so they show up in Linux application menus.
+
+use std::path::{Path, PathBuf};
+
+use crate::app_id_generator::calculate_game_id;
+use crate::launch_options::LaunchOptions;
+use crate::shortcut::Shortcut;
+
+/// How the exported launcher starts the shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesktopLaunch {
+    /// Run the exe of the shortcut with its launch options, without steam
+    Direct,
+    /// Let steam run the shortcut with a `steam://rungameid/<game id>` url
+    Steam,
+}
+
+/// Create the content of a `.desktop` launcher for the shortcut.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::exporters::desktop::{to_desktop_entry, DesktopLaunch};
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let shortcut = Shortcut::new("0", "Celeste", "\"/games/celeste\"", "", "", "", "");
+/// let entry = to_desktop_entry(&shortcut, DesktopLaunch::Direct);
+/// assert!(entry.contains("\nExec=/games/celeste\n"));
+/// ```
+pub fn to_desktop_entry(shortcut: &Shortcut, launch: DesktopLaunch) -> String {
+    let exec = match launch {
+        DesktopLaunch::Direct => direct_exec(shortcut),
+        DesktopLaunch::Steam => format!(
+            "steam steam://rungameid/{}",
+            calculate_game_id(shortcut.app_id)
+        ),
+    };
+    let mut entry = String::from("[Desktop Entry]\nType=Application\n");
+    entry.push_str(&format!("Name={}\n", escape_value(shortcut.app_name)));
+    entry.push_str(&format!("Exec={}\n", escape_value(&exec)));
+    let icon = unquote(shortcut.icon);
+    if !icon.is_empty() {
+        entry.push_str(&format!("Icon={}\n", escape_value(icon)));
+    }
+    let start_dir = unquote(shortcut.start_dir);
+    if launch == DesktopLaunch::Direct && !start_dir.is_empty() {
+        entry.push_str(&format!("Path={}\n", escape_value(start_dir)));
+    }
+    entry.push_str("Terminal=false\nCategories=Game;\n");
+    entry
+}
+
+/// Write a `.desktop` launcher for the shortcut into the given folder.
+///
+/// The file is named after the app id of the shortcut (`steam-shortcut-<app id>.desktop`),
+/// so exporting the same shortcut again replaces the old launcher. Returns the path of the file.
+pub fn write_desktop_entry(
+    shortcut: &Shortcut,
+    launch: DesktopLaunch,
+    dir: &Path,
+) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("steam-shortcut-{}.desktop", shortcut.app_id));
+    std::fs::write(&path, to_desktop_entry(shortcut, launch))?;
+    Ok(path)
+}
+
+/// The command line that runs the exe like steam does with the launch options.
+///
+/// With `%command%` the environment variables are set with `env` and the wrappers run the exe,
+/// otherwise the launch options are arguments of the exe.
+fn direct_exec(shortcut: &Shortcut) -> String {
+    let options = LaunchOptions::parse(shortcut.launch_options);
+    let mut parts = vec![];
+    if options.has_command {
+        if !options.env.is_empty() {
+            parts.push("env".to_string());
+        }
+        for (name, value) in options.env.iter() {
+            parts.push(quote_exec_arg(&format!("{}={}", name, unquote(value))));
+        }
+        parts.extend(options.wrapper.iter().map(|w| quote_exec_arg(unquote(w))));
+    }
+    parts.push(quote_exec_arg(unquote(shortcut.exe)));
+    parts.extend(options.args.iter().map(|arg| quote_exec_arg(unquote(arg))));
+    parts.join(" ")
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    if value.len() > 1 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+/// Quote an argument of Exec if it contains characters the desktop entry spec reserves.
+fn quote_exec_arg(arg: &str) -> String {
+    let reserved = |c: char| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c);
+    let arg = arg.replace('%', "%%");
+    if !arg.contains(reserved) {
+        return arg;
+    }
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        if "\"`$\\".contains(c) {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a value the way the desktop entry spec requires for string values.
+fn escape_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::importers::desktop::parse_desktop_entry;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn direct_launch_round_trips_through_importer() {
+        let shortcut = Shortcut::new(
+            "0",
+            "My Game",
+            "\"/opt/My Game/run.sh\"",
+            "\"/opt/My Game\"",
+            "/opt/My Game/icon.png",
+            "",
+            "--fullscreen",
+        );
+        let entry = to_desktop_entry(&shortcut, DesktopLaunch::Direct);
+        let imported = parse_desktop_entry(&entry).unwrap();
+        assert_eq!("My Game", imported.app_name);
+        assert_eq!(shortcut.app_id, imported.app_id);
+        assert_eq!("\"/opt/My Game/run.sh\"", imported.exe);
+        assert_eq!("\"/opt/My Game\"", imported.start_dir);
+        assert_eq!("--fullscreen", imported.launch_options);
+        assert_eq!("/opt/My Game/icon.png", imported.icon);
+    }
+
+    #[test]
+    fn direct_launch_runs_wrappers_and_env_vars() {
+        let mut shortcut = Shortcut::new("0", "Celeste", "\"/games/celeste\"", "", "", "", "");
+        shortcut.launch_options = "gamemoderun %command% -novid";
+        let entry = to_desktop_entry(&shortcut, DesktopLaunch::Direct);
+        assert!(entry.contains("\nExec=gamemoderun /games/celeste -novid\n"));
+
+        shortcut.launch_options = "MANGOHUD=1 DXVK_HUD=\"fps,memory\" %command%";
+        let entry = to_desktop_entry(&shortcut, DesktopLaunch::Direct);
+        assert!(entry.contains("\nExec=env MANGOHUD=1 DXVK_HUD=fps,memory /games/celeste\n"));
+    }
+
+    #[test]
+    fn steam_launch() {
+        let mut shortcut = Shortcut::new("0", "Celeste", "celeste", "", "", "", "");
+        shortcut.app_id = 2365067149;
+        let entry = to_desktop_entry(&shortcut, DesktopLaunch::Steam);
+        assert!(entry.contains("\nExec=steam steam://rungameid/10157886057832513536\n"));
+        assert!(!entry.contains("Path="));
+    }
+
+    #[test]
+    fn write_to_dir() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let shortcut = Shortcut::new("0", "Celeste", "celeste", "", "", "", "");
+        let path = write_desktop_entry(&shortcut, DesktopLaunch::Steam, dir).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            format!("steam-shortcut-{}.desktop", shortcut.app_id),
+            path.file_name().unwrap().to_string_lossy()
+        );
+        assert!(written.starts_with("[Desktop Entry]\n"));
+    }
+}
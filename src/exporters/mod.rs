@@ -0,0 +1,3 @@
+//! Writing shortcuts to other launcher formats.
+
+pub mod desktop;
@@ -0,0 +1,133 @@
+//! Filling in the icons that imports left empty or that point at deleted files.
+
+use std::path::Path;
+
+use crate::shortcut::ShortcutOwned;
+
+/// The file extensions of icons looked up in an [IconSource::Dir], in the order they are tried.
+const ICON_EXTENSIONS: [&str; 4] = ["ico", "png", "jpg", "jpeg"];
+
+/// Where [refresh_icons] looks for an icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSource<'a> {
+    /// The exe of the shortcut, if it is a Windows `.exe` that exists, steam shows its icon
+    Exe,
+    /// A file in this directory named after the app id or the app name of the shortcut, with
+    /// the extension `ico`, `png`, `jpg` or `jpeg`
+    Dir(&'a Path),
+}
+
+impl IconSource<'_> {
+    fn find(&self, shortcut: &ShortcutOwned) -> Option<String> {
+        match self {
+            IconSource::Exe => {
+                let exe = unquoted(&shortcut.exe);
+                let is_exe = Path::new(exe)
+                    .extension()
+                    .map(|e| e.eq_ignore_ascii_case("exe"))
+                    .unwrap_or_default();
+                if is_exe && Path::new(exe).is_file() {
+                    Some(exe.to_string())
+                } else {
+                    None
+                }
+            }
+            IconSource::Dir(dir) => [shortcut.app_id.to_string(), shortcut.app_name.clone()]
+                .iter()
+                .filter(|name| !name.is_empty())
+                .flat_map(|name| {
+                    ICON_EXTENSIONS
+                        .iter()
+                        .map(move |extension| dir.join(format!("{}.{}", name, extension)))
+                })
+                .find(|icon| icon.is_file())
+                .map(|icon| icon.to_string_lossy().to_string()),
+        }
+    }
+}
+
+/// An icon [refresh_icons] changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconChange {
+    /// The index of the shortcut in the list
+    pub index: usize,
+    /// The icon before, empty or a file that does not exist
+    pub old_icon: String,
+    /// The icon now
+    pub new_icon: String,
+}
+
+/// Set the icon of every shortcut whose icon is empty or a file that does not exist.
+///
+/// The sources are tried in order, the first icon found is used. Shortcuts for which no source
+/// has an icon are left as they are. Returns the icons that changed.
+///
+/// ### Examples
+/// ```no_run
+/// use std::path::Path;
+/// use steam_shortcuts_util::icons::{refresh_icons, IconSource};
+///
+/// # let mut shortcuts = vec![];
+/// let sources = [IconSource::Dir(Path::new("/home/me/icons")), IconSource::Exe];
+/// for change in refresh_icons(&mut shortcuts, &sources) {
+///     println!("{}: {} -> {}", change.index, change.old_icon, change.new_icon);
+/// }
+/// ```
+pub fn refresh_icons(shortcuts: &mut [ShortcutOwned], sources: &[IconSource]) -> Vec<IconChange> {
+    let mut changes = vec![];
+    for (index, shortcut) in shortcuts.iter_mut().enumerate() {
+        let icon = unquoted(&shortcut.icon);
+        if !icon.is_empty() && Path::new(icon).is_file() {
+            continue;
+        }
+        if let Some(new_icon) = sources.iter().find_map(|source| source.find(shortcut)) {
+            let old_icon = std::mem::replace(&mut shortcut.icon, new_icon.clone());
+            changes.push(IconChange {
+                index,
+                old_icon,
+                new_icon,
+            });
+        }
+    }
+    changes
+}
+
+fn unquoted(path: &str) -> &str {
+    path.trim().trim_matches('"')
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn fills_missing_icons() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let exe = dir.join("Game.EXE");
+        std::fs::write(&exe, b"").unwrap();
+        let exe = format!("\"{}\"", exe.display());
+        std::fs::write(dir.join("Celeste.png"), b"").unwrap();
+        let kept = dir.join("kept.ico").to_string_lossy().to_string();
+        std::fs::write(&kept, b"").unwrap();
+
+        let mut shortcuts = vec![
+            Shortcut::new("0", "Celeste", &exe, "", "/gone.ico", "", "").to_owned(),
+            Shortcut::new("1", "Hades", &exe, "", "", "", "").to_owned(),
+            Shortcut::new("2", "Spore", &exe, "", &kept, "", "").to_owned(),
+            Shortcut::new("3", "Linux", "/no/game", "", "", "", "").to_owned(),
+        ];
+        let changes = refresh_icons(&mut shortcuts, &[IconSource::Dir(dir), IconSource::Exe]);
+
+        assert_eq!(2, changes.len());
+        assert_eq!("/gone.ico", changes[0].old_icon);
+        assert!(shortcuts[0].icon.ends_with("Celeste.png"));
+        assert_eq!(1, changes[1].index);
+        assert!(shortcuts[1].icon.ends_with("Game.EXE"));
+        assert_eq!(kept, shortcuts[2].icon);
+        assert_eq!("", shortcuts[3].icon);
+    }
+}
@@ -0,0 +1,162 @@
+//! Importing the games installed with the Amazon Games launcher (needs the `sqlite` feature).
+//!
+//! The launcher keeps its installs in the `GameInstallInfo.sqlite` database. The shortcuts
+//! launch the games through the launcher with an `amazon-games://play/<id>` url.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::importers::quote;
+use crate::importers::url::UrlOpener;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A game installed with the Amazon Games launcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmazonGame {
+    /// The id of the game in the launcher
+    pub id: String,
+    /// The title of the game
+    pub title: String,
+    /// The folder the game is installed in
+    pub install_directory: String,
+}
+
+impl AmazonGame {
+    /// The url that makes the launcher start this game.
+    pub fn launch_url(&self) -> String {
+        format!("amazon-games://play/{}", self.id)
+    }
+
+    /// Create a shortcut that launches this game through the Amazon Games launcher.
+    ///
+    /// The icon is taken from the executable named in the `fuel.json` of the install, if any.
+    pub fn to_shortcut(&self, opener: &UrlOpener) -> ShortcutOwned {
+        let exe = quote(&opener.exe());
+        let start_dir = quote(&self.install_directory);
+        let icon = std::fs::read_to_string(Path::new(&self.install_directory).join("fuel.json"))
+            .ok()
+            .and_then(|fuel| main_command(&fuel))
+            .map(|command| {
+                Path::new(&self.install_directory)
+                    .join(command)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let launch_options = self.launch_url();
+        let shortcut = Shortcut::new(
+            "0",
+            &self.title,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// The location of the launcher's install database, if the launcher is installed.
+pub fn database_path() -> Option<PathBuf> {
+    let local_app_data = std::env::var_os("LOCALAPPDATA")?;
+    let path = Path::new(&local_app_data)
+        .join("Amazon Games")
+        .join("Data")
+        .join("Games")
+        .join("Sql")
+        .join("GameInstallInfo.sqlite");
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Read the installed games from the launcher's install database.
+pub fn installed_games(database: &Path) -> rusqlite::Result<Vec<AmazonGame>> {
+    let connection = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(
+        "SELECT Id, ProductTitle, InstallDirectory FROM DbSet \
+         WHERE Installed = 1 ORDER BY ProductTitle",
+    )?;
+    let games = statement
+        .query_map([], |row| {
+            Ok(AmazonGame {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                install_directory: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            })
+        })?
+        .collect();
+    games
+}
+
+/// Create shortcuts for the games installed with the Amazon Games launcher.
+pub fn installed_shortcuts(
+    database: &Path,
+    opener: &UrlOpener,
+) -> rusqlite::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games(database)?
+        .iter()
+        .map(|game| game.to_shortcut(opener))
+        .collect())
+}
+
+/// The executable in the `Main` section of a `fuel.json` file.
+fn main_command(fuel: &str) -> Option<String> {
+    let fuel = json::parse(fuel).ok()?;
+    let command = fuel.get("Main")?["Command"].as_str()?;
+    Some(command.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn read_database() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let install = dir.join("Tales");
+        std::fs::create_dir_all(&install).unwrap();
+        std::fs::write(
+            install.join("fuel.json"),
+            r#"{"SchemaVersion": "2", "Main": {"Command": "Tales.exe", "Args": []}}"#,
+        )
+        .unwrap();
+        let database = dir.join("GameInstallInfo.sqlite");
+        {
+            let connection = Connection::open(&database).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE DbSet (Id TEXT, ProductTitle TEXT, InstallDirectory TEXT, Installed INTEGER);
+                     INSERT INTO DbSet VALUES ('amzn1.adg.product.2', 'Uninstalled', NULL, 0);",
+                )
+                .unwrap();
+            connection
+                .execute(
+                    "INSERT INTO DbSet VALUES ('amzn1.adg.product.1', 'Tales', ?1, 1)",
+                    [install.to_string_lossy()],
+                )
+                .unwrap();
+        }
+        let opener = UrlOpener::Program("C:\\Windows\\explorer.exe".to_string());
+        let shortcuts = installed_shortcuts(&database, &opener);
+        let shortcuts = shortcuts.unwrap();
+        assert_eq!(1, shortcuts.len());
+        assert_eq!("Tales", shortcuts[0].app_name);
+        assert_eq!(
+            "amazon-games://play/amzn1.adg.product.1",
+            shortcuts[0].launch_options
+        );
+        assert_eq!(
+            install.join("Tales.exe").to_string_lossy(),
+            shortcuts[0].icon
+        );
+    }
+}
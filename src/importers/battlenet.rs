@@ -0,0 +1,252 @@
+//! Importing the games installed with Battle.net.
+//!
+//! The Battle.net agent keeps its installs in `product.db`, a protobuf file. The shortcuts
+//! launch the games through Battle.net with a `battlenet://<code>` url, where the code is the
+//! launch code Battle.net uses for the product (which differs from the code in `product.db`).
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::importers::url::UrlOpener;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The product code in `product.db`, the launch code and the name of known games.
+const KNOWN_PRODUCTS: &[(&str, &str, &str)] = &[
+    ("anbs", "ANBS", "Diablo Immortal"),
+    ("auks", "AUKS", "Call of Duty"),
+    ("d3", "D3", "Diablo III"),
+    ("fenris", "Fen", "Diablo IV"),
+    ("fore", "FORE", "Call of Duty: Vanguard"),
+    ("gryphon", "GRY", "Warcraft Rumble"),
+    ("hero", "Hero", "Heroes of the Storm"),
+    ("hs_beta", "WTCG", "Hearthstone"),
+    (
+        "lazarus",
+        "LAZR",
+        "Call of Duty: Modern Warfare 2 Campaign Remastered",
+    ),
+    ("odin", "ODIN", "Call of Duty: Modern Warfare"),
+    ("osi", "OSI", "Diablo II: Resurrected"),
+    ("pro", "Pro", "Overwatch 2"),
+    ("rtro", "RTRO", "Blizzard Arcade Collection"),
+    ("s1", "S1", "StarCraft"),
+    ("s2", "S2", "StarCraft II"),
+    ("viper", "VIPR", "Call of Duty: Black Ops 4"),
+    ("w3", "W3", "Warcraft III: Reforged"),
+    ("wlby", "WLBY", "Crash Bandicoot 4: It's About Time"),
+    ("wow", "WoW", "World of Warcraft"),
+    ("wow_classic", "WoWC", "World of Warcraft Classic"),
+    ("zeus", "ZEUS", "Call of Duty: Black Ops Cold War"),
+];
+
+/// Products in `product.db` that are parts of Battle.net itself.
+const LAUNCHER_PRODUCTS: &[&str] = &["agent", "battle.net", "bna"];
+
+/// A game installed with Battle.net.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattleNetGame {
+    /// The code of the product in `product.db`, e.g. `pro`
+    pub product_code: String,
+    /// The code Battle.net launches the product with, e.g. `Pro`
+    pub launch_code: String,
+    /// The name of the game
+    pub name: String,
+    /// The folder the game is installed in
+    pub install_path: String,
+}
+
+impl BattleNetGame {
+    /// The url that makes Battle.net start this game.
+    pub fn launch_url(&self) -> String {
+        format!("battlenet://{}", self.launch_code)
+    }
+
+    /// Create a shortcut that launches this game through Battle.net.
+    pub fn to_shortcut(&self, opener: &UrlOpener) -> ShortcutOwned {
+        let exe = quote(&opener.exe());
+        let start_dir = quote(&self.install_path);
+        let launch_options = self.launch_url();
+        let shortcut = Shortcut::new("0", &self.name, &exe, &start_dir, "", "", &launch_options);
+        shortcut.to_owned()
+    }
+}
+
+/// The location of Battle.net's `product.db`, if it exists.
+pub fn product_db_path() -> Option<PathBuf> {
+    let program_data = std::env::var_os("ProgramData")?;
+    let path = Path::new(&program_data)
+        .join("Battle.net")
+        .join("Agent")
+        .join("product.db");
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Read the installed games from `product.db`.
+pub fn installed_games(product_db: &Path) -> std::io::Result<Vec<BattleNetGame>> {
+    let bytes = std::fs::read(product_db)?;
+    parse_product_db(&bytes)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid product.db"))
+}
+
+/// Create shortcuts for the games installed with Battle.net.
+pub fn installed_shortcuts(
+    product_db: &Path,
+    opener: &UrlOpener,
+) -> std::io::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games(product_db)?
+        .iter()
+        .map(|game| game.to_shortcut(opener))
+        .collect())
+}
+
+/// Parse the bytes of `product.db`.
+///
+/// Battle.net components are skipped. Unknown products are kept, launched with their
+/// product code in upper case and named after it.
+pub fn parse_product_db(bytes: &[u8]) -> Option<Vec<BattleNetGame>> {
+    let mut games = vec![];
+    // Database { repeated ProductInstall product_install = 1; ... }
+    for (field, value) in fields(bytes)? {
+        let install = match (field, value) {
+            (1, Value::Bytes(install)) => install,
+            _ => continue,
+        };
+        // ProductInstall { string uid = 1; string product_code = 2; UserSettings settings = 3; ... }
+        let mut product_code = String::new();
+        let mut install_path = String::new();
+        for (field, value) in fields(install)? {
+            match (field, value) {
+                (2, Value::Bytes(code)) => product_code = String::from_utf8_lossy(code).to_string(),
+                (3, Value::Bytes(settings)) => {
+                    // UserSettings { string install_path = 1; ... }
+                    for (field, value) in fields(settings)? {
+                        if let (1, Value::Bytes(path)) = (field, value) {
+                            install_path = String::from_utf8_lossy(path).to_string();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if product_code.is_empty() || LAUNCHER_PRODUCTS.contains(&product_code.as_str()) {
+            continue;
+        }
+        let (launch_code, name) = KNOWN_PRODUCTS
+            .iter()
+            .find(|(code, _, _)| *code == product_code)
+            .map(|(_, launch_code, name)| (launch_code.to_string(), name.to_string()))
+            .unwrap_or_else(|| (product_code.to_uppercase(), product_code.clone()));
+        games.push(BattleNetGame {
+            product_code,
+            launch_code,
+            name,
+            install_path,
+        });
+    }
+    Some(games)
+}
+
+enum Value<'a> {
+    Varint,
+    Fixed,
+    Bytes(&'a [u8]),
+}
+
+/// Split a protobuf message into its fields, returns `None` if it is malformed.
+fn fields(mut bytes: &[u8]) -> Option<Vec<(u64, Value<'_>)>> {
+    let mut res = vec![];
+    while !bytes.is_empty() {
+        let key = read_varint(&mut bytes)?;
+        let value = match key & 0x7 {
+            0 => {
+                read_varint(&mut bytes)?;
+                Value::Varint
+            }
+            1 => {
+                bytes = bytes.get(8..)?;
+                Value::Fixed
+            }
+            2 => {
+                let len = read_varint(&mut bytes)? as usize;
+                let value = bytes.get(..len)?;
+                bytes = &bytes[len..];
+                Value::Bytes(value)
+            }
+            5 => {
+                bytes = bytes.get(4..)?;
+                Value::Fixed
+            }
+            _ => return None,
+        };
+        res.push((key >> 3, value));
+    }
+    Some(res)
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn length_delimited(res: &mut Vec<u8>, field: u8, value: &[u8]) {
+        res.push(field << 3 | 2);
+        res.push(value.len() as u8);
+        res.extend_from_slice(value);
+    }
+
+    fn product_install(code: &str, path: &str) -> Vec<u8> {
+        let mut settings = vec![];
+        length_delimited(&mut settings, 1, path.as_bytes());
+        // A varint field that should be skipped
+        settings.extend_from_slice(&[2 << 3, 0x96, 0x01]);
+        let mut install = vec![];
+        length_delimited(&mut install, 1, code.as_bytes());
+        length_delimited(&mut install, 2, code.as_bytes());
+        length_delimited(&mut install, 3, &settings);
+        install
+    }
+
+    #[test]
+    fn parse_db() {
+        let mut db = vec![];
+        length_delimited(&mut db, 1, &product_install("pro", "C:/Games/Overwatch"));
+        length_delimited(
+            &mut db,
+            1,
+            &product_install("agent", "C:/ProgramData/Battle.net"),
+        );
+        length_delimited(&mut db, 1, &product_install("newgame", "C:/Games/New"));
+        let games = parse_product_db(&db).unwrap();
+        assert_eq!(2, games.len());
+        assert_eq!("Overwatch 2", games[0].name);
+        assert_eq!("battlenet://Pro", games[0].launch_url());
+        assert_eq!("C:/Games/Overwatch", games[0].install_path);
+        assert_eq!("battlenet://NEWGAME", games[1].launch_url());
+
+        let shortcut = games[0].to_shortcut(&UrlOpener::Program("explorer.exe".to_string()));
+        assert_eq!("battlenet://Pro", shortcut.launch_options);
+        assert_eq!("\"C:/Games/Overwatch\"", shortcut.start_dir);
+    }
+
+    #[test]
+    fn malformed_db() {
+        assert_eq!(None, parse_product_db(&[1 << 3 | 2, 10, 1]));
+    }
+}
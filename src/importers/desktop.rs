@@ -0,0 +1,316 @@
+//! Importing freedesktop `.desktop` launchers, as used by Linux application menus.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// Read a `.desktop` file and create a shortcut for it.
+///
+/// Returns `None` if the file is not an application launcher, or is marked as hidden.
+pub fn from_desktop_entry(path: &Path) -> std::io::Result<Option<ShortcutOwned>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(parse_desktop_entry(&content))
+}
+
+/// Create shortcuts for all `.desktop` files in a folder and its subfolders.
+///
+/// Files that can not be read or are not application launchers are skipped.
+pub fn from_desktop_entries_in_dir(dir: &Path) -> Vec<ShortcutOwned> {
+    let mut files = vec![];
+    collect_desktop_files(dir, &mut files);
+    files.sort();
+    files
+        .iter()
+        .filter_map(|file| from_desktop_entry(file).ok().flatten())
+        .collect()
+}
+
+/// The folders application launchers are usually installed in, that exist on this machine.
+pub fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(data_home) = std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        dirs.push(PathBuf::from(data_home));
+    } else if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".local/share"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    dirs.extend(data_dirs.split(':').map(PathBuf::from));
+    dirs.into_iter()
+        .map(|dir| dir.join("applications"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Create a shortcut from the content of a `.desktop` file.
+///
+/// The Name, Exec, Icon and Path keys of the `[Desktop Entry]` group are used.
+/// Field codes like `%U` are removed from Exec, the program becomes the exe and
+/// the rest of the command becomes the launch options.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::importers::desktop::parse_desktop_entry;
+///
+/// let entry = "[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox %u\n";
+/// let shortcut = parse_desktop_entry(entry).unwrap();
+/// assert_eq!("Firefox", shortcut.app_name);
+/// assert_eq!("\"firefox\"", shortcut.exe);
+/// ```
+pub fn parse_desktop_entry(content: &str) -> Option<ShortcutOwned> {
+    let entry = desktop_entry_group(content);
+    let get = |key: &str| {
+        entry
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    if get("Type").unwrap_or("Application") != "Application"
+        || get("Hidden") == Some("true")
+        || get("NoDisplay") == Some("true")
+    {
+        return None;
+    }
+    let name = get("Name")?;
+    let icon = get("Icon").map(resolve_icon).unwrap_or_default();
+    let mut args = split_exec(&expand_field_codes(get("Exec")?, name, &icon));
+    if args.is_empty() {
+        return None;
+    }
+    let program = args.remove(0);
+    let launch_options = args
+        .iter()
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let start_dir = match get("Path") {
+        Some(path) if !path.is_empty() => path.to_string(),
+        _ => Path::new(&program)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    let exe = quote(&program);
+    let start_dir = quote(&start_dir);
+    let shortcut = Shortcut::new("0", name, &exe, &start_dir, &icon, "", &launch_options);
+    Some(shortcut.to_owned())
+}
+
+fn collect_desktop_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_desktop_files(&path, files);
+        } else if path.extension().map(|e| e == "desktop").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+}
+
+/// The keys and (unescaped) values of the `[Desktop Entry]` group.
+fn desktop_entry_group(content: &str) -> Vec<(String, String)> {
+    let mut in_group = false;
+    let mut entries = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_group || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            entries.push((key.trim().to_string(), unescape_value(value.trim())));
+        }
+    }
+    entries
+}
+
+fn unescape_value(value: &str) -> String {
+    let mut res = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => res.push(' '),
+            Some('n') => res.push('\n'),
+            Some('t') => res.push('\t'),
+            Some('r') => res.push('\r'),
+            Some('\\') => res.push('\\'),
+            Some(other) => {
+                res.push('\\');
+                res.push(other);
+            }
+            None => res.push('\\'),
+        }
+    }
+    res
+}
+
+fn expand_field_codes(exec: &str, name: &str, icon: &str) -> String {
+    let mut res = String::new();
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => res.push('%'),
+            Some('c') => res.push_str(&quote_arg(name)),
+            Some('i') if !icon.is_empty() => {
+                res.push_str("--icon ");
+                res.push_str(&quote_arg(icon));
+            }
+            // Files, urls and deprecated codes have nothing to expand to
+            _ => {}
+        }
+    }
+    res
+}
+
+/// Split an Exec value into arguments, following the quoting rules of the desktop entry spec.
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quotes = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                in_arg = true;
+            }
+            '\\' if in_quotes => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c.is_whitespace() || c == '"') {
+        arg.to_string()
+    } else {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Find the file of an icon given by name, or return the name as is if it can not be found.
+fn resolve_icon(icon: &str) -> String {
+    if icon.is_empty() || Path::new(icon).is_absolute() {
+        return icon.to_string();
+    }
+    let sizes = ["256x256", "128x128", "96x96", "64x64", "48x48", "32x32"];
+    let mut candidates = vec![];
+    for dir in ["/usr/share/icons/hicolor", "/usr/local/share/icons/hicolor"] {
+        for size in sizes.iter() {
+            candidates.push(
+                Path::new(dir)
+                    .join(size)
+                    .join("apps")
+                    .join(format!("{}.png", icon)),
+            );
+        }
+    }
+    candidates.push(Path::new("/usr/share/pixmaps").join(format!("{}.png", icon)));
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .map(|found| found.to_string_lossy().to_string())
+        .unwrap_or_else(|| icon.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn parse_entry_with_field_codes() {
+        let content = r#"[Desktop Entry]
+Version=1.0
+Type=Application
+Name=My Game
+Name[de]=Mein Spiel
+Exec="/opt/My Game/run.sh" --fullscreen %U
+Icon=/opt/My Game/icon.png
+Path=/opt/My Game
+
+[Desktop Action new-window]
+Name=Other
+Exec=other
+"#;
+        let shortcut = parse_desktop_entry(content).unwrap();
+        assert_eq!("My Game", shortcut.app_name);
+        assert_eq!("\"/opt/My Game/run.sh\"", shortcut.exe);
+        assert_eq!("--fullscreen", shortcut.launch_options);
+        assert_eq!("\"/opt/My Game\"", shortcut.start_dir);
+        assert_eq!("/opt/My Game/icon.png", shortcut.icon);
+        assert!(shortcut.borrow().app_id_matches_calculation());
+    }
+
+    #[test]
+    fn start_dir_defaults_to_exe_dir() {
+        let content = "[Desktop Entry]\nName=Game\nExec=/usr/games/game --name %c %%\n";
+        let shortcut = parse_desktop_entry(content).unwrap();
+        assert_eq!("\"/usr/games\"", shortcut.start_dir);
+        assert_eq!("--name Game %", shortcut.launch_options);
+    }
+
+    #[test]
+    fn skips_hidden_and_non_applications() {
+        let hidden = "[Desktop Entry]\nName=Game\nExec=game\nNoDisplay=true\n";
+        let link = "[Desktop Entry]\nType=Link\nName=Site\nURL=https://example.com\n";
+        assert!(parse_desktop_entry(hidden).is_none());
+        assert!(parse_desktop_entry(link).is_none());
+    }
+
+    #[test]
+    fn scan_dir() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("games")).unwrap();
+        std::fs::write(dir.join("a.desktop"), "[Desktop Entry]\nName=A\nExec=a\n").unwrap();
+        std::fs::write(
+            dir.join("games/b.desktop"),
+            "[Desktop Entry]\nName=B\nExec=b\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "[Desktop Entry]\nName=C\nExec=c\n").unwrap();
+        let shortcuts = from_desktop_entries_in_dir(dir);
+        let names: Vec<&str> = shortcuts.iter().map(|s| s.app_name.as_str()).collect();
+        assert_eq!(vec!["A", "B"], names);
+    }
+}
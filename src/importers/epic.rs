@@ -0,0 +1,197 @@
+//! Importing the games installed with the Epic Games Launcher (requires the `json` feature).
+//!
+//! The launcher writes a JSON `.item` manifest for every install. The shortcuts launch
+//! the games through the launcher with a `com.epicgames.launcher://apps/<id>?action=launch` url.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::importers::url::UrlOpener;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A game installed with the Epic Games Launcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpicManifest {
+    /// The id of the game in the launcher
+    pub app_name: String,
+    /// The display name of the game
+    pub display_name: String,
+    /// The folder the game is installed in
+    pub install_location: String,
+    /// The executable of the game, relative to the install location
+    pub launch_executable: String,
+}
+
+impl EpicManifest {
+    /// The url that makes the launcher start this game.
+    pub fn launch_url(&self) -> String {
+        format!(
+            "com.epicgames.launcher://apps/{}?action=launch&silent=true",
+            self.app_name
+        )
+    }
+
+    /// Create a shortcut that launches this game through the Epic Games Launcher.
+    ///
+    /// The install location is used as start dir and the game executable as icon.
+    pub fn to_shortcut(&self, opener: &UrlOpener) -> ShortcutOwned {
+        let exe = quote(&opener.exe());
+        let start_dir = quote(&self.install_location);
+        let icon = if self.launch_executable.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{}\\{}",
+                self.install_location.trim_end_matches('\\'),
+                self.launch_executable.replace('/', "\\")
+            )
+        };
+        let launch_options = self.launch_url();
+        let shortcut = Shortcut::new(
+            "0",
+            &self.display_name,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// The folder the Epic Games Launcher keeps its install manifests in.
+#[cfg(windows)]
+pub fn manifests_dir() -> Option<PathBuf> {
+    let program_data = std::env::var_os("ProgramData")?;
+    let dir = Path::new(&program_data).join("Epic\\EpicGamesLauncher\\Data\\Manifests");
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+/// Read all `.item` manifests in a folder.
+///
+/// Manifests that can not be read, DLCs and unfinished installs are skipped.
+pub fn manifests_in_dir(dir: &Path) -> std::io::Result<Vec<EpicManifest>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|e| e.eq_ignore_ascii_case("item"))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    Ok(files
+        .iter()
+        .filter_map(|file| std::fs::read(file).ok())
+        .filter_map(|content| parse_manifest(&String::from_utf8_lossy(&content)))
+        .collect())
+}
+
+/// Create shortcuts for all games with a manifest in the given folder.
+pub fn shortcuts_from_manifests(
+    dir: &Path,
+    opener: &UrlOpener,
+) -> std::io::Result<Vec<ShortcutOwned>> {
+    Ok(manifests_in_dir(dir)?
+        .iter()
+        .map(|manifest| manifest.to_shortcut(opener))
+        .collect())
+}
+
+/// Parse the content of an `.item` manifest.
+///
+/// Returns `None` if it is not a valid manifest, is a DLC or the install is incomplete.
+pub fn parse_manifest(content: &str) -> Option<EpicManifest> {
+    let manifest = json::parse(content).ok()?;
+    let app_name = manifest["AppName"].as_str()?;
+    if manifest["bIsIncompleteInstall"].as_bool().unwrap_or(false) {
+        return None;
+    }
+    match manifest["MainGameAppName"].as_str() {
+        Some(main_game) if !main_game.is_empty() && main_game != app_name => return None,
+        _ => {}
+    }
+    Some(EpicManifest {
+        app_name: app_name.to_string(),
+        display_name: manifest["DisplayName"]
+            .as_str()
+            .unwrap_or(app_name)
+            .to_string(),
+        install_location: manifest["InstallLocation"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        launch_executable: manifest["LaunchExecutable"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const MANIFEST: &str = r#"{
+        "FormatVersion": 0,
+        "bIsIncompleteInstall": false,
+        "LaunchExecutable": "IdleDragons.exe",
+        "DisplayName": "Idle Champions of the Forgotten Realms",
+        "InstallLocation": "F:\\EpicExtra\\IdleChampions",
+        "CatalogNamespace": "7e508f543b05465abe3a935960eb70ac",
+        "AppName": "40cb42e38c0b4a14a1bb133eb3291572",
+        "MainGameAppName": "40cb42e38c0b4a14a1bb133eb3291572"
+    }"#;
+
+    #[test]
+    fn parse_item() {
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        let opener = UrlOpener::Program("C:\\Windows\\explorer.exe".to_string());
+        let shortcut = manifest.to_shortcut(&opener);
+        assert_eq!("Idle Champions of the Forgotten Realms", shortcut.app_name);
+        assert_eq!("\"C:\\Windows\\explorer.exe\"", shortcut.exe);
+        assert_eq!("\"F:\\EpicExtra\\IdleChampions\"", shortcut.start_dir);
+        assert_eq!(
+            "F:\\EpicExtra\\IdleChampions\\IdleDragons.exe",
+            shortcut.icon
+        );
+        assert_eq!(
+            "com.epicgames.launcher://apps/40cb42e38c0b4a14a1bb133eb3291572?action=launch&silent=true",
+            shortcut.launch_options
+        );
+    }
+
+    #[test]
+    fn skips_dlc_and_incomplete() {
+        let dlc = MANIFEST.replace(
+            "\"MainGameAppName\": \"40cb42e38c0b4a14a1bb133eb3291572\"",
+            "\"MainGameAppName\": \"other\"",
+        );
+        let incomplete = MANIFEST.replace(
+            "\"bIsIncompleteInstall\": false",
+            "\"bIsIncompleteInstall\": true",
+        );
+        assert_eq!(None, parse_manifest(&dlc));
+        assert_eq!(None, parse_manifest(&incomplete));
+        assert_eq!(None, parse_manifest("not json"));
+    }
+
+    #[test]
+    fn read_manifests_dir() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        std::fs::write(dir.join("A1B2.item"), MANIFEST).unwrap();
+        std::fs::write(dir.join("broken.item"), "{").unwrap();
+        let manifests = manifests_in_dir(dir);
+        assert_eq!(1, manifests.unwrap().len());
+    }
+}
@@ -0,0 +1,100 @@
+//! Importing installed Flatpak applications (Linux only).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+const FLATPAK_EXE: &str = "\"/usr/bin/flatpak\"";
+
+/// Create shortcuts for the Flatpak applications installed on this machine.
+///
+/// Runs `flatpak list` to find the applications, so Flatpak must be installed.
+pub fn installed_flatpaks() -> std::io::Result<Vec<ShortcutOwned>> {
+    let output = Command::new("flatpak")
+        .args(["list", "--app", "--columns=application,name"])
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(parse_flatpak_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Create shortcuts from the output of `flatpak list --app --columns=application,name`.
+pub fn parse_flatpak_list(output: &str) -> Vec<ShortcutOwned> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            let app_id = columns.next()?.trim();
+            let name = columns.next().map(|n| n.trim()).unwrap_or(app_id);
+            if app_id.is_empty() {
+                None
+            } else {
+                Some(flatpak_shortcut(app_id, name))
+            }
+        })
+        .collect()
+}
+
+/// Create a shortcut that runs the Flatpak app with the given id, the way steam does.
+///
+/// The exe is `flatpak`, the launch options are `run <app id>` and the FlatpakAppID field is set.
+pub fn flatpak_shortcut(app_id: &str, name: &str) -> ShortcutOwned {
+    let launch_options = format!("run {}", app_id);
+    let icon = exported_icon(app_id)
+        .map(|icon| icon.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut shortcut = Shortcut::new("0", name, FLATPAK_EXE, "", &icon, "", &launch_options);
+    shortcut.flatpak_app_id = app_id;
+    shortcut.to_owned()
+}
+
+fn exported_icon(app_id: &str) -> Option<PathBuf> {
+    let mut exports = vec![PathBuf::from(
+        "/var/lib/flatpak/exports/share/icons/hicolor",
+    )];
+    if let Some(home) = std::env::var_os("HOME") {
+        exports.insert(
+            0,
+            Path::new(&home).join(".local/share/flatpak/exports/share/icons/hicolor"),
+        );
+    }
+    let sizes = ["256x256", "128x128", "64x64", "48x48"];
+    exports
+        .iter()
+        .flat_map(|dir| {
+            sizes
+                .iter()
+                .map(move |size| dir.join(size).join("apps").join(format!("{}.png", app_id)))
+        })
+        .find(|icon| icon.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_list_output() {
+        let output = "org.libretro.RetroArch\tRetroArch\nnet.lutris.Lutris\tLutris\n\n";
+        let shortcuts = parse_flatpak_list(output);
+        assert_eq!(2, shortcuts.len());
+        let retroarch = &shortcuts[0];
+        assert_eq!("RetroArch", retroarch.app_name);
+        assert_eq!("\"/usr/bin/flatpak\"", retroarch.exe);
+        assert_eq!("run org.libretro.RetroArch", retroarch.launch_options);
+        assert_eq!("org.libretro.RetroArch", retroarch.flatpak_app_id);
+    }
+
+    #[test]
+    fn flatpak_app_id_survives_writing() {
+        let shortcut = flatpak_shortcut("net.lutris.Lutris", "Lutris");
+        let bytes = crate::shortcuts_to_bytes(&vec![shortcut.borrow()]);
+        let parsed = crate::parse_shortcuts(bytes.as_slice()).unwrap();
+        assert_eq!("net.lutris.Lutris", parsed[0].flatpak_app_id);
+    }
+}
@@ -0,0 +1,214 @@
+//! Importing games from EmulationStation / ES-DE `gamelist.xml` files.
+//!
+//! The roms in a gamelist are launched through an emulator command template, in which
+//! these variables are replaced (like in EmulationStation's `es_systems.xml`):
+//!
+//! - `%ROM%`: the quoted path of the rom
+//! - `%ROMRAW%`: the path of the rom without quotes
+//! - `%BASENAME%`: the file name of the rom without extension
+//! - `%EMULATOR%`: the emulator chosen for the game in the gamelist (may be empty)
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The command games in a gamelist are launched with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmulatorCommand {
+    /// The program to start
+    pub exe: String,
+    /// The arguments passed to the program, with the variables to replace
+    pub args: String,
+}
+
+impl EmulatorCommand {
+    /// Create a command template.
+    pub fn new(exe: &str, args: &str) -> Self {
+        Self {
+            exe: exe.to_string(),
+            args: args.to_string(),
+        }
+    }
+
+    fn expand(&self, rom: &Path, emulator: &str) -> String {
+        let rom_raw = rom.to_string_lossy();
+        let base_name = rom
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.args
+            .replace("%ROMRAW%", &rom_raw)
+            .replace("%ROM%", &format!("\"{}\"", rom_raw))
+            .replace("%BASENAME%", &base_name)
+            .replace("%EMULATOR%", emulator)
+    }
+}
+
+/// A game of a gamelist.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamelistGame {
+    /// The shortcut that launches the game with the emulator command
+    pub shortcut: ShortcutOwned,
+    /// The path of the rom
+    pub rom: PathBuf,
+    /// The image of the game, if the gamelist has one
+    pub image: Option<PathBuf>,
+    /// The emulator chosen for the game, if the gamelist has one
+    pub emulator: Option<String>,
+}
+
+/// Read a `gamelist.xml` file and create a shortcut for each game.
+///
+/// Relative paths in the gamelist are resolved against `rom_dir`, the folder of the system's roms.
+pub fn from_gamelist_file(
+    gamelist: &Path,
+    rom_dir: &Path,
+    command: &EmulatorCommand,
+) -> std::io::Result<Vec<GamelistGame>> {
+    let content = std::fs::read_to_string(gamelist)?;
+    Ok(parse_gamelist(&content, rom_dir, command))
+}
+
+/// Create a shortcut for each game in the content of a `gamelist.xml` file.
+///
+/// Games without a path and hidden games are skipped, games without a name are named after their rom.
+/// The image of a game is used as the icon of its shortcut.
+pub fn parse_gamelist(
+    content: &str,
+    rom_dir: &Path,
+    command: &EmulatorCommand,
+) -> Vec<GamelistGame> {
+    let exe = quote(&command.exe);
+    let mut games = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find("<game>").or_else(|| rest.find("<game ")) {
+        rest = &rest[start..];
+        let end = rest.find("</game>").unwrap_or(rest.len());
+        let game = &rest[..end];
+        rest = &rest[end..];
+
+        let path = match element(game, "path") {
+            Some(path) if !path.is_empty() => path,
+            _ => continue,
+        };
+        if element(game, "hidden").as_deref() == Some("true") {
+            continue;
+        }
+        let rom = resolve(rom_dir, &path);
+        let name = element(game, "name")
+            .filter(|name| !name.is_empty())
+            .or_else(|| {
+                rom.file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+            })
+            .unwrap_or(path);
+        let image = element(game, "image")
+            .filter(|image| !image.is_empty())
+            .map(|image| resolve(rom_dir, &image));
+        let emulator = element(game, "altemulator")
+            .or_else(|| element(game, "emulator"))
+            .filter(|emulator| !emulator.is_empty());
+
+        let launch_options = command.expand(&rom, emulator.as_deref().unwrap_or_default());
+        let start_dir = quote(&rom_dir.to_string_lossy());
+        let icon = image
+            .as_ref()
+            .map(|image| image.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let shortcut = Shortcut::new("0", &name, &exe, &start_dir, &icon, "", &launch_options);
+        games.push(GamelistGame {
+            shortcut: shortcut.to_owned(),
+            rom,
+            image,
+            emulator,
+        });
+    }
+    games
+}
+
+fn resolve(rom_dir: &Path, path: &str) -> PathBuf {
+    match path.strip_prefix("./") {
+        Some(relative) => rom_dir.join(relative),
+        None => rom_dir.join(path),
+    }
+}
+
+/// The unescaped text of the first element with the given name.
+fn element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&format!("</{}>", name))? + start;
+    Some(unescape(xml[start..end].trim()))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const GAMELIST: &str = r#"<?xml version="1.0"?>
+<gameList>
+	<game>
+		<path>./Super Mario World.sfc</path>
+		<name>Super Mario World</name>
+		<image>./images/Super Mario World-image.png</image>
+		<altemulator>Snes9x</altemulator>
+	</game>
+	<game>
+		<path>./Zelda &amp; Friends.sfc</path>
+		<name></name>
+	</game>
+	<game>
+		<path>./bios.sfc</path>
+		<name>Bios</name>
+		<hidden>true</hidden>
+	</game>
+	<folder><path>./subfolder</path></folder>
+</gameList>"#;
+
+    #[test]
+    fn parse_games() {
+        let command = EmulatorCommand::new("/usr/bin/retroarch", "-L %EMULATOR% %ROM%");
+        let games = parse_gamelist(GAMELIST, Path::new("/roms/snes"), &command);
+        assert_eq!(2, games.len());
+
+        let mario = &games[0];
+        assert_eq!("Super Mario World", mario.shortcut.app_name);
+        assert_eq!("\"/usr/bin/retroarch\"", mario.shortcut.exe);
+        assert_eq!("\"/roms/snes\"", mario.shortcut.start_dir);
+        assert_eq!(
+            "-L Snes9x \"/roms/snes/Super Mario World.sfc\"",
+            mario.shortcut.launch_options
+        );
+        assert_eq!(
+            Some(PathBuf::from(
+                "/roms/snes/images/Super Mario World-image.png"
+            )),
+            mario.image
+        );
+        assert_eq!(Some("Snes9x".to_string()), mario.emulator);
+
+        let zelda = &games[1];
+        assert_eq!("Zelda & Friends", zelda.shortcut.app_name);
+        assert_eq!(None, zelda.image);
+    }
+
+    #[test]
+    fn template_variables() {
+        let command = EmulatorCommand::new("emu", "--name %BASENAME% --raw %ROMRAW%");
+        let games = parse_gamelist(GAMELIST, Path::new("/roms/snes"), &command);
+        assert_eq!(
+            "--name Super Mario World --raw /roms/snes/Super Mario World.sfc",
+            games[0].shortcut.launch_options
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Importing games installed with GOG Galaxy or the GOG offline installers
+//! (requires the `json` feature).
+//!
+//! Every GOG install has a `goggame-<id>.info` JSON file in its folder that names the
+//! game and the tasks to play it. On Windows the install folders are found in the registry.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A game installed from GOG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GogGame {
+    /// The GOG id of the game
+    pub game_id: String,
+    /// The display name of the game
+    pub name: String,
+    /// The folder the game is installed in
+    pub install_dir: PathBuf,
+    /// The primary play task of the game
+    pub exe: PathBuf,
+    /// The folder the game is started in
+    pub working_dir: PathBuf,
+    /// The arguments passed to the game
+    pub arguments: String,
+    /// The `goggame-<id>.ico` icon of the game, if it exists
+    pub icon: Option<PathBuf>,
+}
+
+impl GogGame {
+    /// Create a shortcut that starts the game executable directly.
+    pub fn to_shortcut(&self) -> ShortcutOwned {
+        let exe = quote(&self.exe.to_string_lossy());
+        let start_dir = quote(&self.working_dir.to_string_lossy());
+        let icon = self
+            .icon
+            .as_ref()
+            .unwrap_or(&self.exe)
+            .to_string_lossy()
+            .to_string();
+        let shortcut = Shortcut::new(
+            "0",
+            &self.name,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &self.arguments,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// The folders GOG Galaxy installed games in, read from the registry.
+#[cfg(windows)]
+pub fn install_dirs() -> Vec<PathBuf> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let games = match RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey("SOFTWARE\\WOW6432Node\\GOG.com\\Games")
+    {
+        Ok(games) => games,
+        Err(_) => return vec![],
+    };
+    games
+        .enum_keys()
+        .filter_map(|key| key.ok())
+        .filter_map(|key| games.open_subkey(key).ok())
+        .filter_map(|game| game.get_value::<String, _>("path").ok())
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Read the game installed in the given folder from its `goggame-<id>.info` file.
+///
+/// Returns `None` if there is no info file for a game with a play task.
+pub fn game_in_dir(install_dir: &Path) -> std::io::Result<Option<GogGame>> {
+    let mut info_files: Vec<PathBuf> = std::fs::read_dir(install_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with("goggame-") && name.ends_with(".info")
+        })
+        .collect();
+    info_files.sort();
+    for file in info_files {
+        let content = String::from_utf8_lossy(&std::fs::read(&file)?).to_string();
+        if let Some(game) = parse_game_info(&content, install_dir) {
+            return Ok(Some(game));
+        }
+    }
+    Ok(None)
+}
+
+/// Create shortcuts for the games installed in the given folders and their direct subfolders.
+///
+/// This finds both single installs (`C:\GOG Games\Celeste`) and library folders (`C:\GOG Games`).
+pub fn shortcuts_from_dirs(dirs: &[PathBuf]) -> Vec<ShortcutOwned> {
+    let mut candidates = vec![];
+    for dir in dirs {
+        candidates.push(dir.clone());
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            let mut sub_dirs: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect();
+            sub_dirs.sort();
+            candidates.extend(sub_dirs);
+        }
+    }
+    let mut games: Vec<GogGame> = vec![];
+    for candidate in candidates {
+        if let Ok(Some(game)) = game_in_dir(&candidate) {
+            if !games.iter().any(|g| g.game_id == game.game_id) {
+                games.push(game);
+            }
+        }
+    }
+    games.iter().map(GogGame::to_shortcut).collect()
+}
+
+/// Parse the content of a `goggame-<id>.info` file of a game installed in `install_dir`.
+///
+/// Returns `None` for DLCs and games without a play task.
+pub fn parse_game_info(content: &str, install_dir: &Path) -> Option<GogGame> {
+    let info = json::parse(content).ok()?;
+    let game_id = info.get("gameId").and_then(json::as_text)?;
+    if let Some(root_id) = info.get("rootGameId").and_then(json::as_text) {
+        if root_id != game_id {
+            return None;
+        }
+    }
+    let tasks = info["playTasks"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let task = tasks
+        .iter()
+        .filter(|task| task["type"].as_str() == Some("FileTask"))
+        .find(|task| task["isPrimary"].as_bool().unwrap_or(false))
+        .or_else(|| {
+            tasks
+                .iter()
+                .find(|task| task["type"].as_str() == Some("FileTask"))
+        })?;
+    let exe = install_dir.join(native_path(task["path"].as_str()?));
+    let working_dir = match task["workingDir"].as_str() {
+        Some(dir) if !dir.is_empty() => install_dir.join(native_path(dir)),
+        _ => exe.parent().unwrap_or(install_dir).to_path_buf(),
+    };
+    let icon = install_dir.join(format!("goggame-{}.ico", game_id));
+    Some(GogGame {
+        name: info["name"].as_str().unwrap_or(&game_id).to_string(),
+        install_dir: install_dir.to_path_buf(),
+        exe,
+        working_dir,
+        arguments: task["arguments"].as_str().unwrap_or_default().to_string(),
+        icon: if icon.is_file() { Some(icon) } else { None },
+        game_id,
+    })
+}
+
+/// The info files always use `\`, use the separator of this OS instead.
+fn native_path(path: &str) -> String {
+    path.replace('\\', std::path::MAIN_SEPARATOR_STR)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const INFO: &str = r#"{
+        "buildId": "51234",
+        "gameId": "1207658771",
+        "rootGameId": "1207658771",
+        "name": "Celeste",
+        "playTasks": [
+            {"category": "document", "isPrimary": false, "type": "FileTask", "path": "manual.pdf"},
+            {"category": "game", "isPrimary": true, "type": "FileTask", "path": "bin\\Celeste.exe", "arguments": "-windowed"}
+        ]
+    }"#;
+
+    #[test]
+    fn parse_info() {
+        let dir = Path::new("/games/Celeste");
+        let game = parse_game_info(INFO, dir).unwrap();
+        assert_eq!("Celeste", game.name);
+        assert_eq!(dir.join("bin").join("Celeste.exe"), game.exe);
+        assert_eq!(dir.join("bin"), game.working_dir);
+        let shortcut = game.to_shortcut();
+        assert_eq!("-windowed", shortcut.launch_options);
+        assert_eq!(format!("\"{}\"", game.exe.display()), shortcut.exe);
+    }
+
+    #[test]
+    fn skips_dlc() {
+        let dlc = INFO.replace(
+            "\"rootGameId\": \"1207658771\"",
+            "\"rootGameId\": \"1000000000\"",
+        );
+        assert_eq!(None, parse_game_info(&dlc, Path::new("/games/Celeste")));
+    }
+
+    #[test]
+    fn scan_library_dir() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let game_dir = dir.join("Celeste");
+        std::fs::create_dir_all(&game_dir).unwrap();
+        std::fs::create_dir_all(dir.join("Empty")).unwrap();
+        std::fs::write(game_dir.join("goggame-1207658771.info"), INFO).unwrap();
+        std::fs::write(game_dir.join("goggame-1207658771.ico"), b"ico").unwrap();
+        let shortcuts = shortcuts_from_dirs(&[dir.to_path_buf(), game_dir.clone()]);
+        assert_eq!(1, shortcuts.len());
+        assert_eq!(
+            game_dir.join("goggame-1207658771.ico").to_string_lossy(),
+            shortcuts[0].icon
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Importing the games installed with the Heroic Games Launcher (requires the `json` feature).
+//!
+//! Heroic keeps its Epic Games (legendary) and GOG installs in JSON files in its
+//! config folder. The shortcuts launch the games through Heroic with a
+//! `heroic://launch/<runner>/<app name>` url, the same way Heroic's own "Add to Steam" does.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::importers::url::UrlOpener;
+use serde_json::Value;
+
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The store a Heroic game was installed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeroicRunner {
+    /// Epic Games, installed through legendary
+    Legendary,
+    /// GOG
+    Gog,
+}
+
+impl HeroicRunner {
+    /// The name Heroic uses for the runner in launch urls.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeroicRunner::Legendary => "legendary",
+            HeroicRunner::Gog => "gog",
+        }
+    }
+}
+
+/// A game installed with Heroic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeroicGame {
+    /// The id of the game in its store
+    pub app_name: String,
+    /// The display name of the game
+    pub title: String,
+    /// The store the game was installed from
+    pub runner: HeroicRunner,
+    /// The folder the game is installed in
+    pub install_path: String,
+    /// The path of the executable, relative to the install path (may be empty)
+    pub executable: String,
+}
+
+impl HeroicGame {
+    /// The url that makes Heroic launch this game.
+    pub fn launch_url(&self) -> String {
+        format!("heroic://launch/{}/{}", self.runner.name(), self.app_name)
+    }
+
+    /// Create a shortcut that launches this game through Heroic, by opening its launch url.
+    pub fn to_shortcut(&self, opener: &UrlOpener) -> ShortcutOwned {
+        let exe = quote(&opener.exe());
+        let start_dir = quote(&self.install_path);
+        let icon = if self.executable.is_empty() {
+            String::new()
+        } else {
+            Path::new(&self.install_path)
+                .join(&self.executable)
+                .to_string_lossy()
+                .to_string()
+        };
+        let launch_options = self.launch_url();
+        let shortcut = Shortcut::new(
+            "0",
+            &self.title,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// The Heroic config folders that exist on this machine (native and Flatpak installs).
+pub fn config_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        dirs.push(Path::new(&app_data).join("heroic"));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        let home = Path::new(&home);
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|c| !c.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".config"));
+        dirs.push(config.join("heroic"));
+        dirs.push(home.join(".var/app/com.heroicgameslauncher.hgl/config/heroic"));
+        dirs.push(home.join("Library/Application Support/heroic"));
+    }
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// Read the games installed with Heroic from its config folder.
+///
+/// Stores that have no installed games file are skipped.
+pub fn installed_games(config_dir: &Path) -> std::io::Result<Vec<HeroicGame>> {
+    let mut games = vec![];
+    let legendary = config_dir.join("legendaryConfig/legendary/installed.json");
+    if let Some(content) = read_if_exists(&legendary)? {
+        games.extend(parse_legendary_installed(&content).map_err(invalid_data)?);
+    }
+    let gog = config_dir.join("gog_store/installed.json");
+    if let Some(content) = read_if_exists(&gog)? {
+        let library = read_if_exists(&config_dir.join("gog_store/library.json"))?;
+        games.extend(parse_gog_installed(&content, library.as_deref()).map_err(invalid_data)?);
+    }
+    Ok(games)
+}
+
+/// Create shortcuts for the games installed with Heroic.
+pub fn installed_shortcuts(
+    config_dir: &Path,
+    opener: &UrlOpener,
+) -> std::io::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games(config_dir)?
+        .iter()
+        .map(|game| game.to_shortcut(opener))
+        .collect())
+}
+
+/// Parse legendary's `installed.json`, skipping DLCs.
+pub fn parse_legendary_installed(content: &str) -> Result<Vec<HeroicGame>, String> {
+    let installed = json::parse(content)?;
+    let installed = installed
+        .as_object()
+        .ok_or_else(|| "installed.json is not an object".to_string())?;
+    Ok(installed
+        .iter()
+        .filter(|(_, game)| !game["is_dlc"].as_bool().unwrap_or(false))
+        .map(|(key, game)| {
+            let app_name = game["app_name"].as_str().unwrap_or(key).to_string();
+            HeroicGame {
+                title: game["title"].as_str().unwrap_or(&app_name).to_string(),
+                app_name,
+                runner: HeroicRunner::Legendary,
+                install_path: game["install_path"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                executable: game["executable"].as_str().unwrap_or_default().to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Parse Heroic's GOG `installed.json`, skipping DLCs.
+///
+/// GOG installs do not record their title, it is looked up in the content of
+/// `library.json` if given, or else taken from the name of the install folder.
+pub fn parse_gog_installed(
+    content: &str,
+    library: Option<&str>,
+) -> Result<Vec<HeroicGame>, String> {
+    let installed = json::parse(content)?;
+    let library = match library {
+        Some(library) => json::parse(library)?,
+        None => Value::Null,
+    };
+    let title_of = |app_name: &str| {
+        library["games"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .find(|game| game.get("app_name").and_then(json::as_text).as_deref() == Some(app_name))
+            .and_then(|game| game["title"].as_str())
+            .map(|title| title.to_string())
+    };
+    Ok(installed["installed"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|game| !game["is_dlc"].as_bool().unwrap_or(false))
+        .filter_map(|game| {
+            let app_name = game.get("appName").and_then(json::as_text)?;
+            let install_path = game["install_path"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let title = title_of(&app_name).unwrap_or_else(|| {
+                Path::new(&install_path.replace('\\', "/"))
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| app_name.clone())
+            });
+            Some(HeroicGame {
+                app_name,
+                title,
+                runner: HeroicRunner::Gog,
+                install_path,
+                executable: game["executable"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+fn read_if_exists(path: &Path) -> std::io::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const LEGENDARY: &str = r#"{
+        "Sugar": {
+            "app_name": "Sugar",
+            "title": "Rocket League",
+            "install_path": "/home/deck/Games/Heroic/rocketleague",
+            "executable": "Binaries/Win64/RocketLeague.exe",
+            "is_dlc": false,
+            "platform": "Windows"
+        },
+        "SugarDlc": {"app_name": "SugarDlc", "title": "Some DLC", "is_dlc": true}
+    }"#;
+
+    #[test]
+    fn parse_legendary() {
+        let games = parse_legendary_installed(LEGENDARY).unwrap();
+        assert_eq!(1, games.len());
+        let shortcut = games[0].to_shortcut(&UrlOpener::Program("/usr/bin/xdg-open".to_string()));
+        assert_eq!("Rocket League", shortcut.app_name);
+        assert_eq!("\"/usr/bin/xdg-open\"", shortcut.exe);
+        assert_eq!("heroic://launch/legendary/Sugar", shortcut.launch_options);
+        assert_eq!(
+            "\"/home/deck/Games/Heroic/rocketleague\"",
+            shortcut.start_dir
+        );
+        assert_eq!(
+            "/home/deck/Games/Heroic/rocketleague/Binaries/Win64/RocketLeague.exe",
+            shortcut.icon
+        );
+    }
+
+    #[test]
+    fn parse_gog_with_and_without_library() {
+        let installed = r#"{"installed": [
+            {"appName": "1207658771", "install_path": "/games/Celeste", "platform": "linux", "is_dlc": false},
+            {"appName": "1453375253", "install_path": "/games/Hades", "platform": "windows"}
+        ]}"#;
+        let library = r#"{"games": [{"app_name": "1207658771", "title": "Celeste"}]}"#;
+        let games = parse_gog_installed(installed, Some(library)).unwrap();
+        assert_eq!("Celeste", games[0].title);
+        assert_eq!("heroic://launch/gog/1207658771", games[0].launch_url());
+        assert_eq!("Hades", games[1].title);
+        assert_eq!(HeroicRunner::Gog, games[1].runner);
+    }
+
+    #[test]
+    fn read_config_dir() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let legendary = dir.join("legendaryConfig/legendary");
+        std::fs::create_dir_all(&legendary).unwrap();
+        std::fs::write(legendary.join("installed.json"), LEGENDARY).unwrap();
+        let shortcuts = installed_shortcuts(dir, &UrlOpener::Default);
+        assert_eq!(1, shortcuts.unwrap().len());
+    }
+}
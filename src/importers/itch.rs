@@ -0,0 +1,176 @@
+//! Importing the games installed with the itch app (needs the `sqlite` feature).
+//!
+//! The itch app keeps its installs ("caves") in butler's SQLite database. Each cave has a
+//! verdict listing the executables found in the install folder, which become the launch target.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags};
+
+use crate::importers::quote;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A game installed with the itch app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItchGame {
+    /// The id of the install in the itch app
+    pub cave_id: String,
+    /// The title of the game
+    pub title: String,
+    /// The folder the game is installed in
+    pub base_path: PathBuf,
+    /// The executable the game is launched with
+    pub exe: PathBuf,
+}
+
+impl ItchGame {
+    /// Create a shortcut that starts the game executable directly.
+    pub fn to_shortcut(&self) -> ShortcutOwned {
+        let exe_path = self.exe.to_string_lossy();
+        let exe = quote(&exe_path);
+        let start_dir = self.exe.parent().unwrap_or(&self.base_path);
+        let start_dir = quote(&start_dir.to_string_lossy());
+        let shortcut = Shortcut::new("0", &self.title, &exe, &start_dir, &exe_path, "", "");
+        shortcut.to_owned()
+    }
+}
+
+/// The location of butler's database, if the itch app is installed.
+pub fn database_path() -> Option<PathBuf> {
+    let config = if cfg!(windows) {
+        PathBuf::from(std::env::var_os("APPDATA")?)
+    } else {
+        let home = PathBuf::from(std::env::var_os("HOME")?);
+        if cfg!(target_os = "macos") {
+            home.join("Library/Application Support")
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .filter(|c| !c.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".config"))
+        }
+    };
+    let path = config.join("itch").join("db").join("butler.db");
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Read the installed games from butler's database.
+///
+/// Installs without a launchable executable are skipped.
+pub fn installed_games(database: &Path) -> rusqlite::Result<Vec<ItchGame>> {
+    let connection = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(
+        "SELECT caves.id, games.title, caves.verdict FROM caves \
+         JOIN games ON games.id = caves.game_id ORDER BY games.title",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    let mut games = vec![];
+    for row in rows {
+        let (cave_id, title, verdict) = row?;
+        if let Some((base_path, exe)) = verdict.as_deref().and_then(parse_verdict) {
+            games.push(ItchGame {
+                cave_id,
+                title,
+                base_path,
+                exe,
+            });
+        }
+    }
+    Ok(games)
+}
+
+/// Create shortcuts for the games installed with the itch app.
+pub fn installed_shortcuts(database: &Path) -> rusqlite::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games(database)?
+        .iter()
+        .map(ItchGame::to_shortcut)
+        .collect())
+}
+
+/// Find the install folder and launch target in the verdict of a cave.
+///
+/// When there are several candidates the one made for this OS is preferred.
+pub fn parse_verdict(verdict: &str) -> Option<(PathBuf, PathBuf)> {
+    let verdict = json::parse(verdict).ok()?;
+    let base_path = PathBuf::from(verdict["basePath"].as_str()?);
+    let candidates = verdict["candidates"].as_array()?;
+    let native_flavors: &[&str] = if cfg!(windows) {
+        &["windows"]
+    } else if cfg!(target_os = "macos") {
+        &["app-macos", "macos"]
+    } else {
+        &["linux", "script"]
+    };
+    let candidate = candidates
+        .iter()
+        .find(|c| native_flavors.contains(&c["flavor"].as_str().unwrap_or_default()))
+        .or_else(|| candidates.first())?;
+    let exe = base_path.join(candidate["path"].as_str()?);
+    Some((base_path, exe))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const VERDICT: &str = r#"{"basePath": "/home/deck/.config/itch/apps/celeste",
+        "totalSize": 1000,
+        "candidates": [{"path": "Celeste.exe", "depth": 1, "flavor": "windows", "arch": "386"}]}"#;
+
+    #[test]
+    fn parse_cave_verdict() {
+        let (base, exe) = parse_verdict(VERDICT).unwrap();
+        assert_eq!(PathBuf::from("/home/deck/.config/itch/apps/celeste"), base);
+        assert_eq!(base.join("Celeste.exe"), exe);
+        assert_eq!(
+            None,
+            parse_verdict(r#"{"basePath": "/a", "candidates": []}"#)
+        );
+    }
+
+    #[test]
+    fn read_database() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let database = dir.join("butler.db");
+        {
+            let connection = Connection::open(&database).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE games (id INTEGER PRIMARY KEY, title TEXT);
+                     CREATE TABLE caves (id TEXT PRIMARY KEY, game_id INTEGER, verdict TEXT);
+                     INSERT INTO games VALUES (1, 'Celeste'), (2, 'Broken');
+                     INSERT INTO caves VALUES ('cave-2', 2, NULL);",
+                )
+                .unwrap();
+            connection
+                .execute("INSERT INTO caves VALUES ('cave-1', 1, ?1)", [VERDICT])
+                .unwrap();
+        }
+        let shortcuts = installed_shortcuts(&database);
+        let shortcuts = shortcuts.unwrap();
+        assert_eq!(1, shortcuts.len());
+        assert_eq!("Celeste", shortcuts[0].app_name);
+        assert_eq!(
+            "\"/home/deck/.config/itch/apps/celeste/Celeste.exe\"",
+            shortcuts[0].exe
+        );
+        assert_eq!(
+            "\"/home/deck/.config/itch/apps/celeste\"",
+            shortcuts[0].start_dir
+        );
+    }
+}
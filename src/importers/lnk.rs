@@ -0,0 +1,300 @@
+//! Importing Windows `.lnk` shortcuts, e.g. from the Start Menu.
+//!
+//! The `.lnk` files are read as bytes following the Shell Link format, so they can be
+//! imported on any OS. Only finding the Start Menu folders is Windows specific.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x01;
+const HAS_LINK_INFO: u32 = 0x02;
+const HAS_NAME: u32 = 0x04;
+const HAS_RELATIVE_PATH: u32 = 0x08;
+const HAS_WORKING_DIR: u32 = 0x10;
+const HAS_ARGUMENTS: u32 = 0x20;
+const HAS_ICON_LOCATION: u32 = 0x40;
+const IS_UNICODE: u32 = 0x80;
+
+/// The parts of a `.lnk` file that matter for a shortcut.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellLink {
+    /// The path of the target the link points to
+    pub target: String,
+    /// The arguments passed to the target
+    pub arguments: String,
+    /// The folder the target is started in
+    pub working_dir: String,
+    /// The file the icon of the link is taken from
+    pub icon_location: String,
+}
+
+impl ShellLink {
+    /// Create a shortcut with the given name from this link.
+    pub fn to_shortcut(&self, app_name: &str) -> ShortcutOwned {
+        let exe = quote(&self.target);
+        let start_dir = if self.working_dir.is_empty() {
+            Path::new(&self.target.replace('\\', "/"))
+                .parent()
+                .map(|p| p.to_string_lossy().replace('/', "\\"))
+                .unwrap_or_default()
+        } else {
+            self.working_dir.clone()
+        };
+        let start_dir = quote(&start_dir);
+        let shortcut = Shortcut::new(
+            "0",
+            app_name,
+            &exe,
+            &start_dir,
+            &self.icon_location,
+            "",
+            &self.arguments,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// Read a `.lnk` file and create a shortcut for it, named after the file.
+///
+/// Returns `None` if the file is not a valid link or does not point to a file path.
+pub fn from_lnk_file(path: &Path) -> std::io::Result<Option<ShortcutOwned>> {
+    let bytes = std::fs::read(path)?;
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(parse_shell_link(&bytes).map(|link| link.to_shortcut(&name)))
+}
+
+/// Create shortcuts for all `.lnk` files in a folder and its subfolders.
+pub fn from_lnk_files_in_dir(dir: &Path) -> Vec<ShortcutOwned> {
+    let mut files = vec![];
+    collect_lnk_files(dir, &mut files);
+    files.sort();
+    files
+        .iter()
+        .filter_map(|file| from_lnk_file(file).ok().flatten())
+        .collect()
+}
+
+/// The Start Menu program folders of the current user and of all users.
+#[cfg(windows)]
+pub fn start_menu_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        dirs.push(Path::new(&app_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+    if let Some(program_data) = std::env::var_os("ProgramData") {
+        dirs.push(Path::new(&program_data).join("Microsoft\\Windows\\Start Menu\\Programs"));
+    }
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// Parse the bytes of a `.lnk` file.
+pub fn parse_shell_link(bytes: &[u8]) -> Option<ShellLink> {
+    if read_u32(bytes, 0)? != 0x4C {
+        return None;
+    }
+    let flags = read_u32(bytes, 0x14)?;
+    let mut offset = 0x4C;
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        offset += 2 + read_u16(bytes, offset)? as usize;
+    }
+    let mut link = ShellLink::default();
+    if flags & HAS_LINK_INFO != 0 {
+        let link_info = bytes.get(offset..offset + read_u32(bytes, offset)? as usize)?;
+        link.target = target_from_link_info(link_info).unwrap_or_default();
+        offset += link_info.len();
+    }
+
+    let unicode = flags & IS_UNICODE != 0;
+    let mut relative_path = String::new();
+    let string_flags = [
+        HAS_NAME,
+        HAS_RELATIVE_PATH,
+        HAS_WORKING_DIR,
+        HAS_ARGUMENTS,
+        HAS_ICON_LOCATION,
+    ];
+    for flag in string_flags.iter() {
+        if flags & flag == 0 {
+            continue;
+        }
+        let (value, next) = read_counted_string(bytes, offset, unicode)?;
+        offset = next;
+        match *flag {
+            HAS_RELATIVE_PATH => relative_path = value,
+            HAS_WORKING_DIR => link.working_dir = value,
+            HAS_ARGUMENTS => link.arguments = value,
+            HAS_ICON_LOCATION => link.icon_location = value,
+            _ => {}
+        }
+    }
+    if link.target.is_empty() {
+        link.target = relative_path;
+    }
+    if link.target.is_empty() {
+        None
+    } else {
+        Some(link)
+    }
+}
+
+fn target_from_link_info(link_info: &[u8]) -> Option<String> {
+    let header_size = read_u32(link_info, 4)?;
+    let has_local_path = read_u32(link_info, 8)? & 0x01 != 0;
+    if !has_local_path {
+        return None;
+    }
+    let suffix_offset = read_u32(link_info, 24)? as usize;
+    let (base, suffix) = if header_size >= 0x24 {
+        (
+            read_utf16_null_terminated(link_info, read_u32(link_info, 28)? as usize)?,
+            read_utf16_null_terminated(link_info, read_u32(link_info, 32)? as usize)?,
+        )
+    } else {
+        (
+            read_ansi_null_terminated(link_info, read_u32(link_info, 16)? as usize)?,
+            read_ansi_null_terminated(link_info, suffix_offset)?,
+        )
+    };
+    Some(format!("{}{}", base, suffix))
+}
+
+fn read_counted_string(bytes: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = read_u16(bytes, offset)? as usize;
+    let start = offset + 2;
+    if unicode {
+        let data = bytes.get(start..start + count * 2)?;
+        let units: Vec<u16> = data
+            .chunks(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Some((String::from_utf16_lossy(&units), start + count * 2))
+    } else {
+        let data = bytes.get(start..start + count)?;
+        Some((String::from_utf8_lossy(data).to_string(), start + count))
+    }
+}
+
+fn read_ansi_null_terminated(bytes: &[u8], offset: usize) -> Option<String> {
+    let data = bytes.get(offset..)?;
+    let end = data.iter().position(|b| *b == 0)?;
+    Some(String::from_utf8_lossy(&data[..end]).to_string())
+}
+
+fn read_utf16_null_terminated(bytes: &[u8], offset: usize) -> Option<String> {
+    let data = bytes.get(offset..)?;
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&units))
+}
+
+fn collect_lnk_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_lnk_files(&path, files);
+        } else if path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("lnk"))
+            .unwrap_or(false)
+        {
+            files.push(path);
+        }
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn counted_utf16(res: &mut Vec<u8>, value: &str) {
+        let units: Vec<u16> = value.encode_utf16().collect();
+        res.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        for unit in units {
+            res.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+    fn shell_link_bytes() -> Vec<u8> {
+        let flags =
+            HAS_LINK_INFO | HAS_WORKING_DIR | HAS_ARGUMENTS | HAS_ICON_LOCATION | IS_UNICODE;
+        let mut res = vec![0; 0x4C];
+        res[0..4].copy_from_slice(&0x4Cu32.to_le_bytes());
+        res[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+
+        let base_path = b"C:\\Games\\Celeste\\Celeste.exe\0";
+        let mut link_info = vec![0; 0x1C];
+        let header_size = 0x1C;
+        let suffix_offset = header_size + base_path.len();
+        let size = suffix_offset + 1;
+        link_info[0..4].copy_from_slice(&(size as u32).to_le_bytes());
+        link_info[4..8].copy_from_slice(&(header_size as u32).to_le_bytes());
+        link_info[8..12].copy_from_slice(&1u32.to_le_bytes());
+        link_info[16..20].copy_from_slice(&(header_size as u32).to_le_bytes());
+        link_info[24..28].copy_from_slice(&(suffix_offset as u32).to_le_bytes());
+        link_info.extend_from_slice(base_path);
+        link_info.push(0);
+        res.extend_from_slice(&link_info);
+
+        counted_utf16(&mut res, "C:\\Games\\Celeste");
+        counted_utf16(&mut res, "--windowed");
+        counted_utf16(&mut res, "C:\\Games\\Celeste\\Celeste.ico");
+        res
+    }
+
+    #[test]
+    fn parse_link() {
+        let link = parse_shell_link(&shell_link_bytes()).unwrap();
+        assert_eq!(
+            ShellLink {
+                target: "C:\\Games\\Celeste\\Celeste.exe".to_string(),
+                arguments: "--windowed".to_string(),
+                working_dir: "C:\\Games\\Celeste".to_string(),
+                icon_location: "C:\\Games\\Celeste\\Celeste.ico".to_string(),
+            },
+            link
+        );
+
+        let shortcut = link.to_shortcut("Celeste");
+        assert_eq!("\"C:\\Games\\Celeste\\Celeste.exe\"", shortcut.exe);
+        assert_eq!("\"C:\\Games\\Celeste\"", shortcut.start_dir);
+        assert_eq!("--windowed", shortcut.launch_options);
+    }
+
+    #[test]
+    fn start_dir_defaults_to_target_dir() {
+        let link = ShellLink {
+            target: "C:\\Games\\Spore\\Spore.exe".to_string(),
+            ..Default::default()
+        };
+        assert_eq!("\"C:\\Games\\Spore\"", link.to_shortcut("Spore").start_dir);
+    }
+
+    #[test]
+    fn not_a_link() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        assert_eq!(None, parse_shell_link(&content));
+    }
+}
@@ -0,0 +1,240 @@
+//! Importing the games installed with Lutris (Linux only, requires the `json` feature).
+//!
+//! The installed games are listed with `lutris --list-games --installed --json`, which
+//! reads Lutris' local database. The shortcuts launch the games with a
+//! `lutris:rungame/<slug>` url.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::importers::quote;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+const LUTRIS_FLATPAK_ID: &str = "net.lutris.Lutris";
+
+/// How Lutris is installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutrisInstall {
+    /// The `lutris` program on the path
+    Native,
+    /// The `net.lutris.Lutris` Flatpak
+    Flatpak,
+}
+
+/// A game installed with Lutris.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LutrisGame {
+    /// The unique name Lutris uses for the game
+    pub slug: String,
+    /// The display name of the game
+    pub name: String,
+    /// The runner the game is started with, e.g. `wine` or `linux`
+    pub runner: String,
+    /// The folder the game is installed in (may be empty)
+    pub directory: String,
+    /// The banner image Lutris downloaded for the game
+    pub banner: Option<PathBuf>,
+    /// The icon Lutris downloaded for the game
+    pub icon: Option<PathBuf>,
+}
+
+impl LutrisGame {
+    /// Create a shortcut that launches this game through Lutris.
+    ///
+    /// The icon of the shortcut is set to the Lutris icon of the game, if there is one.
+    pub fn to_shortcut(&self, install: LutrisInstall) -> ShortcutOwned {
+        let run_game = format!("lutris:rungame/{}", self.slug);
+        let (exe, launch_options) = match install {
+            LutrisInstall::Native => ("lutris".to_string(), run_game),
+            LutrisInstall::Flatpak => (
+                "/usr/bin/flatpak".to_string(),
+                format!("run {} {}", LUTRIS_FLATPAK_ID, run_game),
+            ),
+        };
+        let exe = quote(&exe);
+        let start_dir = quote(&self.directory);
+        let icon = self
+            .icon
+            .as_ref()
+            .map(|icon| icon.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut shortcut = Shortcut::new(
+            "0",
+            &self.name,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        if install == LutrisInstall::Flatpak {
+            shortcut.flatpak_app_id = LUTRIS_FLATPAK_ID;
+        }
+        shortcut.to_owned()
+    }
+}
+
+/// List the games installed with Lutris.
+///
+/// Runs `lutris` (or the Lutris Flatpak), so it must be installed.
+pub fn installed_games(install: LutrisInstall) -> std::io::Result<Vec<LutrisGame>> {
+    let list_args = ["--list-games", "--installed", "--json"];
+    let output = match install {
+        LutrisInstall::Native => Command::new("lutris").args(list_args).output()?,
+        LutrisInstall::Flatpak => Command::new("flatpak")
+            .args(["run", LUTRIS_FLATPAK_ID])
+            .args(list_args)
+            .output()?,
+    };
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let mut games = parse_lutris_list(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let data_dirs = data_dirs(install);
+    for game in games.iter_mut() {
+        find_images(game, &data_dirs);
+    }
+    Ok(games)
+}
+
+/// Create shortcuts for the games installed with Lutris.
+pub fn installed_shortcuts(install: LutrisInstall) -> std::io::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games(install)?
+        .iter()
+        .map(|game| game.to_shortcut(install))
+        .collect())
+}
+
+/// Parse the output of `lutris --list-games --installed --json`.
+///
+/// Log lines Lutris prints before the list are skipped.
+/// The banner and icon of the games are not looked up.
+pub fn parse_lutris_list(output: &str) -> Result<Vec<LutrisGame>, String> {
+    let start = output
+        .find('[')
+        .ok_or_else(|| "No game list in lutris output".to_string())?;
+    let list = json::parse(&output[start..])?;
+    Ok(list
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|game| {
+            let slug = game["slug"].as_str()?.to_string();
+            Some(LutrisGame {
+                name: game["name"].as_str().unwrap_or(&slug).to_string(),
+                runner: game["runner"].as_str().unwrap_or_default().to_string(),
+                directory: game
+                    .get("directory")
+                    .and_then(json::as_text)
+                    .unwrap_or_default(),
+                slug,
+                banner: None,
+                icon: None,
+            })
+        })
+        .collect())
+}
+
+/// The folders Lutris keeps its data in, that exist on this machine.
+fn data_dirs(install: LutrisInstall) -> Vec<PathBuf> {
+    let home = match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home),
+        None => return vec![],
+    };
+    let share = match install {
+        LutrisInstall::Native => home.join(".local/share"),
+        LutrisInstall::Flatpak => home.join(".var/app").join(LUTRIS_FLATPAK_ID).join("data"),
+    };
+    let cache = match install {
+        LutrisInstall::Native => home.join(".cache"),
+        LutrisInstall::Flatpak => home.join(".var/app").join(LUTRIS_FLATPAK_ID).join("cache"),
+    };
+    vec![share, cache]
+        .into_iter()
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Fill in the banner and icon Lutris stored for the game in one of its data folders.
+fn find_images(game: &mut LutrisGame, data_dirs: &[PathBuf]) {
+    let slug = &game.slug;
+    let mut banners = vec![];
+    let mut icons = vec![];
+    for dir in data_dirs {
+        banners.push(dir.join("lutris/banners").join(format!("{}.jpg", slug)));
+        banners.push(dir.join("lutris/banners").join(format!("{}.png", slug)));
+        icons.push(
+            dir.join("icons/hicolor/128x128/apps")
+                .join(format!("lutris_{}.png", slug)),
+        );
+        icons.push(dir.join("lutris/icons").join(format!("{}.png", slug)));
+    }
+    game.banner = banners.into_iter().find(|file| file.is_file());
+    game.icon = icons.into_iter().find(|file| file.is_file());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const LIST: &str = r#"2024-01-01 12:00:00,000: Startup info
+[
+  {"id": 1, "slug": "celeste", "name": "Celeste", "runner": "linux", "platform": "Linux",
+   "year": 2018, "directory": "/home/deck/Games/celeste", "playtime": "1 hour", "lastplayed": null},
+  {"id": 2, "slug": "battlenet", "name": "Battle.net", "runner": "wine", "directory": null}
+]"#;
+
+    #[test]
+    fn parse_list() {
+        let games = parse_lutris_list(LIST).unwrap();
+        assert_eq!(2, games.len());
+        assert_eq!("Celeste", games[0].name);
+        assert_eq!("wine", games[1].runner);
+        assert_eq!("", games[1].directory);
+
+        let shortcut = games[0].to_shortcut(LutrisInstall::Native);
+        assert_eq!("\"lutris\"", shortcut.exe);
+        assert_eq!("lutris:rungame/celeste", shortcut.launch_options);
+        assert_eq!("\"/home/deck/Games/celeste\"", shortcut.start_dir);
+    }
+
+    #[test]
+    fn flatpak_shortcut() {
+        let games = parse_lutris_list(LIST).unwrap();
+        let shortcut = games[1].to_shortcut(LutrisInstall::Flatpak);
+        assert_eq!("\"/usr/bin/flatpak\"", shortcut.exe);
+        assert_eq!(
+            "run net.lutris.Lutris lutris:rungame/battlenet",
+            shortcut.launch_options
+        );
+        assert_eq!("net.lutris.Lutris", shortcut.flatpak_app_id);
+    }
+
+    #[test]
+    fn find_banner_and_icon() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("lutris/banners")).unwrap();
+        std::fs::create_dir_all(dir.join("icons/hicolor/128x128/apps")).unwrap();
+        std::fs::write(dir.join("lutris/banners/celeste.jpg"), b"jpg").unwrap();
+        std::fs::write(
+            dir.join("icons/hicolor/128x128/apps/lutris_celeste.png"),
+            b"png",
+        )
+        .unwrap();
+        let mut game = parse_lutris_list(LIST).unwrap().remove(0);
+        find_images(&mut game, &[dir.to_path_buf()]);
+        assert_eq!(Some(dir.join("lutris/banners/celeste.jpg")), game.banner);
+        assert_eq!(
+            Some(dir.join("icons/hicolor/128x128/apps/lutris_celeste.png")),
+            game.icon
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Creating shortcuts from other launchers and shortcut formats.
+//!
+//! Each importer produces [ShortcutOwned](crate::shortcut::ShortcutOwned)s that can be
+//! added to a [ShortcutsFile](crate::ShortcutsFile) and written to shortcuts.vdf.
+
+#[cfg(feature = "sqlite")]
+pub mod amazon;
+pub mod battlenet;
+pub mod desktop;
+#[cfg(feature = "json")]
+pub mod epic;
+#[cfg(target_os = "linux")]
+pub mod flatpak;
+pub mod gamelist;
+#[cfg(feature = "json")]
+pub mod gog;
+#[cfg(feature = "json")]
+pub mod heroic;
+#[cfg(feature = "sqlite")]
+pub mod itch;
+pub mod lnk;
+#[cfg(all(target_os = "linux", feature = "json"))]
+pub mod lutris;
+#[cfg(feature = "json")]
+pub mod retroarch;
+pub mod ubisoft;
+pub mod url;
+#[cfg(feature = "json")]
+pub mod uwp;
+
+/// Wrap a path in quotes, the way steam writes the exe and start dir of a shortcut.
+pub(crate) fn quote(path: &str) -> String {
+    if path.is_empty() || (path.starts_with('"') && path.ends_with('"') && path.len() > 1) {
+        path.to_string()
+    } else {
+        format!("\"{}\"", path)
+    }
+}
@@ -0,0 +1,240 @@
+//! Importing games from RetroArch `.lpl` playlists (requires the `json` feature).
+//!
+//! Every entry of a playlist becomes a shortcut that starts RetroArch with the core and
+//! rom of the entry. The thumbnails RetroArch downloaded for the entries are mapped to
+//! the artwork kinds steam shows, so they can be set with [set_artwork](crate::artwork::set_artwork).
+
+use std::path::{Path, PathBuf};
+
+use crate::artwork::ArtworkKind;
+use crate::importers::quote;
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The thumbnail folders of RetroArch and the artwork kind they are used for.
+const THUMBNAIL_KINDS: [(&str, ArtworkKind); 4] = [
+    ("Named_Boxarts", ArtworkKind::Grid),
+    ("Named_Titles", ArtworkKind::WideGrid),
+    ("Named_Snaps", ArtworkKind::Hero),
+    ("Named_Logos", ArtworkKind::Logo),
+];
+
+/// A shortcut for an entry of a playlist, with the thumbnails found for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistGame {
+    /// The shortcut that starts the game in RetroArch
+    pub shortcut: ShortcutOwned,
+    /// The thumbnails of the game and the kind of artwork they fit
+    pub artwork: Vec<(ArtworkKind, PathBuf)>,
+}
+
+/// Read a playlist file and create a shortcut for each entry.
+///
+/// The name of the playlist file is used to find its thumbnails in `thumbnails_dir`.
+pub fn from_playlist_file(
+    playlist: &Path,
+    retroarch_exe: &str,
+    thumbnails_dir: Option<&Path>,
+) -> std::io::Result<Vec<PlaylistGame>> {
+    let content = std::fs::read_to_string(playlist)?;
+    let name = playlist
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    parse_playlist(&content, &name, retroarch_exe, thumbnails_dir)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Create a shortcut for each entry in the content of a playlist.
+///
+/// Both the JSON format and the old six-lines-per-entry format are supported. The launch
+/// options are `-L "<core>" "<rom>"`, using the default core of the playlist for entries
+/// without a core. Entries without any core are started with just the rom.
+pub fn parse_playlist(
+    content: &str,
+    playlist_name: &str,
+    retroarch_exe: &str,
+    thumbnails_dir: Option<&Path>,
+) -> Result<Vec<PlaylistGame>, String> {
+    let entries = if content.trim_start().starts_with('{') {
+        json_entries(content)?
+    } else {
+        legacy_entries(content)
+    };
+    let exe = quote(retroarch_exe);
+    let start_dir = Path::new(retroarch_exe)
+        .parent()
+        .map(|dir| quote(&dir.to_string_lossy()))
+        .unwrap_or_default();
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let launch_options = match &entry.core {
+                Some(core) => format!("-L \"{}\" \"{}\"", core, entry.rom),
+                None => format!("\"{}\"", entry.rom),
+            };
+            let shortcut =
+                Shortcut::new("0", &entry.label, &exe, &start_dir, "", "", &launch_options);
+            let artwork = thumbnails_dir
+                .map(|dir| find_thumbnails(&dir.join(playlist_name), &entry.label))
+                .unwrap_or_default();
+            PlaylistGame {
+                shortcut: shortcut.to_owned(),
+                artwork,
+            }
+        })
+        .collect())
+}
+
+struct Entry {
+    rom: String,
+    label: String,
+    core: Option<String>,
+}
+
+fn json_entries(content: &str) -> Result<Vec<Entry>, String> {
+    let playlist = json::parse(content)?;
+    let default_core = core_path(playlist["default_core_path"].as_str());
+    Ok(playlist["items"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| {
+            let rom = item["path"].as_str().filter(|p| !p.is_empty())?.to_string();
+            Some(Entry {
+                label: label_or_file_name(item["label"].as_str(), &rom),
+                core: core_path(item["core_path"].as_str()).or_else(|| default_core.clone()),
+                rom,
+            })
+        })
+        .collect())
+}
+
+fn legacy_entries(content: &str) -> Vec<Entry> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .chunks(6)
+        .filter(|chunk| chunk.len() >= 3 && !chunk[0].is_empty())
+        .map(|chunk| Entry {
+            rom: chunk[0].to_string(),
+            label: label_or_file_name(Some(chunk[1]), chunk[0]),
+            core: core_path(Some(chunk[2])),
+        })
+        .collect()
+}
+
+fn core_path(core: Option<&str>) -> Option<String> {
+    core.filter(|core| !core.is_empty() && *core != "DETECT")
+        .map(|core| core.to_string())
+}
+
+fn label_or_file_name(label: Option<&str>, rom: &str) -> String {
+    match label {
+        Some(label) if !label.is_empty() => label.to_string(),
+        // Roms in archives are written as "archive.zip#rom.sfc"
+        _ => Path::new(rom.split('#').next().unwrap_or(rom))
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| rom.to_string()),
+    }
+}
+
+fn find_thumbnails(playlist_thumbnails: &Path, label: &str) -> Vec<(ArtworkKind, PathBuf)> {
+    // RetroArch replaces these characters in the file names of thumbnails
+    let file_name: String = label
+        .chars()
+        .map(|c| if "&*/:`<>?\\|".contains(c) { '_' } else { c })
+        .collect();
+    THUMBNAIL_KINDS
+        .iter()
+        .filter_map(|(dir, kind)| {
+            let thumbnail = playlist_thumbnails
+                .join(dir)
+                .join(format!("{}.png", file_name));
+            if thumbnail.is_file() {
+                Some((*kind, thumbnail))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const PLAYLIST: &str = r#"{
+  "version": "1.5",
+  "default_core_path": "/usr/lib/libretro/snes9x_libretro.so",
+  "default_core_name": "Snes9x",
+  "items": [
+    {
+      "path": "/roms/snes/Super Mario World.sfc",
+      "label": "Super Mario World",
+      "core_path": "DETECT",
+      "core_name": "DETECT",
+      "crc32": "B19ED489|crc",
+      "db_name": "Nintendo - Super Nintendo Entertainment System.lpl"
+    },
+    {
+      "path": "/roms/snes/zelda.zip#zelda.sfc",
+      "label": "",
+      "core_path": "/usr/lib/libretro/bsnes_libretro.so",
+      "core_name": "bsnes"
+    }
+  ]
+}"#;
+
+    #[test]
+    fn parse_json_playlist() {
+        let games = parse_playlist(PLAYLIST, "snes", "/usr/bin/retroarch", None).unwrap();
+        assert_eq!(2, games.len());
+        let mario = &games[0].shortcut;
+        assert_eq!("Super Mario World", mario.app_name);
+        assert_eq!("\"/usr/bin/retroarch\"", mario.exe);
+        assert_eq!("\"/usr/bin\"", mario.start_dir);
+        assert_eq!(
+            "-L \"/usr/lib/libretro/snes9x_libretro.so\" \"/roms/snes/Super Mario World.sfc\"",
+            mario.launch_options
+        );
+        let zelda = &games[1].shortcut;
+        assert_eq!("zelda", zelda.app_name);
+        assert!(zelda
+            .launch_options
+            .starts_with("-L \"/usr/lib/libretro/bsnes_libretro.so\""));
+    }
+
+    #[test]
+    fn parse_legacy_playlist() {
+        let content = "/roms/gb/tetris.gb\nTetris\nDETECT\nDETECT\n0|crc\nGB.lpl\n";
+        let games = parse_playlist(content, "gb", "retroarch", None).unwrap();
+        assert_eq!(1, games.len());
+        assert_eq!("Tetris", games[0].shortcut.app_name);
+        assert_eq!("\"/roms/gb/tetris.gb\"", games[0].shortcut.launch_options);
+    }
+
+    #[test]
+    fn finds_thumbnails() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let boxarts = dir.join("snes").join("Named_Boxarts");
+        let logos = dir.join("snes").join("Named_Logos");
+        std::fs::create_dir_all(&boxarts).unwrap();
+        std::fs::create_dir_all(&logos).unwrap();
+        std::fs::write(boxarts.join("Super Mario World.png"), b"png").unwrap();
+        std::fs::write(logos.join("Super Mario World.png"), b"png").unwrap();
+        let games = parse_playlist(PLAYLIST, "snes", "retroarch", Some(dir)).unwrap();
+        assert_eq!(
+            vec![
+                (ArtworkKind::Grid, boxarts.join("Super Mario World.png")),
+                (ArtworkKind::Logo, logos.join("Super Mario World.png")),
+            ],
+            games[0].artwork
+        );
+        assert!(games[1].artwork.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Importing the games installed with Ubisoft Connect.
+//!
+//! Ubisoft Connect records its installs in the registry, keyed by the id of the game.
+//! The shortcuts launch the games through Ubisoft Connect with a `uplay://launch/<id>` url.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use crate::importers::url::UrlOpener;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A game installed with Ubisoft Connect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UbisoftGame {
+    /// The id of the game in Ubisoft Connect
+    pub id: String,
+    /// The name of the game, taken from its install folder
+    pub name: String,
+    /// The folder the game is installed in
+    pub install_dir: PathBuf,
+    /// An icon shipped in the install folder, if there is one
+    pub icon: Option<PathBuf>,
+}
+
+impl UbisoftGame {
+    /// Find the name and icon of the game with the given id, installed in the given folder.
+    pub fn new(id: &str, install_dir: &Path) -> Self {
+        let name = install_dir
+            .components()
+            .next_back()
+            .map(|name| name.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_else(|| id.to_string());
+        Self {
+            id: id.to_string(),
+            name,
+            install_dir: install_dir.to_path_buf(),
+            icon: find_icon(install_dir),
+        }
+    }
+
+    /// The url that makes Ubisoft Connect start this game.
+    pub fn launch_url(&self) -> String {
+        format!("uplay://launch/{}/0", self.id)
+    }
+
+    /// Create a shortcut that launches this game through Ubisoft Connect.
+    pub fn to_shortcut(&self, opener: &UrlOpener) -> ShortcutOwned {
+        let exe = quote(&opener.exe());
+        let start_dir = quote(&self.install_dir.to_string_lossy());
+        let icon = self
+            .icon
+            .as_ref()
+            .map(|icon| icon.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let launch_options = self.launch_url();
+        let shortcut = Shortcut::new(
+            "0",
+            &self.name,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// The games Ubisoft Connect has installed, read from the registry.
+///
+/// Installs whose folder no longer exists are skipped.
+#[cfg(windows)]
+pub fn installed_games() -> Vec<UbisoftGame> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    let installs = match RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey("SOFTWARE\\WOW6432Node\\Ubisoft\\Launcher\\Installs")
+    {
+        Ok(installs) => installs,
+        Err(_) => return vec![],
+    };
+    installs
+        .enum_keys()
+        .filter_map(|id| id.ok())
+        .filter_map(|id| {
+            let install_dir: String = installs
+                .open_subkey(&id)
+                .and_then(|install| install.get_value("InstallDir"))
+                .ok()?;
+            let install_dir = PathBuf::from(install_dir);
+            if install_dir.is_dir() {
+                Some(UbisoftGame::new(&id, &install_dir))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Create shortcuts for the games installed with Ubisoft Connect.
+#[cfg(windows)]
+pub fn installed_shortcuts(opener: &UrlOpener) -> Vec<ShortcutOwned> {
+    installed_games()
+        .iter()
+        .map(|game| game.to_shortcut(opener))
+        .collect()
+}
+
+/// The first `.ico` file in the install folder, in name order.
+fn find_icon(install_dir: &Path) -> Option<PathBuf> {
+    let mut icons: Vec<PathBuf> = std::fs::read_dir(install_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .map(|e| e.eq_ignore_ascii_case("ico"))
+                .unwrap_or(false)
+        })
+        .collect();
+    icons.sort();
+    icons.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn game_from_install_dir() {
+        let temp = temp_dir();
+        let dir = temp.path().join("Assassin's Creed Origins");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("ACOrigins.ico"), b"ico").unwrap();
+        let game = UbisoftGame::new("3539", &dir);
+
+        assert_eq!("Assassin's Creed Origins", game.name);
+        assert_eq!(Some(dir.join("ACOrigins.ico")), game.icon);
+        let shortcut = game.to_shortcut(&UrlOpener::Program("explorer.exe".to_string()));
+        assert_eq!("uplay://launch/3539/0", shortcut.launch_options);
+        assert_eq!(dir.join("ACOrigins.ico").to_string_lossy(), shortcut.icon);
+    }
+}
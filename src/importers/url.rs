@@ -0,0 +1,129 @@
+//! Importing `.url` internet shortcuts, like the ones game launchers put on the desktop.
+
+use std::path::Path;
+
+use crate::importers::quote;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The program a url shortcut is opened with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlOpener {
+    /// Let the OS open the url with the default program
+    /// (`explorer.exe` on Windows, `open` on macOS and `xdg-open` on Linux)
+    Default,
+    /// Open the url with the given program, e.g. the path of a browser
+    Program(String),
+}
+
+impl UrlOpener {
+    pub(crate) fn exe(&self) -> String {
+        match self {
+            UrlOpener::Program(program) => program.clone(),
+            UrlOpener::Default if cfg!(windows) => "C:\\Windows\\explorer.exe".to_string(),
+            UrlOpener::Default if cfg!(target_os = "macos") => "/usr/bin/open".to_string(),
+            UrlOpener::Default => "/usr/bin/xdg-open".to_string(),
+        }
+    }
+}
+
+/// Read a `.url` file and create a shortcut that opens its url, named after the file.
+///
+/// Returns `None` if the file has no url.
+pub fn from_url_file(path: &Path, opener: &UrlOpener) -> std::io::Result<Option<ShortcutOwned>> {
+    let content = String::from_utf8_lossy(&std::fs::read(path)?).to_string();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(parse_url_file(&content, &name, opener))
+}
+
+/// Create a shortcut with the given name from the content of a `.url` file.
+///
+/// The url is passed as the launch options to the opener. The IconFile and
+/// WorkingDirectory of the url file are used for the icon and start dir.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::importers::url::{parse_url_file, UrlOpener};
+///
+/// let content = "[InternetShortcut]\nURL=https://example.com\n";
+/// let opener = UrlOpener::Program("firefox".to_string());
+/// let shortcut = parse_url_file(content, "Example", &opener).unwrap();
+/// assert_eq!("\"firefox\"", shortcut.exe);
+/// assert_eq!("https://example.com", shortcut.launch_options);
+/// ```
+pub fn parse_url_file(content: &str, app_name: &str, opener: &UrlOpener) -> Option<ShortcutOwned> {
+    let mut in_section = false;
+    let mut url = None;
+    let mut icon = "";
+    let mut working_dir = "";
+    // Files written on Windows can start with a byte order mark
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim().to_lowercase().as_str() {
+                "url" => url = Some(value.trim()),
+                "iconfile" => icon = value.trim(),
+                "workingdirectory" => working_dir = value.trim(),
+                _ => {}
+            }
+        }
+    }
+    let url = url.filter(|url| !url.is_empty())?;
+    let launch_options = if url.contains(char::is_whitespace) {
+        format!("\"{}\"", url)
+    } else {
+        url.to_string()
+    };
+    let exe = quote(&opener.exe());
+    let start_dir = quote(working_dir);
+    let shortcut = Shortcut::new("0", app_name, &exe, &start_dir, icon, "", &launch_options);
+    Some(shortcut.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_epic_url_file() {
+        let content = "\u{feff}[{000214A0-0000-0000-C000-000000000046}]\r\nProp3=19,0\r\n\
+            [InternetShortcut]\r\nIDList=\r\nIconIndex=0\r\n\
+            WorkingDirectory=C:\\Program Files (x86)\\Epic Games\r\n\
+            URL=com.epicgames.launcher://apps/7e508f543b05465abe3a935960eb70ac?action=launch&silent=true\r\n\
+            IconFile=F:\\EpicExtra\\IdleChampions\\IdleDragons.exe\r\n";
+        let opener = UrlOpener::Program("C:\\Windows\\explorer.exe".to_string());
+        let shortcut = parse_url_file(content, "Idle Champions", &opener).unwrap();
+        assert_eq!("Idle Champions", shortcut.app_name);
+        assert_eq!("\"C:\\Windows\\explorer.exe\"", shortcut.exe);
+        assert!(shortcut
+            .launch_options
+            .starts_with("com.epicgames.launcher://apps/7e508f543b05465abe3a935960eb70ac"));
+        assert_eq!(
+            "\"C:\\Program Files (x86)\\Epic Games\"",
+            shortcut.start_dir
+        );
+        assert_eq!(
+            "F:\\EpicExtra\\IdleChampions\\IdleDragons.exe",
+            shortcut.icon
+        );
+    }
+
+    #[test]
+    fn no_url_is_none() {
+        let content = "[InternetShortcut]\nIconIndex=0\n";
+        assert!(parse_url_file(content, "Nothing", &UrlOpener::Default).is_none());
+        // The URL key of this file is missing its first letter
+        let path = Path::new("src/testdata/broken_shortcut.url");
+        assert!(from_url_file(path, &UrlOpener::Default).unwrap().is_none());
+    }
+}
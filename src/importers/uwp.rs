@@ -0,0 +1,294 @@
+//! Importing Xbox / Microsoft Store (UWP) games (requires the `json` feature).
+//!
+//! Store games can not be started through their executable, they are launched with
+//! `explorer.exe shell:AppsFolder\<AUMID>`, where the AUMID is the package family name and
+//! the id of the application in the package manifest. Finding the installed packages is
+//! Windows only, the manifest parsing works everywhere.
+
+use std::path::{Path, PathBuf};
+
+use crate::importers::quote;
+use serde_json::Value;
+
+use crate::json;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+const EXPLORER: &str = "C:\\Windows\\explorer.exe";
+
+/// A UWP application that can be launched from Steam.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UwpApp {
+    /// The application user model id, `<package family name>!<application id>`
+    pub aumid: String,
+    /// The display name of the application
+    pub name: String,
+    /// The folder the package is installed in
+    pub install_location: PathBuf,
+    /// The logo of the application, if it could be found
+    pub logo: Option<PathBuf>,
+}
+
+impl UwpApp {
+    /// Create a shortcut that launches the application through `explorer.exe`.
+    pub fn to_shortcut(&self) -> ShortcutOwned {
+        let exe = quote(EXPLORER);
+        let start_dir = quote(&self.install_location.to_string_lossy());
+        let icon = self
+            .logo
+            .as_ref()
+            .map(|logo| logo.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let launch_options = format!("shell:AppsFolder\\{}", self.aumid);
+        let shortcut = Shortcut::new(
+            "0",
+            &self.name,
+            &exe,
+            &start_dir,
+            &icon,
+            "",
+            &launch_options,
+        );
+        shortcut.to_owned()
+    }
+}
+
+/// A package as listed by `Get-AppxPackage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppxPackage {
+    /// The name of the package
+    pub name: String,
+    /// The package family name, the first part of the AUMID
+    pub family_name: String,
+    /// The folder the package is installed in
+    pub install_location: PathBuf,
+}
+
+impl AppxPackage {
+    /// Is this package a game? Xbox games ship a `MicrosoftGame.config` file.
+    pub fn is_game(&self) -> bool {
+        self.install_location.join("MicrosoftGame.config").is_file()
+    }
+
+    /// The applications in the package, read from its `AppxManifest.xml`.
+    pub fn apps(&self) -> std::io::Result<Vec<UwpApp>> {
+        let manifest = std::fs::read_to_string(self.install_location.join("AppxManifest.xml"))?;
+        Ok(parse_appx_manifest(&manifest)
+            .into_iter()
+            .map(|app| {
+                let name = if app.display_name.is_empty()
+                    || app.display_name.starts_with("ms-resource:")
+                {
+                    self.name.clone()
+                } else {
+                    app.display_name
+                };
+                UwpApp {
+                    aumid: format!("{}!{}", self.family_name, app.id),
+                    name,
+                    logo: resolve_logo(&self.install_location, &app.logo),
+                    install_location: self.install_location.clone(),
+                }
+            })
+            .collect())
+    }
+}
+
+/// List the games installed from the Microsoft Store, using PowerShell.
+#[cfg(windows)]
+pub fn installed_games() -> std::io::Result<Vec<UwpApp>> {
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-AppxPackage | Where-Object { -not $_.IsFramework } | \
+             Select-Object Name, PackageFamilyName, InstallLocation | ConvertTo-Json",
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let packages = parse_package_list(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(packages
+        .iter()
+        .filter(|package| package.is_game())
+        .filter_map(|package| package.apps().ok())
+        .flatten()
+        .collect())
+}
+
+/// Create shortcuts for the games installed from the Microsoft Store.
+#[cfg(windows)]
+pub fn installed_shortcuts() -> std::io::Result<Vec<ShortcutOwned>> {
+    Ok(installed_games()?.iter().map(UwpApp::to_shortcut).collect())
+}
+
+/// Parse the JSON output of
+/// `Get-AppxPackage | Select-Object Name, PackageFamilyName, InstallLocation | ConvertTo-Json`.
+pub fn parse_package_list(output: &str) -> Result<Vec<AppxPackage>, String> {
+    let output = output.trim();
+    if output.is_empty() {
+        return Ok(vec![]);
+    }
+    let list = json::parse(output)?;
+    // ConvertTo-Json writes a single object instead of a list when there is one package
+    let packages = match list {
+        Value::Array(packages) => packages,
+        package => vec![package],
+    };
+    Ok(packages
+        .iter()
+        .filter_map(|package| {
+            Some(AppxPackage {
+                name: package["Name"].as_str()?.to_string(),
+                family_name: package["PackageFamilyName"].as_str()?.to_string(),
+                install_location: PathBuf::from(package["InstallLocation"].as_str()?),
+            })
+        })
+        .collect())
+}
+
+/// An application entry of an `AppxManifest.xml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestApp {
+    /// The id of the application in the package
+    pub id: String,
+    /// The DisplayName of its visual elements
+    pub display_name: String,
+    /// The Square150x150Logo of its visual elements, relative to the package
+    pub logo: String,
+}
+
+/// Find the applications in the content of an `AppxManifest.xml`.
+pub fn parse_appx_manifest(manifest: &str) -> Vec<ManifestApp> {
+    let mut apps = vec![];
+    let mut rest = manifest;
+    while let Some(start) = rest.find("<Application ") {
+        rest = &rest[start..];
+        let end = rest[1..]
+            .find("<Application ")
+            .map(|e| e + 1)
+            .unwrap_or(rest.len());
+        let application = &rest[..end];
+        rest = &rest[end..];
+        let id = match attribute(application, "Id") {
+            Some(id) => id,
+            None => continue,
+        };
+        let visual_elements = application
+            .find("VisualElements ")
+            .map(|start| &application[start..])
+            .unwrap_or_default();
+        apps.push(ManifestApp {
+            id,
+            display_name: attribute(visual_elements, "DisplayName").unwrap_or_default(),
+            logo: attribute(visual_elements, "Square150x150Logo").unwrap_or_default(),
+        });
+    }
+    apps
+}
+
+/// The value of the first attribute with the given name in the first tag of `xml`.
+fn attribute(xml: &str, name: &str) -> Option<String> {
+    let tag = &xml[..xml.find('>').unwrap_or(xml.len())];
+    let pattern = format!(" {}=\"", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let end = tag[start..].find('"')? + start;
+    Some(
+        tag[start..end]
+            .replace("&amp;", "&")
+            .replace("&apos;", "'")
+            .replace("&quot;", "\""),
+    )
+}
+
+/// Logos are stored with scale qualifiers, e.g. `Logo.png` is stored as `Logo.scale-200.png`.
+fn resolve_logo(install_location: &Path, logo: &str) -> Option<PathBuf> {
+    if logo.is_empty() {
+        return None;
+    }
+    let logo = install_location.join(logo.replace('\\', std::path::MAIN_SEPARATOR_STR));
+    if logo.is_file() {
+        return Some(logo);
+    }
+    let stem = logo.file_stem()?.to_string_lossy().to_string();
+    let mut scaled: Vec<PathBuf> = std::fs::read_dir(logo.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| {
+                    name.to_string_lossy()
+                        .starts_with(&format!("{}.scale-", stem))
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+    scaled.sort();
+    scaled.pop()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Package xmlns="http://schemas.microsoft.com/appx/manifest/foundation/windows10">
+  <Properties><DisplayName>Halo Infinite</DisplayName></Properties>
+  <Applications>
+    <Application Id="Game" Executable="HaloInfinite.exe" EntryPoint="Windows.FullTrustApplication">
+      <uap:VisualElements DisplayName="Halo Infinite" Square150x150Logo="Assets\Logo.png" Description="Halo" />
+    </Application>
+    <Application Id="Launcher" Executable="Launcher.exe">
+      <uap:VisualElements DisplayName="ms-resource:AppName" Square150x150Logo="Assets\Small.png" />
+    </Application>
+  </Applications>
+</Package>"#;
+
+    #[test]
+    fn parse_manifest() {
+        let apps = parse_appx_manifest(MANIFEST);
+        assert_eq!(2, apps.len());
+        assert_eq!("Game", apps[0].id);
+        assert_eq!("Halo Infinite", apps[0].display_name);
+        assert_eq!("Assets\\Logo.png", apps[0].logo);
+        assert_eq!("ms-resource:AppName", apps[1].display_name);
+    }
+
+    #[test]
+    fn package_apps() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("Assets")).unwrap();
+        std::fs::write(dir.join("AppxManifest.xml"), MANIFEST).unwrap();
+        std::fs::write(dir.join("MicrosoftGame.config"), "<Game/>").unwrap();
+        std::fs::write(dir.join("Assets").join("Logo.scale-100.png"), b"png").unwrap();
+        std::fs::write(dir.join("Assets").join("Logo.scale-200.png"), b"png").unwrap();
+        let output = format!(
+            r#"{{"Name": "Microsoft.254428597CFE2", "PackageFamilyName": "Microsoft.254428597CFE2_8wekyb3d8bbwe", "InstallLocation": {:?}}}"#,
+            dir.to_string_lossy()
+        );
+        let packages = parse_package_list(&output).unwrap();
+        assert!(packages[0].is_game());
+        let apps = packages[0].apps();
+        let apps = apps.unwrap();
+
+        assert_eq!("Microsoft.254428597CFE2", apps[1].name);
+        assert_eq!(
+            Some(dir.join("Assets").join("Logo.scale-200.png")),
+            apps[0].logo
+        );
+        let shortcut = apps[0].to_shortcut();
+        assert_eq!("Halo Infinite", shortcut.app_name);
+        assert_eq!("\"C:\\Windows\\explorer.exe\"", shortcut.exe);
+        assert_eq!(
+            "shell:AppsFolder\\Microsoft.254428597CFE2_8wekyb3d8bbwe!Game",
+            shortcut.launch_options
+        );
+    }
+}
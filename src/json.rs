@@ -0,0 +1,33 @@
+//! Helpers for the JSON files of the launchers the importers read and of steam, which are
+//! read and written with serde_json.
+
+use serde_json::Value;
+
+/// Parse JSON text, which tools on Windows often start with a byte order mark.
+pub(crate) fn parse(input: &str) -> Result<Value, String> {
+    serde_json::from_str(input.trim_start_matches('\u{feff}')).map_err(|e| e.to_string())
+}
+
+/// The value as text, also for numbers (ids are sometimes written as numbers).
+pub(crate) fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn ids_are_text() {
+        let json = parse("\u{feff}{\"a\": \"7\", \"b\": 42, \"c\": null}").unwrap();
+        assert_eq!(Some("7".to_string()), as_text(&json["a"]));
+        assert_eq!(Some("42".to_string()), as_text(&json["b"]));
+        assert_eq!(None, as_text(&json["c"]));
+        assert!(parse(r#"{"a": 1} x"#).is_err());
+    }
+}
@@ -0,0 +1,622 @@
+//! Parsing the launch options of a shortcut into their parts.
+//!
+//! Steam launch options either are plain arguments that are added to the command, or
+//! contain `%command%`, which steam replaces with the command of the game. In the second
+//! form environment variables and wrapper programs can come before the command:
+//!
+//! ```text
+//! DXVK_HUD=fps gamemoderun %command% -windowed
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::shortcut::ShortcutOwned;
+
+/// Launch options split into their parts.
+///
+/// Every part is kept as written, including quotes, so formatting the parsed options
+/// gives back the same text (apart from the amount of whitespace).
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::launch_options::LaunchOptions;
+///
+/// let options = LaunchOptions::parse("PROTON_LOG=1 gamemoderun %command% -windowed");
+/// assert_eq!(Some("1"), options.env_var("PROTON_LOG"));
+/// assert_eq!(vec!["gamemoderun"], options.wrapper);
+/// assert_eq!(vec!["-windowed"], options.args);
+/// assert_eq!("PROTON_LOG=1 gamemoderun %command% -windowed", options.to_string());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Environment variables set before the command, as `(name, value)`
+    pub env: Vec<(String, String)>,
+    /// The wrapper programs (and their arguments) that run the command
+    pub wrapper: Vec<String>,
+    /// Whether the options contain `%command%`
+    pub has_command: bool,
+    /// The arguments after the command
+    pub args: Vec<String>,
+}
+
+const COMMAND: &str = "%command%";
+
+impl LaunchOptions {
+    /// Create empty launch options, to build with [env](Self::env), [wrapper](Self::wrapper) and [arg](Self::arg).
+    ///
+    /// `%command%` is added when formatting as soon as there is an environment variable or wrapper.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::launch_options::LaunchOptions;
+    ///
+    /// let options = LaunchOptions::new()
+    ///     .env("MANGOHUD", "1")
+    ///     .wrapper("gamemoderun")
+    ///     .arg("--fullscreen");
+    /// assert_eq!("MANGOHUD=1 gamemoderun %command% --fullscreen", options.to_string());
+    /// assert_eq!("--fullscreen", LaunchOptions::new().arg("--fullscreen").to_string());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an environment variable, replacing its value if it is already set.
+    ///
+    /// The value is quoted if it contains whitespace or characters the shell would interpret.
+    pub fn env(mut self, name: &str, value: &str) -> Self {
+        let value = quote_if_needed(value);
+        match self.env.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.env.push((name.to_string(), value)),
+        }
+        self
+    }
+
+    /// Add a wrapper program that runs the command, after the wrappers already added.
+    ///
+    /// The wrapper is a command line, e.g. `gamescope -w 1280 -h 800 --`.
+    pub fn wrapper(mut self, command_line: &str) -> Self {
+        self.wrapper.extend(split_tokens(command_line));
+        self
+    }
+
+    /// Add an argument after the command, quoted if it contains whitespace or characters the
+    /// shell would interpret.
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(quote_if_needed(arg));
+        self
+    }
+
+    /// Run the game with Feral's GameMode (`gamemoderun`).
+    pub fn gamemode(self) -> Self {
+        self.wrapper("gamemoderun")
+    }
+
+    /// Show the MangoHud overlay (`mangohud`).
+    pub fn mangohud(self) -> Self {
+        self.wrapper("mangohud")
+    }
+
+    /// Run the game in a gamescope session with the given output size.
+    pub fn gamescope(self, width: u32, height: u32) -> Self {
+        self.wrapper(&format!("gamescope -W {} -H {} --", width, height))
+    }
+
+    /// Whether `%command%` is written when formatting.
+    fn needs_command(&self) -> bool {
+        self.has_command || !self.env.is_empty() || !self.wrapper.is_empty()
+    }
+
+    /// Parse launch options.
+    ///
+    /// Without `%command%` all parts are arguments, as that is how steam treats them.
+    pub fn parse(launch_options: &str) -> Self {
+        let tokens = split_tokens(launch_options);
+        let command_index = match tokens.iter().position(|token| token == COMMAND) {
+            Some(index) => index,
+            None => {
+                return Self {
+                    args: tokens,
+                    ..Default::default()
+                }
+            }
+        };
+        let mut res = Self {
+            has_command: true,
+            ..Default::default()
+        };
+        for token in tokens[..command_index].iter() {
+            match env_assignment(token) {
+                Some((name, value)) if res.wrapper.is_empty() => {
+                    res.env.push((name.to_string(), value.to_string()))
+                }
+                _ => res.wrapper.push(token.clone()),
+            }
+        }
+        res.args = tokens[command_index + 1..].to_vec();
+        res
+    }
+
+    /// The value of an environment variable, as written.
+    pub fn env_var(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Remove an environment variable, returns whether it was set.
+    ///
+    /// `%command%` is kept, so the arguments stay after the command.
+    pub fn remove_env(&mut self, name: &str) -> bool {
+        let len = self.env.len();
+        self.env.retain(|(n, _)| n != name);
+        self.env.len() != len
+    }
+
+    /// Replace a wrapper with another, returns whether it was found.
+    ///
+    /// Both are command lines, and `from` matches the same words in the wrappers, e.g.
+    /// `gamescope -w 1280 --` can be replaced with `gamescope -W 1280 --`.
+    pub fn replace_wrapper(&mut self, from: &str, to: &str) -> bool {
+        let from = split_tokens(from);
+        if from.is_empty() {
+            return false;
+        }
+        match self.wrapper.windows(from.len()).position(|w| w == from) {
+            Some(start) => {
+                self.wrapper
+                    .splice(start..start + from.len(), split_tokens(to));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replace the start of paths, returns whether any path was changed.
+    ///
+    /// Every environment variable value, wrapper and argument that starts with `from` (inside
+    /// its quotes, or after the `=` of `--option=path`) gets `to` instead. Paths that get
+    /// whitespace or characters the shell would interpret are quoted.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::launch_options::LaunchOptions;
+    ///
+    /// let mut options = LaunchOptions::parse(
+    ///     "STEAM_COMPAT_DATA_PATH=/old/pfx /old/proton run %command% --log=/old/log.txt",
+    /// );
+    /// assert!(options.replace_path_prefix("/old", "/mnt/new games"));
+    /// assert_eq!(
+    ///     "STEAM_COMPAT_DATA_PATH=\"/mnt/new games/pfx\" \"/mnt/new games/proton\" run %command% \"--log=/mnt/new games/log.txt\"",
+    ///     options.to_string()
+    /// );
+    /// ```
+    pub fn replace_path_prefix(&mut self, from: &str, to: &str) -> bool {
+        if from.is_empty() {
+            return false;
+        }
+        let mut changed = false;
+        let tokens = self
+            .env
+            .iter_mut()
+            .map(|(_, value)| value)
+            .chain(self.wrapper.iter_mut())
+            .chain(self.args.iter_mut());
+        for token in tokens {
+            let replaced = replace_prefix(token, from, to).or_else(|| {
+                let (option, path) = token.split_once('=')?;
+                let path = replace_prefix(path, from, to)?;
+                Some(quote_if_needed(&format!("{}={}", option, unquoted(&path))))
+            });
+            if let Some(replaced) = replaced {
+                *token = replaced;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Rewrite the launch options of many shortcuts, returns how many shortcuts changed.
+///
+/// The launch options of each shortcut are parsed and given to `rewrite`. They are only written
+/// back if they changed, so the options of the other shortcuts keep their formatting.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::launch_options::rewrite_launch_options;
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let mut celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+/// celeste.launch_options = "gamemoderun %command%".to_string();
+/// let mut shortcuts = vec![celeste];
+/// let changed = rewrite_launch_options(&mut shortcuts, |options| {
+///     options.replace_wrapper("gamemoderun", "mangohud");
+/// });
+/// assert_eq!(1, changed);
+/// assert_eq!("mangohud %command%", shortcuts[0].launch_options);
+/// ```
+pub fn rewrite_launch_options<F: FnMut(&mut LaunchOptions)>(
+    shortcuts: &mut [ShortcutOwned],
+    mut rewrite: F,
+) -> usize {
+    let mut changed = 0;
+    for shortcut in shortcuts.iter_mut() {
+        if rewrite_one(shortcut, &mut rewrite) {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Set an environment variable in the launch options of the shortcuts that match `filter`,
+/// returns how many shortcuts changed.
+///
+/// Shortcuts with plain arguments as launch options get `%command%` before them, as steam
+/// only sets the variables of options with `%command%`. A variable that is already set gets
+/// the new value.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::launch_options::set_env_var;
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let mut celeste = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+/// celeste.launch_options = "-novid".to_string();
+/// let spore = Shortcut::new("1", "Spore", "spore.exe", "", "", "", "").to_owned();
+/// let mut shortcuts = vec![celeste, spore];
+/// let changed = set_env_var(&mut shortcuts, |s| s.app_name == "Celeste", "MANGOHUD", "1");
+/// assert_eq!(1, changed);
+/// assert_eq!("MANGOHUD=1 %command% -novid", shortcuts[0].launch_options);
+/// assert_eq!("", shortcuts[1].launch_options);
+/// ```
+pub fn set_env_var<F: FnMut(&ShortcutOwned) -> bool>(
+    shortcuts: &mut [ShortcutOwned],
+    mut filter: F,
+    name: &str,
+    value: &str,
+) -> usize {
+    let mut changed = 0;
+    for shortcut in shortcuts.iter_mut().filter(|shortcut| filter(shortcut)) {
+        let set =
+            |options: &mut LaunchOptions| *options = core::mem::take(options).env(name, value);
+        if rewrite_one(shortcut, set) {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Remove an environment variable from the launch options of the shortcuts that match
+/// `filter`, returns how many shortcuts changed.
+///
+/// When no variable or wrapper is left, `%command%` is removed as well, so options set with
+/// [set_env_var] go back to what they were, e.g. `MANGOHUD=1 %command%` becomes empty.
+pub fn remove_env_var<F: FnMut(&ShortcutOwned) -> bool>(
+    shortcuts: &mut [ShortcutOwned],
+    mut filter: F,
+    name: &str,
+) -> usize {
+    let mut changed = 0;
+    for shortcut in shortcuts.iter_mut().filter(|shortcut| filter(shortcut)) {
+        let remove = |options: &mut LaunchOptions| {
+            if options.remove_env(name) && options.env.is_empty() && options.wrapper.is_empty() {
+                options.has_command = false;
+            }
+        };
+        if rewrite_one(shortcut, remove) {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Rewrite the launch options of a shortcut, returns whether they changed.
+fn rewrite_one<F: FnMut(&mut LaunchOptions)>(shortcut: &mut ShortcutOwned, mut rewrite: F) -> bool {
+    let parsed = LaunchOptions::parse(&shortcut.launch_options);
+    let mut options = parsed.clone();
+    rewrite(&mut options);
+    if options == parsed {
+        return false;
+    }
+    shortcut.launch_options = options.to_string();
+    true
+}
+
+impl fmt::Display for LaunchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self
+            .env
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        parts.extend(self.wrapper.iter().cloned());
+        if self.needs_command() {
+            parts.push(COMMAND.to_string());
+        }
+        parts.extend(self.args.iter().cloned());
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+impl core::str::FromStr for LaunchOptions {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+/// Split on whitespace outside of quotes, keeping the quotes in the tokens.
+fn split_tokens(input: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            ('"', None) | ('\'', None) => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (c, None) if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(core::mem::take(&mut current));
+                }
+            }
+            (c, _) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Characters the shell that runs the launch options on Linux gives a meaning.
+const SHELL_SPECIAL: [char; 11] = [';', '&', '|', '$', '`', '"', '\'', '<', '>', '(', ')'];
+
+/// The value in double quotes if the shell would split or interpret it, with the characters
+/// the shell still expands inside double quotes escaped.
+pub(crate) fn quote_if_needed(value: &str) -> String {
+    let needs_quotes = |c: char| c.is_whitespace() || SHELL_SPECIAL.contains(&c);
+    if !value.is_empty() && !value.contains(needs_quotes) {
+        return value.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '$' | '`' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            // A backslash only escapes these, or the closing quote at the end
+            '\\' if matches!(chars.peek(), None | Some('"' | '$' | '`' | '\\')) => {
+                quoted.push_str("\\\\")
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The token without the quotes around it.
+pub(crate) fn unquoted(token: &str) -> &str {
+    for quote in ['"', '\''] {
+        if token.len() > 1 && token.starts_with(quote) && token.ends_with(quote) {
+            return &token[1..token.len() - 1];
+        }
+    }
+    token
+}
+
+/// The token with `from` at its start (inside its quotes) replaced with `to`.
+fn replace_prefix(token: &str, from: &str, to: &str) -> Option<String> {
+    let inner = unquoted(token);
+    let rest = inner.strip_prefix(from)?;
+    let replaced = format!("{}{}", to, rest);
+    if inner.len() == token.len() {
+        Some(quote_if_needed(&replaced))
+    } else {
+        let quote = &token[..1];
+        Some(format!("{}{}{}", quote, replaced, quote))
+    }
+}
+
+/// Split `NAME=value` into its name and value, if the token is an assignment.
+fn env_assignment(token: &str) -> Option<(&str, &str)> {
+    let (name, value) = token.split_once('=')?;
+    let valid_name = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid_name {
+        Some((name, value))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_full_options() {
+        let text = r#"DXVK_HUD=fps WINEDLLOVERRIDES="dinput8=n,b" gamescope -w 1280 -- mangohud %command% -novid "+exec my config.cfg""#;
+        let options = LaunchOptions::parse(text);
+        assert_eq!(
+            vec![
+                ("DXVK_HUD".to_string(), "fps".to_string()),
+                (
+                    "WINEDLLOVERRIDES".to_string(),
+                    "\"dinput8=n,b\"".to_string()
+                ),
+            ],
+            options.env
+        );
+        assert_eq!(
+            vec!["gamescope", "-w", "1280", "--", "mangohud"],
+            options.wrapper
+        );
+        assert!(options.has_command);
+        assert_eq!(vec!["-novid", "\"+exec my config.cfg\""], options.args);
+        assert_eq!(text, options.to_string());
+    }
+
+    #[test]
+    fn without_command_everything_is_an_argument() {
+        let options = LaunchOptions::parse("  -windowed   LANG=C  ");
+        assert!(!options.has_command);
+        assert!(options.env.is_empty());
+        assert_eq!(vec!["-windowed", "LANG=C"], options.args);
+        assert_eq!("-windowed LANG=C", options.to_string());
+    }
+
+    #[test]
+    fn round_trips_test_file() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        for shortcut in crate::parse_shortcuts(content.as_slice()).unwrap() {
+            let normalized = split_tokens(shortcut.launch_options).join(" ");
+            assert_eq!(
+                normalized,
+                LaunchOptions::parse(shortcut.launch_options).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn build_options() {
+        let options = LaunchOptions::new()
+            .env("DXVK_HUD", "fps")
+            .env("DXVK_HUD", "full")
+            .env("WINEDLLOVERRIDES", "a b")
+            .gamescope(1280, 800)
+            .mangohud()
+            .arg("+exec my config.cfg");
+        assert_eq!(
+            "DXVK_HUD=full WINEDLLOVERRIDES=\"a b\" gamescope -W 1280 -H 800 -- mangohud %command% \"+exec my config.cfg\"",
+            options.to_string()
+        );
+        let reparsed = LaunchOptions::parse(&options.to_string());
+        assert_eq!(options.env, reparsed.env);
+        assert_eq!(options.wrapper, reparsed.wrapper);
+        assert_eq!(options.args, reparsed.args);
+    }
+
+    #[test]
+    fn shell_characters_are_quoted() {
+        let options = LaunchOptions::new()
+            .env("PROTON_LOG", "1;rm -rf ~")
+            .env("DXVK_CONFIG_FILE", "$HOME/dxvk.conf")
+            .arg("a&b")
+            .arg("a|b")
+            .arg("say \"hi\" `id`")
+            .arg("C:\\Games\\Celeste");
+        assert_eq!(
+            r#"PROTON_LOG="1;rm -rf ~" DXVK_CONFIG_FILE="\$HOME/dxvk.conf" %command% "a&b" "a|b" "say \"hi\" \`id\`" C:\Games\Celeste"#,
+            options.to_string()
+        );
+        assert_eq!(r#""""#, quote_if_needed(""));
+        assert_eq!(r#""C:\Games\ 2\\\$""#, quote_if_needed(r#"C:\Games\ 2\$"#));
+        let reparsed = LaunchOptions::parse(&options.to_string());
+        assert_eq!(options.env, reparsed.env);
+        assert_eq!(options.args, reparsed.args);
+    }
+
+    #[test]
+    fn edit_parsed_options() {
+        let options = LaunchOptions::parse("-novid").gamemode();
+        assert_eq!("gamemoderun %command% -novid", options.to_string());
+    }
+
+    #[test]
+    fn rewrite_only_changed_options() {
+        let mut shortcuts: Vec<ShortcutOwned> = ["gamescope -w 1280 -- %command%", "  -novid  "]
+            .iter()
+            .map(|options| {
+                let mut shortcut =
+                    crate::Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+                shortcut.launch_options = options.to_string();
+                shortcut
+            })
+            .collect();
+        let changed = rewrite_launch_options(&mut shortcuts, |options| {
+            options.replace_wrapper("gamescope -w 1280 --", "gamescope -W 1280 --");
+            options.replace_path_prefix("/old", "/new");
+        });
+        assert_eq!(1, changed);
+        assert_eq!(
+            "gamescope -W 1280 -- %command%",
+            shortcuts[0].launch_options
+        );
+        assert_eq!("  -novid  ", shortcuts[1].launch_options);
+    }
+
+    #[test]
+    fn replace_quoted_path_prefix() {
+        let mut options = LaunchOptions::parse(r#"'/old/a b' %command% "/old/c" /older"#);
+        assert!(options.replace_path_prefix("/old/", "/new/"));
+        assert_eq!(
+            r#"'/new/a b' %command% "/new/c" /older"#,
+            options.to_string()
+        );
+        assert!(!options.replace_path_prefix("/missing", "/new"));
+        assert!(!options.replace_wrapper("gamemoderun", "mangohud"));
+    }
+
+    #[test]
+    fn set_and_remove_env_vars() {
+        let mut shortcuts: Vec<ShortcutOwned> = ["", "MANGOHUD=0 gamemoderun %command%", "-novid"]
+            .iter()
+            .map(|options| {
+                let mut shortcut =
+                    crate::Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+                shortcut.launch_options = options.to_string();
+                shortcut
+            })
+            .collect();
+        assert_eq!(3, set_env_var(&mut shortcuts, |_| true, "MANGOHUD", "1"));
+        assert_eq!("MANGOHUD=1 %command%", shortcuts[0].launch_options);
+        assert_eq!(
+            "MANGOHUD=1 gamemoderun %command%",
+            shortcuts[1].launch_options
+        );
+        assert_eq!("MANGOHUD=1 %command% -novid", shortcuts[2].launch_options);
+        assert_eq!(0, set_env_var(&mut shortcuts, |_| true, "MANGOHUD", "1"));
+
+        assert_eq!(3, remove_env_var(&mut shortcuts, |_| true, "MANGOHUD"));
+        assert_eq!("", shortcuts[0].launch_options);
+        assert_eq!("gamemoderun %command%", shortcuts[1].launch_options);
+        assert_eq!("-novid", shortcuts[2].launch_options);
+        assert_eq!(0, remove_env_var(&mut shortcuts, |_| true, "MANGOHUD"));
+
+        shortcuts[0].launch_options = "%command% -novid".to_string();
+        assert_eq!(0, remove_env_var(&mut shortcuts, |_| true, "MANGOHUD"));
+        assert_eq!("%command% -novid", shortcuts[0].launch_options);
+    }
+
+    #[test]
+    fn empty_options() {
+        assert_eq!(LaunchOptions::default(), LaunchOptions::parse(""));
+        assert_eq!("", LaunchOptions::default().to_string());
+    }
+}
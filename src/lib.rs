@@ -33,14 +33,144 @@
 //! ```
 //!
 //! *Be aware that if you overwrite the shortcuts.vdf file, you will have to restart Steam for the changes to take effect.*
+//!
+//! ## no_std
+//!
+//! Parsing, writing and editing shortcuts only needs `alloc`. Turn off the default `std` feature
+//! to use the crate without the standard library, everything that touches files, paths or
+//! processes is left out then.
+//!
+//! The default `nom` feature reads shortcuts.vdf with [nom](https://docs.rs/nom) parsers. Turn it
+//! off as well to read the same format with a few hand written functions instead, then the only
+//! dependencies of the parser and writer are `memchr` and `crc32fast`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 pub mod app_id_generator;
+#[cfg(feature = "std")]
+pub mod artwork;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+#[cfg(feature = "std")]
+pub mod backups;
+#[cfg(feature = "serde")]
+pub mod binary_vdf;
+mod bytes;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "json")]
+pub mod collections;
+#[cfg(feature = "std")]
+pub mod compat_tools;
+#[cfg(feature = "std")]
+pub mod controller_config;
+pub mod debug;
+pub mod dedupe;
+pub mod diff;
+#[cfg(feature = "exe_icon")]
+pub mod exe_icon;
+#[cfg(feature = "std")]
+pub mod exporters;
+#[cfg(feature = "std")]
+pub mod icons;
+#[cfg(feature = "std")]
+pub mod importers;
+#[cfg(feature = "json")]
+mod json;
+pub mod launch_options;
+mod matching;
+pub mod merge;
+#[cfg(feature = "std")]
+pub mod orphans;
+pub mod path_translation;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod report;
+#[cfg(feature = "std")]
+pub mod shared_config;
 pub mod shortcut;
+#[cfg(any(feature = "json", feature = "toml"))]
+mod shortcut_list;
+pub mod shortcut_view;
+pub mod shortcuts;
+#[cfg(feature = "std")]
+pub mod shortcuts_file;
+#[cfg(feature = "json")]
+pub mod shortcuts_json;
+#[cfg(feature = "keyvalues_parser")]
+pub mod shortcuts_keyvalues;
+#[cfg(feature = "std")]
+pub mod shortcuts_manager;
 pub mod shortcuts_parser;
+#[cfg(feature = "std")]
+pub mod shortcuts_text_vdf;
+#[cfg(feature = "toml")]
+pub mod shortcuts_toml;
 pub mod shortcuts_writer;
+#[cfg(feature = "std")]
+pub mod steam_apps;
+#[cfg(feature = "std")]
+pub mod steam_dirs;
+pub mod steam_id;
+#[cfg(feature = "std")]
+pub mod steam_process;
+#[cfg(feature = "steamgriddb")]
+pub mod steamgriddb;
+#[cfg(feature = "std")]
+pub mod sync;
+pub mod template;
+#[cfg(all(test, feature = "std"))]
+mod test_util;
+#[cfg(feature = "proptest")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod text_vdf;
+#[cfg(feature = "uniffi")]
+pub mod uniffi_bindings;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
 
 // Re-exports
-pub use app_id_generator::calculate_app_id_for_shortcut;
+pub use app_id_generator::{
+    as_signed, calculate_app_id_bytes, calculate_app_id_for_shortcut, calculate_game_id,
+    find_app_id_collisions, find_stale_app_ids, from_signed, recalculate_app_ids, AppIdChange,
+};
+pub use dedupe::dedupe;
+pub use diff::{diff, ShortcutDiff};
+pub use matching::MatchBy;
+pub use merge::{
+    merge_shortcuts, merge_shortcuts_with, ConflictStrategy, MergePolicy, MergeStrategy,
+};
+#[cfg(feature = "std")]
+pub use orphans::find_orphans;
 pub use shortcut::Shortcut;
-pub use shortcuts_parser::parse_shortcuts;
-pub use shortcuts_writer::shortcuts_to_bytes;
+pub use shortcut_view::{parse_shortcut_views, ShortcutView};
+pub use shortcuts::Shortcuts;
+#[cfg(feature = "rayon")]
+pub use shortcuts_file::parse_many;
+#[cfg(feature = "std")]
+pub use shortcuts_file::{ShortcutsFile, ShortcutsFileError};
+#[cfg(feature = "json")]
+pub use shortcuts_json::{from_json, to_json};
+#[cfg(feature = "std")]
+pub use shortcuts_manager::ShortcutsManager;
+pub use shortcuts_parser::{
+    parse_shortcuts, parse_shortcuts_with, Diagnostic, DuplicateKeyPolicy, InvalidOrderPolicy,
+    Limits, ParseOptions, Parsed, TrailingBytesPolicy,
+};
+pub use shortcuts_writer::{
+    shortcuts_len, shortcuts_to_bytes, shortcuts_to_bytes_for, shortcuts_to_bytes_with,
+    shortcuts_to_bytes_with_spelling, write_shortcuts_into, ClientProfile, WriteOptions,
+};
+#[cfg(feature = "std")]
+pub use steam_apps::find_installed_app_collisions;
+#[cfg(feature = "std")]
+pub use steam_process::steam_is_running;
+pub use template::ShortcutTemplate;
@@ -0,0 +1,161 @@
+//! Finding the same shortcut in two lists, or twice in one list, by an identity key.
+//!
+//! [merge](crate::merge), [diff](crate::diff), [dedupe](crate::dedupe) and
+//! [Shortcuts::contains](crate::Shortcuts::contains) all match shortcuts with the keys of
+//! [MatchBy], looked up in a map instead of comparing every pair.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::dedupe::normalize_name;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// What makes two shortcuts the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchBy {
+    /// The same app id
+    AppId,
+    /// The same exe and app_name
+    ExeAndName,
+    /// The same app_name, ignoring case, whitespace, punctuation and symbols like ™
+    NormalizedName,
+}
+
+/// The key of a shortcut for a [MatchBy].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Key<'s> {
+    AppId(u32),
+    ExeAndName(&'s str, &'s str),
+    NormalizedName(String),
+}
+
+/// The fields of a shortcut that identify it, for borrowed and owned shortcuts.
+pub(crate) trait Identity {
+    fn app_id(&self) -> u32;
+    fn exe(&self) -> &str;
+    fn app_name(&self) -> &str;
+}
+
+impl Identity for Shortcut<'_> {
+    fn app_id(&self) -> u32 {
+        self.app_id
+    }
+
+    fn exe(&self) -> &str {
+        self.exe
+    }
+
+    fn app_name(&self) -> &str {
+        self.app_name
+    }
+}
+
+impl Identity for ShortcutOwned {
+    fn app_id(&self) -> u32 {
+        self.app_id
+    }
+
+    fn exe(&self) -> &str {
+        &self.exe
+    }
+
+    fn app_name(&self) -> &str {
+        &self.app_name
+    }
+}
+
+impl MatchBy {
+    pub(crate) fn key<'s, S: Identity>(self, shortcut: &'s S) -> Key<'s> {
+        match self {
+            MatchBy::AppId => Key::AppId(shortcut.app_id()),
+            MatchBy::ExeAndName => Key::ExeAndName(shortcut.exe(), shortcut.app_name()),
+            MatchBy::NormalizedName => Key::NormalizedName(normalize_name(shortcut.app_name())),
+        }
+    }
+}
+
+/// Matches shortcuts to a list, each shortcut of the list at most once.
+///
+/// A shortcut is looked up by the keys in the order they are given, and matches the first
+/// shortcut of the list with that key that was not matched yet.
+pub(crate) struct Matcher<'s> {
+    indexes: Vec<(MatchBy, BTreeMap<Key<'s>, Vec<usize>>)>,
+    matched: Vec<bool>,
+}
+
+impl<'s> Matcher<'s> {
+    pub(crate) fn new<S: Identity>(list: &'s [S], keys: &[MatchBy]) -> Self {
+        let indexes = keys
+            .iter()
+            .map(|&by| {
+                let mut index: BTreeMap<Key<'s>, Vec<usize>> = BTreeMap::new();
+                // Reversed, so the first shortcut with a key is popped first
+                for (position, shortcut) in list.iter().enumerate().rev() {
+                    index.entry(by.key(shortcut)).or_default().push(position);
+                }
+                (by, index)
+            })
+            .collect();
+        Self {
+            indexes,
+            matched: vec![false; list.len()],
+        }
+    }
+
+    /// The position in the list of the shortcut that matches, which can not match again.
+    pub(crate) fn take<S: Identity>(&mut self, shortcut: &'s S) -> Option<usize> {
+        let matched = &mut self.matched;
+        for (by, index) in self.indexes.iter_mut() {
+            if let Some(positions) = index.get_mut(&by.key(shortcut)) {
+                while let Some(position) = positions.pop() {
+                    if !matched[position] {
+                        matched[position] = true;
+                        return Some(position);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether each shortcut of the list was matched, by position.
+    pub(crate) fn into_matched(self) -> Vec<bool> {
+        self.matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn matches_each_shortcut_once_by_the_first_key_that_fits() {
+        let mut renamed = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        renamed.app_id = 1;
+        let list = vec![
+            renamed,
+            Shortcut::new("1", "Celeste", "celeste.exe", "", "", "", ""),
+            Shortcut::new("2", "Spore", "spore.exe", "", "", "", ""),
+        ];
+        let mut matcher = Matcher::new(&list, &[MatchBy::AppId, MatchBy::ExeAndName]);
+        let celeste = Shortcut::new("", "Celeste", "celeste.exe", "", "", "", "");
+        assert_eq!(Some(1), matcher.take(&celeste));
+        assert_eq!(Some(0), matcher.take(&celeste));
+        assert_eq!(None, matcher.take(&celeste));
+        assert_eq!(vec![true, true, false], matcher.into_matched());
+    }
+
+    #[test]
+    fn keys_of_normalized_names() {
+        let a = Shortcut::new("0", "CELESTE™", "a.exe", "", "", "", "");
+        let b = Shortcut::new("1", "celeste", "b.exe", "", "", "", "");
+        assert_eq!(
+            MatchBy::NormalizedName.key(&a),
+            MatchBy::NormalizedName.key(&b)
+        );
+        assert_ne!(MatchBy::ExeAndName.key(&a), MatchBy::ExeAndName.key(&b));
+    }
+}
@@ -0,0 +1,390 @@
+//! Merging a freshly imported list of shortcuts into the existing ones.
+
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::diff::{field_changes, FieldChange, MATCH_BY};
+use crate::matching::Matcher;
+use crate::shortcut::ShortcutOwned;
+
+/// What to keep from an existing shortcut when an incoming shortcut updates it.
+///
+/// The fields that are not kept are taken from the incoming shortcut.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePolicy {
+    /// Keep the tags (collections) of the existing shortcut, and add the incoming tags it misses
+    pub keep_tags: bool,
+    /// Keep the launch options of the existing shortcut
+    pub keep_launch_options: bool,
+    /// Keep the last play time of the existing shortcut
+    pub keep_last_play_time: bool,
+    /// Keep whether the existing shortcut is hidden
+    pub keep_hidden: bool,
+    /// Remove existing shortcuts that are not in the incoming list
+    pub remove_missing: bool,
+}
+
+impl Default for MergePolicy {
+    /// Keep all user edits and never remove shortcuts.
+    fn default() -> Self {
+        Self {
+            keep_tags: true,
+            keep_launch_options: true,
+            keep_last_play_time: true,
+            keep_hidden: true,
+            remove_missing: false,
+        }
+    }
+}
+
+/// Merge the incoming shortcuts into the existing ones.
+///
+/// An incoming shortcut matches an existing one with the same app id, or else with
+/// the same exe and app_name. Matched shortcuts are updated in place, keeping the
+/// fields the policy says to keep. Incoming shortcuts without a match are added at
+/// the end. The order of the result is renumbered from 0.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{merge_shortcuts, MergePolicy, Shortcut};
+///
+/// let mut existing = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+/// existing.launch_options = "-windowed".to_string();
+/// let incoming = Shortcut::new("0", "Celeste", "celeste.exe", "", "celeste.ico", "", "").to_owned();
+///
+/// let merged = merge_shortcuts(&[existing], &[incoming], &MergePolicy::default());
+/// assert_eq!("-windowed", merged[0].launch_options);
+/// assert_eq!("celeste.ico", merged[0].icon);
+/// ```
+pub fn merge_shortcuts(
+    existing: &[ShortcutOwned],
+    incoming: &[ShortcutOwned],
+    policy: &MergePolicy,
+) -> Vec<ShortcutOwned> {
+    merge_by(existing, incoming, policy.remove_missing, |old, new| {
+        update(old, new, policy)
+    })
+}
+
+/// How a field is merged when an existing and incoming shortcut have different values for it.
+pub enum ConflictStrategy<'f> {
+    /// Keep the value of the existing shortcut
+    PreferExisting,
+    /// Take the value of the incoming shortcut
+    PreferIncoming,
+    /// Take the value of the shortcut that was played last, or the incoming value if the
+    /// existing shortcut was not played later
+    Newest,
+    /// Let a function choose, e.g. by asking the user
+    Ask(&'f dyn Fn(&Conflict) -> Side),
+}
+
+/// A field with different values in an existing and incoming shortcut, given to
+/// [ConflictStrategy::Ask].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict<'c> {
+    pub existing: &'c ShortcutOwned,
+    pub incoming: &'c ShortcutOwned,
+    /// The field and its values, with the existing value as `old`
+    pub change: FieldChange,
+}
+
+/// The shortcut whose value is kept for a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Existing,
+    Incoming,
+}
+
+/// How [merge_shortcuts_with] resolves each field that differs between matched shortcuts.
+pub struct MergeStrategy<'f> {
+    /// The strategy for fields that are not in `fields`
+    pub default: ConflictStrategy<'f>,
+    /// Strategies for single fields, by the name they are written with in shortcuts.vdf
+    /// (as in [FieldChange::field], e.g. `LaunchOptions`)
+    pub fields: Vec<(&'static str, ConflictStrategy<'f>)>,
+    /// Remove existing shortcuts that are not in the incoming list
+    pub remove_missing: bool,
+}
+
+impl<'f> MergeStrategy<'f> {
+    /// Resolve every field with the same strategy.
+    pub fn new(default: ConflictStrategy<'f>) -> Self {
+        Self {
+            default,
+            fields: vec![],
+            remove_missing: false,
+        }
+    }
+
+    /// Resolve a field with its own strategy.
+    pub fn field(mut self, field: &'static str, strategy: ConflictStrategy<'f>) -> Self {
+        self.fields.push((field, strategy));
+        self
+    }
+
+    fn strategy(&self, field: &str) -> &ConflictStrategy<'f> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(name, _)| name.eq_ignore_ascii_case(field))
+            .map(|(_, strategy)| strategy)
+            .unwrap_or(&self.default)
+    }
+}
+
+/// Merge the incoming shortcuts into the existing ones, choosing per field which value to keep.
+///
+/// Shortcuts are matched, added and renumbered as by [merge_shortcuts]. For every field that
+/// differs between matched shortcuts the strategy of the field picks the existing or incoming
+/// value. Tags are one field, so they are not combined.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::merge::{merge_shortcuts_with, ConflictStrategy, MergeStrategy, Side};
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let mut existing = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+/// existing.launch_options = "-windowed".to_string();
+/// existing.icon = "old.ico".to_string();
+/// let mut incoming = existing.clone();
+/// incoming.launch_options = "-fullscreen".to_string();
+/// incoming.icon = "celeste.ico".to_string();
+///
+/// let ask = |conflict: &steam_shortcuts_util::merge::Conflict| {
+///     println!("{}: {} or {}?", conflict.change.field, conflict.change.old, conflict.change.new);
+///     Side::Existing
+/// };
+/// let strategy = MergeStrategy::new(ConflictStrategy::PreferIncoming)
+///     .field("LaunchOptions", ConflictStrategy::Ask(&ask));
+/// let merged = merge_shortcuts_with(&[existing], &[incoming], &strategy);
+/// assert_eq!("-windowed", merged[0].launch_options);
+/// assert_eq!("celeste.ico", merged[0].icon);
+/// ```
+pub fn merge_shortcuts_with(
+    existing: &[ShortcutOwned],
+    incoming: &[ShortcutOwned],
+    strategy: &MergeStrategy,
+) -> Vec<ShortcutOwned> {
+    merge_by(existing, incoming, strategy.remove_missing, |old, new| {
+        resolve(old, new, strategy)
+    })
+}
+
+fn merge_by<F: Fn(&ShortcutOwned, &ShortcutOwned) -> ShortcutOwned>(
+    existing: &[ShortcutOwned],
+    incoming: &[ShortcutOwned],
+    remove_missing: bool,
+    update: F,
+) -> Vec<ShortcutOwned> {
+    let mut merged: Vec<Option<ShortcutOwned>> = existing
+        .iter()
+        .map(|shortcut| {
+            if remove_missing {
+                None
+            } else {
+                Some(shortcut.clone())
+            }
+        })
+        .collect();
+    let mut matcher = Matcher::new(existing, &MATCH_BY);
+    let mut added = vec![];
+    for new in incoming {
+        match matcher.take(new) {
+            Some(index) => {
+                merged[index] = Some(update(&existing[index], new));
+            }
+            None => added.push(new.clone()),
+        }
+    }
+    let mut res: Vec<ShortcutOwned> = merged.into_iter().flatten().chain(added).collect();
+    for (index, shortcut) in res.iter_mut().enumerate() {
+        shortcut.order = index.to_string();
+    }
+    res
+}
+
+fn update(old: &ShortcutOwned, new: &ShortcutOwned, policy: &MergePolicy) -> ShortcutOwned {
+    let mut res = new.clone();
+    if policy.keep_tags {
+        res.tags = old.tags.clone();
+        for tag in new.tags.iter() {
+            if !res.tags.contains(tag) {
+                res.tags.push(tag.clone());
+            }
+        }
+    }
+    if policy.keep_launch_options {
+        res.launch_options = old.launch_options.clone();
+    }
+    if policy.keep_last_play_time {
+        res.last_play_time = old.last_play_time;
+    }
+    if policy.keep_hidden {
+        res.is_hidden = old.is_hidden;
+    }
+    res
+}
+
+fn resolve(old: &ShortcutOwned, new: &ShortcutOwned, strategy: &MergeStrategy) -> ShortcutOwned {
+    let mut res = new.clone();
+    for change in field_changes(&old.borrow(), &new.borrow()) {
+        let side = match strategy.strategy(change.field) {
+            ConflictStrategy::PreferExisting => Side::Existing,
+            ConflictStrategy::PreferIncoming => Side::Incoming,
+            ConflictStrategy::Newest if old.last_play_time > new.last_play_time => Side::Existing,
+            ConflictStrategy::Newest => Side::Incoming,
+            ConflictStrategy::Ask(ask) => ask(&Conflict {
+                existing: old,
+                incoming: new,
+                change: change.clone(),
+            }),
+        };
+        if side == Side::Existing {
+            copy_field(&mut res, old, change.field);
+        }
+    }
+    res
+}
+
+/// Copy a field, by its name in shortcuts.vdf, from one shortcut to another.
+fn copy_field(to: &mut ShortcutOwned, from: &ShortcutOwned, field: &str) {
+    match field {
+        "appid" => to.app_id = from.app_id,
+        "AppName" => to.app_name = from.app_name.clone(),
+        "Exe" => to.exe = from.exe.clone(),
+        "StartDir" => to.start_dir = from.start_dir.clone(),
+        "icon" => to.icon = from.icon.clone(),
+        "ShortcutPath" => to.shortcut_path = from.shortcut_path.clone(),
+        "LaunchOptions" => to.launch_options = from.launch_options.clone(),
+        "IsHidden" => to.is_hidden = from.is_hidden,
+        "AllowDesktopConfig" => to.allow_desktop_config = from.allow_desktop_config,
+        "AllowOverlay" => to.allow_overlay = from.allow_overlay,
+        "openvr" => to.open_vr = from.open_vr,
+        "Devkit" => to.dev_kit = from.dev_kit,
+        "DevkitGameID" => to.dev_kit_game_id = from.dev_kit_game_id.clone(),
+        "DevkitOverrideAppID" => to.dev_kit_overrite_app_id = from.dev_kit_overrite_app_id,
+        "LastPlayTime" => to.last_play_time = from.last_play_time,
+        "FlatpakAppID" => to.flatpak_app_id = from.flatpak_app_id.clone(),
+        "tags" => to.tags = from.tags.clone(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+
+    fn shortcut(order: &str, name: &str, exe: &str) -> ShortcutOwned {
+        Shortcut::new(order, name, exe, "", "", "", "").to_owned()
+    }
+
+    #[test]
+    fn keeps_user_edits() {
+        let mut celeste = shortcut("0", "Celeste", "celeste.exe");
+        celeste.tags.push("Favorite".to_string());
+        celeste.launch_options = "-windowed".to_string();
+        celeste.last_play_time = 1234;
+        celeste.is_hidden = true;
+        let spore = shortcut("1", "Spore", "spore.exe");
+
+        let mut new_celeste = shortcut("0", "Celeste", "celeste.exe");
+        new_celeste.icon = "celeste.ico".to_string();
+        new_celeste.tags.push("Installed".to_string());
+        new_celeste.tags.push("Favorite".to_string());
+        let hades = shortcut("1", "Hades", "hades.exe");
+
+        let merged = merge_shortcuts(
+            &[celeste, spore],
+            &[new_celeste, hades],
+            &MergePolicy::default(),
+        );
+        let names: Vec<&str> = merged.iter().map(|s| s.app_name.as_str()).collect();
+        assert_eq!(vec!["Celeste", "Spore", "Hades"], names);
+        let orders: Vec<&str> = merged.iter().map(|s| s.order.as_str()).collect();
+        assert_eq!(vec!["0", "1", "2"], orders);
+
+        let celeste = &merged[0];
+        assert_eq!("celeste.ico", celeste.icon);
+        assert_eq!(["Favorite", "Installed"], celeste.tags[..]);
+        assert_eq!("-windowed", celeste.launch_options);
+        assert_eq!(1234, celeste.last_play_time);
+        assert!(celeste.is_hidden);
+    }
+
+    #[test]
+    fn overwrite_and_remove_missing() {
+        let mut celeste = shortcut("0", "Celeste", "celeste.exe");
+        celeste.launch_options = "-windowed".to_string();
+        celeste.tags.push("Favorite".to_string());
+        let spore = shortcut("1", "Spore", "spore.exe");
+        let policy = MergePolicy {
+            keep_tags: false,
+            keep_launch_options: false,
+            keep_last_play_time: false,
+            keep_hidden: false,
+            remove_missing: true,
+        };
+        let merged = merge_shortcuts(
+            &[celeste, spore],
+            &[shortcut("5", "Celeste", "celeste.exe")],
+            &policy,
+        );
+        assert_eq!(1, merged.len());
+        assert_eq!("", merged[0].launch_options);
+        assert!(merged[0].tags.is_empty());
+        assert_eq!("0", merged[0].order);
+    }
+
+    #[test]
+    fn matches_by_exe_and_name_when_app_id_differs() {
+        let mut old = shortcut("0", "Celeste", "celeste.exe");
+        old.app_id = 1;
+        old.launch_options = "-windowed".to_string();
+        let new = shortcut("0", "Celeste", "celeste.exe");
+        let merged = merge_shortcuts(&[old], std::slice::from_ref(&new), &MergePolicy::default());
+        assert_eq!(1, merged.len());
+        assert_eq!(new.app_id, merged[0].app_id);
+        assert_eq!("-windowed", merged[0].launch_options);
+    }
+
+    #[test]
+    fn strategies_per_field() {
+        let mut old = shortcut("0", "Celeste", "celeste.exe");
+        old.launch_options = "-windowed".to_string();
+        old.icon = "old.ico".to_string();
+        old.start_dir = "C:\\Games".to_string();
+        old.last_play_time = 10;
+        let mut new = shortcut("3", "Celeste", "celeste.exe");
+        new.launch_options = "-fullscreen".to_string();
+        new.icon = "new.ico".to_string();
+        new.start_dir = "D:\\Games".to_string();
+        new.last_play_time = 5;
+
+        let asked = core::cell::RefCell::new(vec![]);
+        let ask = |conflict: &Conflict| {
+            asked.borrow_mut().push(conflict.change.field);
+            Side::Incoming
+        };
+        let strategy = MergeStrategy::new(ConflictStrategy::PreferExisting)
+            .field("icon", ConflictStrategy::Ask(&ask))
+            .field("StartDir", ConflictStrategy::Newest);
+        let merged = merge_shortcuts_with(&[old.clone()], &[new.clone()], &strategy);
+        assert_eq!(vec!["icon"], *asked.borrow());
+        assert_eq!("-windowed", merged[0].launch_options);
+        assert_eq!("new.ico", merged[0].icon);
+        assert_eq!("C:\\Games", merged[0].start_dir);
+        assert_eq!(10, merged[0].last_play_time);
+
+        let merged = merge_shortcuts_with(
+            &[old],
+            &[new.clone()],
+            &MergeStrategy::new(ConflictStrategy::PreferIncoming),
+        );
+        assert_eq!(new.launch_options, merged[0].launch_options);
+        assert_eq!(5, merged[0].last_play_time);
+    }
+}
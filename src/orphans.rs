@@ -0,0 +1,141 @@
+//! Finding shortcuts whose game is gone, e.g. to offer cleaning up dead entries.
+
+use std::path::{Path, PathBuf};
+
+use crate::shortcut::Shortcut;
+
+/// What an [Orphan] misses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    /// The exe does not exist
+    Exe,
+    /// The Flatpak app of the FlatpakAppID field is not installed
+    FlatpakApp,
+    /// The icon is set, but the file does not exist
+    Icon,
+}
+
+/// A shortcut that points at something that does not exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    /// The index of the shortcut in the list
+    pub index: usize,
+    /// The app id of the shortcut
+    pub app_id: u32,
+    /// Everything the shortcut misses, never empty
+    pub missing: Vec<Missing>,
+}
+
+/// Check that the exe, Flatpak app and icon of every shortcut exist on this machine.
+///
+/// The exe is unquoted first. An exe without a directory, like `flatpak`, is looked up in the
+/// `PATH`, and a relative exe is looked up in the start dir. Flatpak apps are looked up in the
+/// system and user installation. An empty icon is not reported, steam shows its default icon.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::orphans::{find_orphans, Missing};
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let gone = Shortcut::new("0", "Gone", "\"/no/such/game.exe\"", "", "", "", "");
+/// let orphans = find_orphans(&[gone]);
+/// assert_eq!(vec![Missing::Exe], orphans[0].missing);
+/// ```
+pub fn find_orphans(shortcuts: &[Shortcut]) -> Vec<Orphan> {
+    let flatpak_dirs = flatpak_dirs();
+    shortcuts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, shortcut)| {
+            let mut missing = vec![];
+            if !exe_exists(unquoted(shortcut.exe), unquoted(shortcut.start_dir)) {
+                missing.push(Missing::Exe);
+            }
+            let flatpak = shortcut.flatpak_app_id.trim();
+            if !flatpak.is_empty() && !flatpak_dirs.iter().any(|dir| dir.join(flatpak).is_dir()) {
+                missing.push(Missing::FlatpakApp);
+            }
+            let icon = unquoted(shortcut.icon);
+            if !icon.is_empty() && !Path::new(icon).is_file() {
+                missing.push(Missing::Icon);
+            }
+            if missing.is_empty() {
+                None
+            } else {
+                Some(Orphan {
+                    index,
+                    app_id: shortcut.app_id,
+                    missing,
+                })
+            }
+        })
+        .collect()
+}
+
+fn unquoted(path: &str) -> &str {
+    path.trim().trim_matches('"')
+}
+
+fn exe_exists(exe: &str, start_dir: &str) -> bool {
+    if exe.is_empty() {
+        return false;
+    }
+    let path = Path::new(exe);
+    if path.is_absolute() {
+        return path.exists();
+    }
+    if path.components().count() == 1 {
+        let in_path = std::env::var_os("PATH")
+            .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(path).is_file()))
+            .unwrap_or_default();
+        if in_path {
+            return true;
+        }
+    }
+    !start_dir.is_empty() && Path::new(start_dir).join(path).exists()
+}
+
+/// The directories Flatpak installs apps in.
+fn flatpak_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("/var/lib/flatpak/app")];
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(Path::new(&home).join(".local/share/flatpak/app"));
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn finds_what_is_missing() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let exe = dir.join("game.exe");
+        std::fs::write(&exe, b"").unwrap();
+        let exe = format!("\"{}\"", exe.display());
+        let start_dir = format!("\"{}\"", dir.display());
+
+        let installed = Shortcut::new("0", "Installed", &exe, "", "", "", "");
+        let relative = Shortcut::new("1", "Relative", "game.exe", &start_dir, "", "", "");
+        let mut broken = Shortcut::new(
+            "2",
+            "Broken",
+            "gone.exe",
+            &start_dir,
+            "/no/icon.png",
+            "",
+            "",
+        );
+        broken.flatpak_app_id = "com.example.NotInstalled";
+        let orphans = find_orphans(&[installed, relative, broken]);
+
+        assert_eq!(1, orphans.len());
+        assert_eq!(2, orphans[0].index);
+        let missing = vec![Missing::Exe, Missing::FlatpakApp, Missing::Icon];
+        assert_eq!(missing, orphans[0].missing);
+    }
+}
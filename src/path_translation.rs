@@ -0,0 +1,179 @@
+//! Translating the paths of shortcuts between Windows and Linux, e.g. to share a library between
+//! the installs of steam on a dual-boot machine.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::shortcut::ShortcutOwned;
+
+/// The kind of paths a steam install uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    /// `C:\Games\celeste.exe`
+    Windows,
+    /// `/mnt/games/celeste.exe`
+    Unix,
+}
+
+/// Translates paths with a list of directories that are the same on Windows and Linux.
+///
+/// Windows directories match regardless of case and of `\` or `/`. When several directories
+/// match a path the longest one is used. The rest of the path gets the separators of the other
+/// style, and quotes around a path are kept.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::path_translation::{PathStyle, PathTranslator};
+///
+/// let translator = PathTranslator::new().map("C:\\Games", "/mnt/games");
+/// assert_eq!(
+///     Some("\"/mnt/games/Celeste/celeste.exe\"".to_string()),
+///     translator.translate("\"c:\\games\\Celeste\\celeste.exe\"", PathStyle::Unix)
+/// );
+/// assert_eq!(
+///     Some("C:\\Games\\Celeste".to_string()),
+///     translator.translate("/mnt/games/Celeste", PathStyle::Windows)
+/// );
+/// assert_eq!(None, translator.translate("/home/me/game", PathStyle::Windows));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathTranslator {
+    mappings: Vec<(String, String)>,
+}
+
+impl PathTranslator {
+    /// A translator without directories, add them with [map](Self::map).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Translate paths in the `windows` directory to the `unix` directory and back.
+    pub fn map(mut self, windows: &str, unix: &str) -> Self {
+        let windows = windows.trim_end_matches(['\\', '/']).replace('/', "\\");
+        let unix = unix.trim_end_matches('/');
+        self.mappings.push((windows, String::from(unix)));
+        self
+    }
+
+    /// Translate a path to the given style.
+    ///
+    /// Returns `None` if the path is in none of the directories, also if it already has the
+    /// style.
+    pub fn translate(&self, path: &str, to: PathStyle) -> Option<String> {
+        let quoted = path.len() > 1 && path.starts_with('"') && path.ends_with('"');
+        let unquoted = if quoted {
+            &path[1..path.len() - 1]
+        } else {
+            path
+        };
+        let (_, to_dir, rest) = self
+            .mappings
+            .iter()
+            .filter_map(|(windows, unix)| match to {
+                PathStyle::Unix => Some((windows.len(), unix, windows_rest(unquoted, windows)?)),
+                PathStyle::Windows => Some((unix.len(), windows, unix_rest(unquoted, unix)?)),
+            })
+            .max_by_key(|(from_len, _, _)| *from_len)?;
+        let (separator, rest) = match to {
+            PathStyle::Unix => ('/', rest.replace('\\', "/")),
+            PathStyle::Windows => ('\\', rest.replace('/', "\\")),
+        };
+        let mut translated = String::with_capacity(to_dir.len() + rest.len() + 3);
+        if quoted {
+            translated.push('"');
+        }
+        translated.push_str(to_dir);
+        if !rest.is_empty() {
+            translated.push(separator);
+            translated.push_str(&rest);
+        }
+        if quoted {
+            translated.push('"');
+        }
+        Some(translated)
+    }
+
+    /// Translate the exe, start dir and icon of a shortcut, returns if any of them changed.
+    ///
+    /// Paths in none of the directories are left as they are. The app id is not changed, so
+    /// the shortcut keeps its artwork, even though steam would calculate another one for the
+    /// new exe.
+    pub fn translate_shortcut(&self, shortcut: &mut ShortcutOwned, to: PathStyle) -> bool {
+        let mut changed = false;
+        for field in [
+            &mut shortcut.exe,
+            &mut shortcut.start_dir,
+            &mut shortcut.icon,
+        ] {
+            if let Some(translated) = self.translate(field, to) {
+                changed |= *field != translated;
+                *field = translated;
+            }
+        }
+        changed
+    }
+}
+
+/// The part of a Windows path after `dir`, without the separator.
+fn windows_rest<'p>(path: &'p str, dir: &str) -> Option<&'p str> {
+    let start = path.get(..dir.len())?;
+    let same = start
+        .chars()
+        .zip(dir.chars())
+        .all(|(a, b)| a.eq_ignore_ascii_case(&b) || (a == '/' && b == '\\'));
+    if !same {
+        return None;
+    }
+    match &path[dir.len()..] {
+        "" => Some(""),
+        rest if rest.starts_with(['\\', '/']) => Some(&rest[1..]),
+        _ => None,
+    }
+}
+
+/// The part of a unix path after `dir`, without the separator.
+fn unix_rest<'p>(path: &'p str, dir: &str) -> Option<&'p str> {
+    match path.strip_prefix(dir)? {
+        "" => Some(""),
+        rest => rest.strip_prefix('/'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+
+    #[test]
+    fn shortcuts_go_back_and_forth() {
+        let translator = PathTranslator::new()
+            .map("D:\\", "/mnt/d")
+            .map("D:\\Steam Games\\", "/home/me/games");
+        let original = Shortcut::new(
+            "0",
+            "Celeste",
+            "\"D:\\Steam Games\\Celeste\\celeste.exe\"",
+            "\"D:\\Steam Games\\Celeste\"",
+            "D:\\icons\\celeste.ico",
+            "",
+            "",
+        )
+        .to_owned();
+        let mut shortcut = original.clone();
+        assert!(translator.translate_shortcut(&mut shortcut, PathStyle::Unix));
+        assert_eq!("\"/home/me/games/Celeste/celeste.exe\"", shortcut.exe);
+        assert_eq!("\"/home/me/games/Celeste\"", shortcut.start_dir);
+        assert_eq!("/mnt/d/icons/celeste.ico", shortcut.icon);
+        assert_eq!(original.app_id, shortcut.app_id);
+        assert!(!translator.translate_shortcut(&mut shortcut, PathStyle::Unix));
+
+        assert!(translator.translate_shortcut(&mut shortcut, PathStyle::Windows));
+        assert_eq!(original.exe, shortcut.exe);
+        assert_eq!(original.start_dir, shortcut.start_dir);
+        assert_eq!(original.icon, shortcut.icon);
+        let boundary = translator.translate("d:/Steam Gamesx", PathStyle::Unix);
+        assert_eq!(Some("/mnt/d/Steam Gamesx".to_string()), boundary);
+        assert_eq!(None, translator.translate("/mnt/dd", PathStyle::Windows));
+    }
+}
@@ -0,0 +1,171 @@
+//! Python bindings (requires the `python` feature).
+//!
+//! Build the module with [maturin](https://www.maturin.rs):
+//! `maturin build --release --features python,pyo3/extension-module`.
+//!
+//! ```python
+//! import steam_shortcuts_util as ssu
+//!
+//! with open("shortcuts.vdf", "rb") as f:
+//!     shortcuts = ssu.parse_shortcuts(f.read())
+//! shortcuts.append({"app_name": "Celeste", "exe": '"/games/celeste/Celeste"'})
+//! with open("shortcuts.vdf", "wb") as f:
+//!     f.write(ssu.shortcuts_to_bytes(shortcuts))
+//! ```
+//!
+//! Shortcuts are dicts with the field names of the JSON schema of `shortcuts_json`.
+//! Only `app_name` and `exe` are required when writing, a missing `app_id` is calculated.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList};
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// Parse the content of a shortcuts.vdf file to a list of dicts.
+#[pyfunction]
+fn parse_shortcuts<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyList>> {
+    let shortcuts = crate::parse_shortcuts(data).map_err(PyValueError::new_err)?;
+    let list = PyList::empty(py);
+    for shortcut in &shortcuts {
+        list.append(to_dict(py, shortcut)?)?;
+    }
+    Ok(list)
+}
+
+/// Serialize a list of shortcut dicts to the content of a shortcuts.vdf file.
+#[pyfunction]
+fn shortcuts_to_bytes<'py>(
+    py: Python<'py>,
+    shortcuts: Vec<Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let owned = shortcuts
+        .iter()
+        .enumerate()
+        .map(|(index, dict)| from_dict(index, dict))
+        .collect::<PyResult<Vec<ShortcutOwned>>>()?;
+    let borrowed: Vec<Shortcut> = owned.iter().map(|s| s.borrow()).collect();
+    Ok(PyBytes::new(py, &crate::shortcuts_to_bytes(&borrowed)))
+}
+
+/// Calculate the app id steam uses for a shortcut with this exe and app name.
+#[pyfunction]
+fn calculate_app_id(exe: &str, app_name: &str) -> u32 {
+    crate::app_id_generator::calculate_app_id(exe, app_name)
+}
+
+/// The `steam_shortcuts_util` Python module.
+#[pymodule]
+fn steam_shortcuts_util(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse_shortcuts, module)?)?;
+    module.add_function(wrap_pyfunction!(shortcuts_to_bytes, module)?)?;
+    module.add_function(wrap_pyfunction!(calculate_app_id, module)?)?;
+    Ok(())
+}
+
+fn to_dict<'py>(py: Python<'py>, shortcut: &Shortcut) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("app_id", shortcut.app_id)?;
+    dict.set_item("app_name", shortcut.app_name)?;
+    dict.set_item("exe", shortcut.exe)?;
+    dict.set_item("start_dir", shortcut.start_dir)?;
+    dict.set_item("icon", shortcut.icon)?;
+    dict.set_item("shortcut_path", shortcut.shortcut_path)?;
+    dict.set_item("launch_options", shortcut.launch_options)?;
+    dict.set_item("is_hidden", shortcut.is_hidden)?;
+    dict.set_item("allow_desktop_config", shortcut.allow_desktop_config)?;
+    dict.set_item("allow_overlay", shortcut.allow_overlay)?;
+    dict.set_item("open_vr", shortcut.open_vr)?;
+    dict.set_item("dev_kit", shortcut.dev_kit)?;
+    dict.set_item("dev_kit_game_id", shortcut.dev_kit_game_id)?;
+    dict.set_item("dev_kit_override_app_id", shortcut.dev_kit_overrite_app_id)?;
+    dict.set_item("last_play_time", shortcut.last_play_time)?;
+    dict.set_item("flatpak_app_id", shortcut.flatpak_app_id)?;
+    dict.set_item("tags", shortcut.tags.as_slice())?;
+    Ok(dict)
+}
+
+fn field<'py, T: FromPyObject<'py>>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<Option<T>> {
+    match dict.get_item(key)? {
+        Some(value) if !value.is_none() => value.extract().map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn from_dict(index: usize, dict: &Bound<'_, PyDict>) -> PyResult<ShortcutOwned> {
+    let required = |key: &str| -> PyResult<String> {
+        field(dict, key)?
+            .ok_or_else(|| PyValueError::new_err(format!("Shortcut {} misses '{}'", index, key)))
+    };
+    let text = |key: &str| -> PyResult<String> { Ok(field(dict, key)?.unwrap_or_default()) };
+
+    let order = index.to_string();
+    let app_name = required("app_name")?;
+    let exe = required("exe")?;
+    let start_dir = text("start_dir")?;
+    let icon = text("icon")?;
+    let shortcut_path = text("shortcut_path")?;
+    let launch_options = text("launch_options")?;
+    let dev_kit_game_id = text("dev_kit_game_id")?;
+    let flatpak_app_id = text("flatpak_app_id")?;
+    let tags: Vec<String> = field(dict, "tags")?.unwrap_or_default();
+
+    let mut shortcut = Shortcut::new(
+        &order,
+        &app_name,
+        &exe,
+        &start_dir,
+        &icon,
+        &shortcut_path,
+        &launch_options,
+    );
+    if let Some(app_id) = field(dict, "app_id")? {
+        shortcut.app_id = app_id;
+    }
+    if let Some(is_hidden) = field(dict, "is_hidden")? {
+        shortcut.is_hidden = is_hidden;
+    }
+    if let Some(allow_desktop_config) = field(dict, "allow_desktop_config")? {
+        shortcut.allow_desktop_config = allow_desktop_config;
+    }
+    if let Some(allow_overlay) = field(dict, "allow_overlay")? {
+        shortcut.allow_overlay = allow_overlay;
+    }
+    shortcut.open_vr = field(dict, "open_vr")?.unwrap_or_default();
+    shortcut.dev_kit = field(dict, "dev_kit")?.unwrap_or_default();
+    shortcut.dev_kit_game_id = &dev_kit_game_id;
+    shortcut.dev_kit_overrite_app_id = field(dict, "dev_kit_override_app_id")?.unwrap_or_default();
+    shortcut.last_play_time = field(dict, "last_play_time")?.unwrap_or_default();
+    shortcut.flatpak_app_id = &flatpak_app_id;
+    shortcut.tags = tags.iter().map(|tag| tag.as_str()).collect();
+    Ok(shortcut.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_and_write_from_python() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+            let list = parse_shortcuts(py, &content).unwrap();
+            assert_eq!(42, list.len());
+            let first = list.get_item(0).unwrap();
+            let first = first.downcast::<PyDict>().unwrap();
+            let name: String = field(first, "app_name").unwrap().unwrap();
+            assert_eq!("Celeste", name);
+
+            let dicts: Vec<Bound<PyDict>> = list.extract().unwrap();
+            let bytes = shortcuts_to_bytes(py, dicts).unwrap();
+            let written = crate::parse_shortcuts(bytes.as_bytes()).unwrap();
+            assert_eq!(crate::parse_shortcuts(&content).unwrap(), written);
+
+            let missing_exe = PyDict::new(py);
+            missing_exe.set_item("app_name", "Celeste").unwrap();
+            assert!(shortcuts_to_bytes(py, vec![missing_exe]).is_err());
+        });
+    }
+}
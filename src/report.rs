@@ -0,0 +1,145 @@
+//! Tables of shortcuts for people to read, e.g. to attach to a bug report.
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::shortcut::Shortcut;
+
+/// The headers of the columns of a report.
+const HEADERS: [&str; 5] = ["Name", "Exe", "Tags", "Last played", "App id"];
+
+/// A Markdown table with the name, exe, tags, last time played and app id of every shortcut.
+///
+/// Times are in UTC. `|` in the fields is escaped and line breaks are replaced by spaces, so
+/// every shortcut stays one row.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::report::to_markdown;
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let mut celeste = Shortcut::new("0", "Celeste", "\"celeste.exe\"", "", "", "", "");
+/// celeste.app_id = 3_000_000_000;
+/// celeste.last_play_time = 1_700_000_000;
+/// celeste.tags = vec!["Platformer"].into_iter().collect();
+/// let expected = "\
+/// | Name | Exe | Tags | Last played | App id |
+/// | --- | --- | --- | --- | --- |
+/// | Celeste | \"celeste.exe\" | Platformer | 2023-11-14 22:13 | 3000000000 |
+/// ";
+/// assert_eq!(expected, to_markdown(&[celeste]));
+/// ```
+pub fn to_markdown(shortcuts: &[Shortcut]) -> String {
+    let mut markdown = format!("| {} |\n", HEADERS.join(" | "));
+    markdown.push_str("| --- | --- | --- | --- | --- |\n");
+    for shortcut in shortcuts {
+        markdown.push('|');
+        for field in row(shortcut).iter() {
+            markdown.push(' ');
+            for c in field.chars() {
+                match c {
+                    '|' => markdown.push_str("\\|"),
+                    '\r' | '\n' => markdown.push(' '),
+                    c => markdown.push(c),
+                }
+            }
+            markdown.push_str(" |");
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+/// An HTML table with the same columns as [to_markdown].
+///
+/// Only the `<table>` is written, so it can be put in a page or a mail as is.
+pub fn to_html(shortcuts: &[Shortcut]) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for header in HEADERS.iter() {
+        html.push_str(&format!("<th>{}</th>", header));
+    }
+    html.push_str("</tr>\n");
+    for shortcut in shortcuts {
+        html.push_str("<tr>");
+        for field in row(shortcut).iter() {
+            html.push_str("<td>");
+            for c in field.chars() {
+                match c {
+                    '&' => html.push_str("&amp;"),
+                    '<' => html.push_str("&lt;"),
+                    '>' => html.push_str("&gt;"),
+                    '"' => html.push_str("&quot;"),
+                    c => html.push(c),
+                }
+            }
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn row(shortcut: &Shortcut) -> [String; 5] {
+    let last_played = match shortcut.last_played() {
+        Some(time) => utc_time(time),
+        None => String::from("Never"),
+    };
+    [
+        String::from(shortcut.app_name),
+        String::from(shortcut.exe),
+        shortcut.tags.join(", "),
+        last_played,
+        format!("{}", shortcut.app_id),
+    ]
+}
+
+/// Seconds since the unix epoch as `YYYY-MM-DD HH:MM` in UTC.
+fn utc_time(time: i64) -> String {
+    let days = time.div_euclid(86_400);
+    let seconds = time.rem_euclid(86_400);
+    // Days to a date in the proleptic Gregorian calendar, from Howard Hinnant's date algorithms
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn times_are_utc_dates() {
+        assert_eq!("1970-01-01 00:00", utc_time(0));
+        assert_eq!("2000-02-29 23:59", utc_time(951_868_799));
+        assert_eq!("2106-02-07 06:28", utc_time(u32::MAX as i64));
+    }
+
+    #[test]
+    fn fields_are_escaped() {
+        let shortcut = Shortcut::new("0", "A|B\n<C>", "", "", "", "", "");
+        let shortcuts = [shortcut];
+        assert!(to_markdown(&shortcuts).contains("| A\\|B <C> |"));
+        assert!(to_html(&shortcuts).contains("<td>A|B\n&lt;C&gt;</td>"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Reading and changing the app categories in `sharedconfig.vdf`.
+//!
+//! Older steam clients keep the categories (tags) of apps in
+//! `userdata/<account id>/7/remote/sharedconfig.vdf`, under
+//! `UserRoamingConfigStore/Software/Valve/Steam/Apps/<game id>/tags`.
+//!
+//! The game id of a steam app is its app id, that of a shortcut is
+//! [calculate_game_id](crate::calculate_game_id) of its app id.
+
+use std::path::{Path, PathBuf};
+
+use crate::text_vdf::{self, Section};
+
+const APPS_PATH: [&str; 5] = [
+    "UserRoamingConfigStore",
+    "Software",
+    "Valve",
+    "Steam",
+    "Apps",
+];
+
+/// The path of sharedconfig.vdf for a steam account.
+pub fn shared_config_path(steam_root: &Path, user_id: &str) -> PathBuf {
+    steam_root
+        .join("userdata")
+        .join(user_id)
+        .join("7")
+        .join("remote")
+        .join("sharedconfig.vdf")
+}
+
+/// A sharedconfig.vdf file, loaded to edit the categories of apps.
+///
+/// Steam may overwrite the file while it runs, so save changes while steam is closed.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::shared_config::SharedConfig;
+/// use steam_shortcuts_util::calculate_game_id;
+/// use std::path::Path;
+///
+/// fn example() -> std::io::Result<()> {
+///     let mut config = SharedConfig::load_for_user(Path::new("/home/deck/.steam/steam"), "12345")?;
+///     config.set_tags(calculate_game_id(2365067149), &["Platformers", "favorite"]);
+///     config.save()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedConfig {
+    path: PathBuf,
+    root: Section,
+}
+
+impl SharedConfig {
+    /// Load the sharedconfig.vdf at the given path.
+    ///
+    /// A missing file is treated as an empty config, which is created when saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let root = text_vdf::read_file(&path)?;
+        Ok(Self { path, root })
+    }
+
+    /// Load the sharedconfig.vdf of a steam account.
+    pub fn load_for_user(steam_root: &Path, user_id: &str) -> std::io::Result<Self> {
+        Self::load(shared_config_path(steam_root, user_id))
+    }
+
+    /// The path this config was loaded from and will be saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The categories of the app with the given game id, in the order steam lists them.
+    pub fn tags(&self, game_id: u64) -> Vec<String> {
+        self.root
+            .get_path(&APPS_PATH)
+            .and_then(|apps| apps.get_section(&game_id.to_string()))
+            .map(tags_of)
+            .unwrap_or_default()
+    }
+
+    /// The game ids and categories of all apps with categories, in the order they are in the
+    /// file.
+    pub fn all_tags(&self) -> Vec<(u64, Vec<String>)> {
+        let apps = match self.root.get_path(&APPS_PATH) {
+            Some(apps) => apps,
+            None => return vec![],
+        };
+        apps.entries
+            .iter()
+            .filter_map(|(key, value)| {
+                let tags = tags_of(value.as_section()?);
+                if tags.is_empty() {
+                    return None;
+                }
+                Some((key.parse().ok()?, tags))
+            })
+            .collect()
+    }
+
+    /// Set the categories of the app with the given game id, replacing the ones it had.
+    ///
+    /// The other settings of the app are kept. An empty list removes the categories.
+    pub fn set_tags(&mut self, game_id: u64, tags: &[&str]) {
+        let app = self
+            .root
+            .path_or_insert(&APPS_PATH)
+            .section_or_insert(&game_id.to_string());
+        if tags.is_empty() {
+            app.remove("tags");
+            return;
+        }
+        let mut section = Section::new();
+        for (index, tag) in tags.iter().enumerate() {
+            section.set_text(&index.to_string(), tag);
+        }
+        app.set_section("tags", section);
+    }
+
+    /// Serialize the config to the text that [save](Self::save) would write.
+    pub fn to_text(&self) -> String {
+        self.root.to_text()
+    }
+
+    /// Write the config back to its path.
+    pub fn save(&self) -> std::io::Result<()> {
+        text_vdf::write_file(&self.path, &self.root)
+    }
+}
+
+fn tags_of(app: &Section) -> Vec<String> {
+    app.get_section("tags")
+        .map(|tags| {
+            tags.entries
+                .iter()
+                .filter_map(|(_, tag)| tag.as_text())
+                .map(|tag| tag.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::calculate_game_id;
+    use crate::test_util::temp_dir;
+
+    const SHARED_CONFIG: &str = r#""UserRoamingConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"apps"
+				{
+					"400"
+					{
+						"tags"
+						{
+							"0"		"Puzzle"
+							"1"		"favorite"
+						}
+					}
+					"2365067149"
+					{
+						"Hidden"		"1"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+
+    #[test]
+    fn read_tags() {
+        let config = SharedConfig {
+            path: PathBuf::new(),
+            root: text_vdf::parse(SHARED_CONFIG).unwrap(),
+        };
+        assert_eq!(vec!["Puzzle", "favorite"], config.tags(400));
+        assert!(config.tags(2365067149).is_empty());
+        assert_eq!(1, config.all_tags().len());
+    }
+
+    #[test]
+    fn shortcuts_by_game_id() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(content.as_slice()).unwrap();
+        let game_id = calculate_game_id(shortcuts[0].app_id);
+        let text = SHARED_CONFIG.replace("\"400\"", &format!("\"{}\"", game_id));
+        let mut config = SharedConfig {
+            path: PathBuf::new(),
+            root: text_vdf::parse(&text).unwrap(),
+        };
+        assert_eq!(vec!["Puzzle", "favorite"], config.tags(game_id));
+        assert_eq!(
+            vec![(game_id, vec!["Puzzle".to_string(), "favorite".to_string()])],
+            config.all_tags()
+        );
+
+        config.set_tags(game_id, &["Platformers"]);
+        let text = config.to_text();
+        assert!(text.contains(&format!("\"{}\"", game_id)));
+        assert_eq!(vec!["Platformers"], config.tags(game_id));
+    }
+
+    #[test]
+    fn edit_and_save() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = shared_config_path(dir, "12345");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, SHARED_CONFIG).unwrap();
+
+        let mut config = SharedConfig::load_for_user(dir, "12345").unwrap();
+        config.set_tags(2365067149, &["Platformers"]);
+        config.set_tags(400, &[]);
+        config.save().unwrap();
+
+        let reloaded = SharedConfig::load(&path);
+        let reloaded = reloaded.unwrap();
+        assert_eq!(vec!["Platformers"], reloaded.tags(2365067149));
+        assert!(reloaded.tags(400).is_empty());
+        let app = reloaded
+            .root
+            .get_path(&APPS_PATH)
+            .unwrap()
+            .get_section("2365067149")
+            .unwrap();
+        assert_eq!(Some("1"), app.get_text("Hidden"));
+    }
+}
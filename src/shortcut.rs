@@ -1,91 +1,252 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+use alloc::borrow::{Cow, ToOwned};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
 
 /// Struct with data for a steam shortcut.vdf file.
+///
+/// With the `serde` feature it can be read and written with [binary_vdf](crate::binary_vdf),
+/// as an entry of the `shortcuts` list. The order is the key of the entry, so it is not a field.
+/// With the `schemars` feature it also has a JSON Schema of that serde representation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Shortcut<'a> {
     /// Order/Index  of the shortcut
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub order: &'a str,
     /// The id for this shortcut.
     ///
     /// This id can be generated for a shortcut (in a way that steam will accept) with
     /// [calculate_app_id](crate::calculate_app_id)
+    #[cfg_attr(feature = "serde", serde(rename = "appid", alias = "app_id", default))]
     pub app_id: u32,
     /// The app name for this shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AppName", alias = "appname", default)
+    )]
     pub app_name: &'a str,
     /// the target location
+    #[cfg_attr(feature = "serde", serde(rename = "Exe", alias = "exe", default))]
     pub exe: &'a str,
     /// The directory to launch the shortcut in (also known as working directory).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "StartDir", alias = "startdir", default)
+    )]
     pub start_dir: &'a str,
     /// Path to the icon of the shortcut
+    #[cfg_attr(feature = "serde", serde(rename = "icon", default))]
     pub icon: &'a str,
     /// The path to the shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "ShortcutPath", alias = "shortcutpath", default)
+    )]
     pub shortcut_path: &'a str,
     /// Options to pass to the exe in the target location
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "LaunchOptions", alias = "launchoptions", default)
+    )]
     pub launch_options: &'a str,
     /// Is this shortcut hidden?
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "IsHidden", alias = "ishidden", default)
+    )]
     pub is_hidden: bool,
     /// Is dekstop configuration allowed
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AllowDesktopConfig", alias = "allowdesktopconfig", default)
+    )]
     pub allow_desktop_config: bool,
     /// Are steam overlays allowed
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AllowOverlay", alias = "allowoverlay", default)
+    )]
     pub allow_overlay: bool,
-    /// Open vr id
+    /// 1 if the shortcut is in the VR library of steam, 0 if not, see [Shortcut::is_vr]
+    #[cfg_attr(feature = "serde", serde(rename = "openvr", alias = "OpenVR", default))]
     pub open_vr: u32,
     /// Devkit id
+    #[cfg_attr(feature = "serde", serde(rename = "Devkit", alias = "devkit", default))]
     pub dev_kit: u32,
     /// Devkit game id
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "DevkitGameID", alias = "devkitgameid", default)
+    )]
     pub dev_kit_game_id: &'a str,
     /// Devkit overrite_app_id
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "DevkitOverrideAppID", alias = "devkitoverrideappid", default)
+    )]
     pub dev_kit_overrite_app_id: u32,
-    /// The last time played in u32 seconds
+    /// The last time played in u32 seconds, see [Shortcut::last_played]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "LastPlayTime", alias = "lastplaytime", default)
+    )]
     pub last_play_time: u32,
+    /// The id of the Flatpak app this shortcut runs, empty if it is not a Flatpak
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "FlatpakAppID", alias = "flatpakappid", default)
+    )]
+    pub flatpak_app_id: &'a str,
     /// A list of tags for this shortcut
     ///
     /// The tags: "Installed", "Ready TO Play" are recommended
-    pub tags: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(rename = "tags", default, borrow))]
+    pub tags: Tags<'a>,
 }
 
 /// Struct with data for a steam shortcut.vdf file.
 /// This struct owns the data it is referecing.
+///
+/// With the `schemars` feature, `schemars::schema_for!(ShortcutOwned)` gives a JSON Schema
+/// to validate shortcut definitions against before deserializing them.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ShortcutOwned {
     /// Order/Index  of the shortcut
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub order: String,
     /// The id for this shortcut.
     ///
     /// This id can be generated for a shortcut (in a way that steam will accept) with
     /// [calculate_app_id](crate::calculate_app_id)
+    #[cfg_attr(feature = "serde", serde(rename = "appid", alias = "app_id", default))]
     pub app_id: u32,
     /// The app name for this shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AppName", alias = "appname", default)
+    )]
     pub app_name: String,
     /// the target location
+    #[cfg_attr(feature = "serde", serde(rename = "Exe", alias = "exe", default))]
     pub exe: String,
     /// The directory to launch the shortcut in (also known as working directory).
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "StartDir", alias = "startdir", default)
+    )]
     pub start_dir: String,
     /// Path to the icon of the shortcut
+    #[cfg_attr(feature = "serde", serde(rename = "icon", default))]
     pub icon: String,
     /// The path to the shortcut.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "ShortcutPath", alias = "shortcutpath", default)
+    )]
     pub shortcut_path: String,
     /// Options to pass to the exe in the target location
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "LaunchOptions", alias = "launchoptions", default)
+    )]
     pub launch_options: String,
     /// Is this shortcut hidden?
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "IsHidden", alias = "ishidden", default)
+    )]
     pub is_hidden: bool,
     /// Is dekstop configuration allowed
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AllowDesktopConfig", alias = "allowdesktopconfig", default)
+    )]
     pub allow_desktop_config: bool,
     /// Are steam overlays allowed
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "AllowOverlay", alias = "allowoverlay", default)
+    )]
     pub allow_overlay: bool,
-    /// Open vr id
+    /// 1 if the shortcut is in the VR library of steam, 0 if not, see [Shortcut::is_vr]
+    #[cfg_attr(feature = "serde", serde(rename = "openvr", alias = "OpenVR", default))]
     pub open_vr: u32,
     /// Devkit id
+    #[cfg_attr(feature = "serde", serde(rename = "Devkit", alias = "devkit", default))]
     pub dev_kit: u32,
     /// Devkit game id
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "DevkitGameID", alias = "devkitgameid", default)
+    )]
     pub dev_kit_game_id: String,
     /// Devkit overrite_app_id
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "DevkitOverrideAppID", alias = "devkitoverrideappid", default)
+    )]
     pub dev_kit_overrite_app_id: u32,
     /// The last time played in u32 seconds
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "LastPlayTime", alias = "lastplaytime", default)
+    )]
     pub last_play_time: u32,
+    /// The id of the Flatpak app this shortcut runs, empty if it is not a Flatpak
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "FlatpakAppID", alias = "flatpakappid", default)
+    )]
+    pub flatpak_app_id: String,
     /// A list of tags for this shortcut
     ///
     /// The tags: "Installed", "Ready TO Play" are recommended
-    pub tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "tags", default))]
+    pub tags: TagsOwned,
+}
+
+/// The tags of a [Shortcut].
+pub type Tags<'a> = Vec<&'a str>;
+
+/// The tags of a [ShortcutOwned].
+pub type TagsOwned = Vec<String>;
+
+/// The exact spelling of the keys a shortcut used in the file it was parsed from.
+///
+/// Steam clients have spelled the keys differently over time (`AppName`, `appname`, ...),
+/// and writing them back the same way keeps the file byte for byte identical. The parser
+/// gives them next to the shortcuts, see
+/// [shortcuts_to_bytes_with_spelling](crate::shortcuts_writer::shortcuts_to_bytes_with_spelling).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct KeySpelling<'a>(Vec<Cow<'a, str>>);
+
+impl<'a> KeySpelling<'a> {
+    /// Remember the spelling of the given keys.
+    pub fn new(keys: Vec<&'a str>) -> Self {
+        Self(keys.into_iter().map(Cow::Borrowed).collect())
+    }
+
+    /// The spelling of the key, if it was parsed (compared case insensitively).
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|k| k.eq_ignore_ascii_case(key))
+            .map(|k| k.as_ref())
+    }
+
+    /// True if no spelling was recorded, as for shortcuts that were not parsed.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// A copy of the spelling that does not borrow from the parsed bytes.
+    pub fn to_owned(&self) -> KeySpelling<'static> {
+        KeySpelling(self.0.iter().map(|k| Cow::Owned(k.to_string())).collect())
+    }
 }
 
 impl ShortcutOwned {
@@ -107,9 +268,91 @@ impl ShortcutOwned {
             dev_kit_game_id: &self.dev_kit_game_id,
             dev_kit_overrite_app_id: self.dev_kit_overrite_app_id,
             last_play_time: self.last_play_time,
+            flatpak_app_id: &self.flatpak_app_id,
             tags: self.tags.iter().map(|x| x.as_str()).collect(),
         }
     }
+
+    /// Mark the shortcut as played now (requires the `std` feature).
+    ///
+    /// Launchers that start the exe themselves can call this, so the "last played" column of
+    /// steam is right. Does nothing if the clock is before 1970 or after 2106.
+    #[cfg(feature = "std")]
+    pub fn touch_last_played(&mut self) {
+        if let Some(now) = now() {
+            let _ = self.touch_last_played_at(now);
+        }
+    }
+
+    /// Mark the shortcut as played at `time`, in seconds since the unix epoch.
+    ///
+    /// Fails, without changing the shortcut, if steam can not store the time, see
+    /// [Shortcut::set_last_played].
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let mut shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "").to_owned();
+    /// shortcut.touch_last_played_at(1_700_000_000).unwrap();
+    /// assert_eq!(1_700_000_000, shortcut.last_play_time);
+    /// ```
+    pub fn touch_last_played_at(&mut self, time: i64) -> Result<(), String> {
+        self.last_play_time = play_time(time)?;
+        Ok(())
+    }
+}
+
+/// A shortcut read by `steamlocate`, which only reads the app id, name, exe and start dir
+/// (requires the `steamlocate` feature).
+///
+/// The order is left empty and the other fields have the defaults of [Shortcut::new].
+#[cfg(feature = "steamlocate")]
+impl From<&steamlocate::Shortcut> for ShortcutOwned {
+    fn from(shortcut: &steamlocate::Shortcut) -> Self {
+        let mut owned = Shortcut::new(
+            "",
+            &shortcut.app_name,
+            &shortcut.executable,
+            &shortcut.start_dir,
+            "",
+            "",
+            "",
+        )
+        .to_owned();
+        owned.app_id = shortcut.app_id;
+        owned
+    }
+}
+
+/// A shortcut for `steamlocate` (requires the `steamlocate` feature).
+#[cfg(feature = "steamlocate")]
+impl From<&ShortcutOwned> for steamlocate::Shortcut {
+    fn from(shortcut: &ShortcutOwned) -> Self {
+        steamlocate::Shortcut::new(
+            shortcut.app_id,
+            shortcut.app_name.clone(),
+            shortcut.exe.clone(),
+            shortcut.start_dir.clone(),
+        )
+    }
+}
+
+/// The seconds since the unix epoch, if the clock is after it.
+#[cfg(feature = "std")]
+pub(crate) fn now() -> Option<i64> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .and_then(|d| i64::try_from(d.as_secs()).ok())
+}
+
+/// A time as steam stores it, 0 means not played so it is not a time.
+fn play_time(time: i64) -> Result<u32, String> {
+    match u32::try_from(time) {
+        Ok(time) if time != 0 => Ok(time),
+        _ => Err(alloc::format!("{} is not a time steam can store", time)),
+    }
 }
 
 impl<'a> Shortcut<'a> {
@@ -142,7 +385,8 @@ impl<'a> Shortcut<'a> {
         let dev_kit_game_id = "";
         let last_play_time = 0;
         let dev_kit_overrite_app_id = 0;
-        let tags = vec![];
+        let flatpak_app_id = "";
+        let tags = Tags::new();
         Self {
             order,
             app_id,
@@ -160,10 +404,222 @@ impl<'a> Shortcut<'a> {
             dev_kit_game_id,
             last_play_time,
             dev_kit_overrite_app_id,
+            flatpak_app_id,
             tags,
         }
     }
 
+    /// Check if the stored app id is the one steam would calculate for this shortcut.
+    ///
+    /// The app id is a hash of the exe and app_name, so if either was changed after the
+    /// app id was calculated, steam will no longer find the custom images for the shortcut.
+    pub fn app_id_matches_calculation(&self) -> bool {
+        self.app_id == crate::app_id_generator::calculate_app_id_for_shortcut(self)
+    }
+
+    /// If the shortcut is in the VR library of steam.
+    ///
+    /// This is the "Include in VR Library" checkbox in the properties of the shortcut. SteamVR
+    /// lists these shortcuts and starts them from VR, the app itself still has to use OpenVR.
+    /// Steam writes 1 for a checked box, any other number than 0 is also read as checked.
+    pub fn is_vr(&self) -> bool {
+        self.open_vr != 0
+    }
+
+    /// Put the shortcut in the VR library of steam.
+    ///
+    /// Sets `open_vr` to 1, as steam does, see [is_vr](Self::is_vr).
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let mut shortcut = Shortcut::new("0", "Beat Saber", "beatsaber.exe", "", "", "", "");
+    /// assert!(!shortcut.is_vr());
+    /// shortcut.mark_as_vr();
+    /// assert_eq!(1, shortcut.open_vr);
+    /// shortcut.remove_from_vr();
+    /// assert!(!shortcut.is_vr());
+    /// ```
+    pub fn mark_as_vr(&mut self) {
+        self.open_vr = 1;
+    }
+
+    /// Take the shortcut out of the VR library of steam.
+    pub fn remove_from_vr(&mut self) {
+        self.open_vr = 0;
+    }
+
+    /// The last time the shortcut was played, in seconds since the unix epoch.
+    ///
+    /// `None` if it has not been played. Steam stores the time as an unsigned 32 bit number,
+    /// which lasts until 2106, this gives it as the `i64` that most time APIs use.
+    pub fn last_played(&self) -> Option<i64> {
+        match self.last_play_time {
+            0 => None,
+            time => Some(time as i64),
+        }
+    }
+
+    /// Set the last time the shortcut was played, in seconds since the unix epoch.
+    ///
+    /// `None` marks it as not played. Fails, without changing the shortcut, if the time does not
+    /// fit in the 32 bits steam stores (before 1970 or after 2106).
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let mut shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+    /// assert_eq!(None, shortcut.last_played());
+    /// shortcut.set_last_played(Some(1_700_000_000)).unwrap();
+    /// assert_eq!(Some(1_700_000_000), shortcut.last_played());
+    /// assert!(shortcut.set_last_played(Some(-1)).is_err());
+    /// ```
+    pub fn set_last_played(&mut self, time: Option<i64>) -> Result<(), String> {
+        self.last_play_time = match time {
+            None => 0,
+            Some(time) => play_time(time)?,
+        };
+        Ok(())
+    }
+
+    /// A copy of the tags that keeps up to 4 of them without a heap allocation.
+    ///
+    /// Most shortcuts have only a few tags, this is for code that collects the tags of many
+    /// shortcuts, e.g. to sort or group them.
+    #[cfg(feature = "smallvec")]
+    pub fn small_tags(&self) -> smallvec::SmallVec<[&'a str; 4]> {
+        self.tags.iter().copied().collect()
+    }
+
+    /// Compare two shortcuts, ignoring differences steam does not care about.
+    ///
+    /// The order index, the spelling of the keys, the order of the tags and quotes around
+    /// the exe, start dir, icon and shortcut path are not compared.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let mut a = Shortcut::new("0", "Celeste", "\"C:\\Games\\Celeste.exe\"", "", "", "", "");
+    /// a.tags = vec!["Installed", "favorite"];
+    /// let mut b = a.clone();
+    /// b.order = "4";
+    /// b.exe = "C:\\Games\\Celeste.exe";
+    /// b.tags = vec!["favorite", "Installed"];
+    /// assert!(a.semantically_eq(&b));
+    /// ```
+    pub fn semantically_eq(&self, other: &Shortcut) -> bool {
+        let mut tags = self.tags.clone();
+        let mut other_tags = other.tags.clone();
+        tags.sort_unstable();
+        other_tags.sort_unstable();
+        self.app_id == other.app_id
+            && self.app_name == other.app_name
+            && unquoted(self.exe) == unquoted(other.exe)
+            && unquoted(self.start_dir) == unquoted(other.start_dir)
+            && unquoted(self.icon) == unquoted(other.icon)
+            && unquoted(self.shortcut_path) == unquoted(other.shortcut_path)
+            && self.launch_options == other.launch_options
+            && self.is_hidden == other.is_hidden
+            && self.allow_desktop_config == other.allow_desktop_config
+            && self.allow_overlay == other.allow_overlay
+            && self.open_vr == other.open_vr
+            && self.dev_kit == other.dev_kit
+            && self.dev_kit_game_id == other.dev_kit_game_id
+            && self.dev_kit_overrite_app_id == other.dev_kit_overrite_app_id
+            && self.last_play_time == other.last_play_time
+            && self.flatpak_app_id == other.flatpak_app_id
+            && tags == other_tags
+    }
+
+    /// The number of bytes of this shortcut in the file [shortcuts_to_bytes](crate::shortcuts_to_bytes)
+    /// writes, when its order is also its index in the list (as in files written by steam).
+    ///
+    /// Use [shortcuts_len](crate::shortcuts_len) for the length of a whole file.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::{shortcuts_len, Shortcut};
+    ///
+    /// let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+    /// let len = shortcut.serialized_len();
+    /// let mut longer = shortcut.clone();
+    /// longer.launch_options = "-w";
+    /// assert_eq!(len + 2, longer.serialized_len());
+    /// ```
+    pub fn serialized_len(&self) -> usize {
+        crate::shortcuts_writer::shortcut_len(self)
+    }
+
+    /// A 64 bit hash of the content of this shortcut, to quickly see if a stored shortcut changed.
+    ///
+    /// The hash is computed over the same normalized fields that
+    /// [semantically_eq](Self::semantically_eq) compares, so cosmetic differences do not change it.
+    /// It is stable across runs, platforms and versions of this crate (FNV-1a).
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+    /// let mut moved = shortcut.clone();
+    /// moved.order = "3";
+    /// assert_eq!(shortcut.content_hash(), moved.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut tags = self.tags.clone();
+        tags.sort_unstable();
+        let mut hasher = Fnv1a::new();
+        hasher.write(&self.app_id.to_le_bytes());
+        hasher.write_str(self.app_name);
+        hasher.write_str(unquoted(self.exe));
+        hasher.write_str(unquoted(self.start_dir));
+        hasher.write_str(unquoted(self.icon));
+        hasher.write_str(unquoted(self.shortcut_path));
+        hasher.write_str(self.launch_options);
+        hasher.write(&[
+            self.is_hidden as u8,
+            self.allow_desktop_config as u8,
+            self.allow_overlay as u8,
+        ]);
+        hasher.write(&self.open_vr.to_le_bytes());
+        hasher.write(&self.dev_kit.to_le_bytes());
+        hasher.write_str(self.dev_kit_game_id);
+        hasher.write(&self.dev_kit_overrite_app_id.to_le_bytes());
+        hasher.write(&self.last_play_time.to_le_bytes());
+        hasher.write_str(self.flatpak_app_id);
+        hasher.write(&(tags.len() as u64).to_le_bytes());
+        for tag in tags {
+            hasher.write_str(tag);
+        }
+        hasher.0
+    }
+
+    /// Create a shortcut from a freedesktop `.desktop` launcher file.
+    ///
+    /// Returns `None` if the file is not an application launcher, or is marked as hidden.
+    /// See [importers::desktop](crate::importers::desktop) for how the fields are mapped.
+    #[cfg(feature = "std")]
+    pub fn from_desktop_entry<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> std::io::Result<Option<ShortcutOwned>> {
+        crate::importers::desktop::from_desktop_entry(path.as_ref())
+    }
+
+    /// Create a shortcut from a `.url` internet shortcut file, named after the file.
+    ///
+    /// The shortcut opens the url with the default program of the OS.
+    /// Use [importers::url](crate::importers::url) to open it with a specific browser.
+    #[cfg(feature = "std")]
+    pub fn from_url_file<P: AsRef<std::path::Path>>(
+        path: P,
+    ) -> std::io::Result<Option<ShortcutOwned>> {
+        use crate::importers::url::{from_url_file, UrlOpener};
+        from_url_file(path.as_ref(), &UrlOpener::Default)
+    }
+
     pub fn to_owned(&self) -> ShortcutOwned {
         let owned_tags = self.tags.iter().map(|s| s.to_string()).collect();
         ShortcutOwned {
@@ -183,10 +639,79 @@ impl<'a> Shortcut<'a> {
             dev_kit_game_id: self.dev_kit_game_id.to_owned(),
             dev_kit_overrite_app_id: self.dev_kit_overrite_app_id,
             last_play_time: self.last_play_time,
+            flatpak_app_id: self.flatpak_app_id.to_owned(),
             tags: owned_tags,
         }
     }
+
+    /// Copy the texts of this shortcut into an arena, instead of a `String` for each of them
+    /// like [to_owned](Self::to_owned) does (requires the `bumpalo` feature).
+    ///
+    /// ### Examples
+    /// ```
+    /// use bumpalo::Bump;
+    /// use steam_shortcuts_util::Shortcut;
+    ///
+    /// let arena = Bump::new();
+    /// let copy = {
+    ///     let name = String::from("Celeste");
+    ///     let shortcut = Shortcut::new("0", &name, "celeste.exe", "", "", "", "");
+    ///     shortcut.to_arena(&arena)
+    /// };
+    /// assert_eq!("Celeste", copy.app_name);
+    /// ```
+    #[cfg(feature = "bumpalo")]
+    pub fn to_arena<'b>(&self, arena: &'b bumpalo::Bump) -> Shortcut<'b> {
+        let text = |text: &str| -> &'b str { arena.alloc_str(text) };
+        Shortcut {
+            order: text(self.order),
+            app_id: self.app_id,
+            app_name: text(self.app_name),
+            exe: text(self.exe),
+            start_dir: text(self.start_dir),
+            icon: text(self.icon),
+            shortcut_path: text(self.shortcut_path),
+            launch_options: text(self.launch_options),
+            is_hidden: self.is_hidden,
+            allow_desktop_config: self.allow_desktop_config,
+            allow_overlay: self.allow_overlay,
+            open_vr: self.open_vr,
+            dev_kit: self.dev_kit,
+            dev_kit_game_id: text(self.dev_kit_game_id),
+            dev_kit_overrite_app_id: self.dev_kit_overrite_app_id,
+            last_play_time: self.last_play_time,
+            flatpak_app_id: text(self.flatpak_app_id),
+            tags: self.tags.iter().map(|tag| text(tag)).collect(),
+        }
+    }
 }
+
+/// The 64 bit FNV-1a hash, used because it gives the same result everywhere.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Write the length before the text, so fields can not run into each other.
+    fn write_str(&mut self, text: &str) {
+        self.write(&(text.len() as u64).to_le_bytes());
+        self.write(text.as_bytes());
+    }
+}
+
+fn unquoted(path: &str) -> &str {
+    path.trim().trim_matches('"')
+}
+
 impl PartialEq for ShortcutOwned {
     fn eq(&self, other: &Self) -> bool {
         self.app_id == other.app_id
@@ -194,3 +719,147 @@ impl PartialEq for ShortcutOwned {
 }
 
 impl Eq for ShortcutOwned {}
+
+/// Generates shortcuts that can be written to a shortcuts.vdf file (requires the `arbitrary` feature).
+///
+/// Strings and tags never contain a null byte, as they are null terminated in the file,
+/// and the app id is calculated from the exe and app name like steam does.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ShortcutOwned {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        fn text(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<String> {
+            let text: String = u.arbitrary()?;
+            Ok(text.replace('\0', ""))
+        }
+
+        let app_name = text(u)?;
+        let exe = text(u)?;
+        let tags = (0..u.arbitrary_len::<String>()?)
+            .map(|_| text(u))
+            .collect::<arbitrary::Result<TagsOwned>>()?;
+        Ok(ShortcutOwned {
+            order: "0".to_string(),
+            app_id: crate::app_id_generator::calculate_app_id(&exe, &app_name),
+            start_dir: text(u)?,
+            icon: text(u)?,
+            shortcut_path: text(u)?,
+            launch_options: text(u)?,
+            is_hidden: u.arbitrary()?,
+            allow_desktop_config: u.arbitrary()?,
+            allow_overlay: u.arbitrary()?,
+            open_vr: u.arbitrary()?,
+            dev_kit: u.arbitrary()?,
+            dev_kit_game_id: text(u)?,
+            dev_kit_overrite_app_id: u.arbitrary()?,
+            last_play_time: u.arbitrary()?,
+            flatpak_app_id: text(u)?,
+            app_name,
+            exe,
+            tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn content_hash_is_stable() {
+        let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        assert_eq!(13061353904695512540, shortcut.content_hash());
+    }
+
+    #[test]
+    fn vr_shortcuts_are_saved() {
+        let mut shortcut = Shortcut::new("0", "Beat Saber", "beatsaber.exe", "", "", "", "");
+        shortcut.mark_as_vr();
+        let bytes = crate::shortcuts_to_bytes(&vec![shortcut]);
+        let loaded = crate::shortcuts_parser::parse_shortcuts(&bytes).unwrap();
+        assert!(loaded[0].is_vr());
+        assert_eq!(1, loaded[0].open_vr);
+    }
+
+    #[test]
+    fn content_hash_changes_with_content() {
+        let shortcut = Shortcut::new("0", "Celeste", "\"celeste.exe\"", "", "", "", "");
+        let mut same = shortcut.clone();
+        same.exe = "celeste.exe";
+        assert_eq!(shortcut.content_hash(), same.content_hash());
+        let mut changed = shortcut.clone();
+        changed.launch_options = "-windowed";
+        assert_ne!(shortcut.content_hash(), changed.content_hash());
+    }
+
+    #[test]
+    fn last_played_limits() {
+        let mut shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        shortcut.set_last_played(Some(u32::MAX as i64)).unwrap();
+        assert_eq!(Some(4294967295), shortcut.last_played());
+        assert!(shortcut.set_last_played(Some(u32::MAX as i64 + 1)).is_err());
+        assert!(shortcut.set_last_played(Some(0)).is_err());
+        assert_eq!(u32::MAX, shortcut.last_play_time);
+        shortcut.set_last_played(None).unwrap();
+        assert_eq!(0, shortcut.last_play_time);
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn to_arena_copies_every_field() {
+        let arena = bumpalo::Bump::new();
+        let content = std::fs::read("src/testdata/shortcutsfirefox.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(&content).unwrap();
+        let copies: Vec<Shortcut> = shortcuts.iter().map(|s| s.to_arena(&arena)).collect();
+        assert_eq!(
+            crate::shortcuts_to_bytes(&shortcuts),
+            crate::shortcuts_to_bytes(&copies)
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schema_uses_serde_names() {
+        let schema = schemars::schema_for!(ShortcutOwned);
+        let properties = schema.get("properties").unwrap().as_object().unwrap();
+        assert!(properties.contains_key("AppName"));
+        assert!(properties.contains_key("tags"));
+        assert!(!properties.contains_key("order"));
+        assert!(schema.get("required").is_none());
+    }
+
+    #[cfg(feature = "steamlocate")]
+    #[test]
+    fn steamlocate_shortcuts_convert() {
+        let exe = "\"C:\\Games\\Celeste.exe\"";
+        let shortcut = Shortcut::new("0", "Celeste", exe, "C:\\Games", "", "", "").to_owned();
+        let located = steamlocate::Shortcut::from(&shortcut);
+        assert_eq!(
+            crate::calculate_game_id(shortcut.app_id),
+            located.steam_id()
+        );
+        let back = ShortcutOwned::from(&located);
+        assert_eq!(shortcut.app_id, back.app_id);
+        assert_eq!(shortcut.app_name, back.app_name);
+        assert_eq!(shortcut.exe, back.exe);
+        assert_eq!(shortcut.start_dir, back.start_dir);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_shortcuts_are_valid() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        while let Ok(shortcut) = ShortcutOwned::arbitrary(&mut u) {
+            let borrowed = shortcut.borrow();
+            assert!(borrowed.app_id_matches_calculation());
+            assert!(!borrowed.app_name.contains('\0'));
+            assert!(borrowed.tags.iter().all(|tag| !tag.contains('\0')));
+            if u.is_empty() {
+                break;
+            }
+        }
+    }
+}
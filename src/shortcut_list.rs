@@ -0,0 +1,73 @@
+//! The versioned list of shortcuts that [shortcuts_json](crate::shortcuts_json) and
+//! [shortcuts_toml](crate::shortcuts_toml) write and read.
+//!
+//! The shortcuts in the list are the serde representation of [Shortcut] and [ShortcutOwned],
+//! so both formats have the key names of shortcuts.vdf.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_id_generator::calculate_app_id;
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The version of the schema that is written, and the newest one that is read.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A list of shortcuts, as it is written.
+#[derive(Serialize)]
+pub(crate) struct ListRef<'s, 'a> {
+    version: u32,
+    shortcuts: &'s [Shortcut<'a>],
+}
+
+impl<'s, 'a> ListRef<'s, 'a> {
+    pub(crate) fn new(shortcuts: &'s [Shortcut<'a>]) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            shortcuts,
+        }
+    }
+}
+
+/// The version of a list, read before the shortcuts so a newer list gives a clear error.
+#[derive(Deserialize)]
+pub(crate) struct Version {
+    version: u32,
+}
+
+impl Version {
+    pub(crate) fn check(&self) -> Result<(), String> {
+        if self.version > SCHEMA_VERSION {
+            return Err(format!("Unsupported schema version {}", self.version));
+        }
+        Ok(())
+    }
+}
+
+/// A list of shortcuts, as it is read.
+#[derive(Deserialize)]
+pub(crate) struct List {
+    #[serde(default)]
+    shortcuts: Vec<ShortcutOwned>,
+}
+
+impl List {
+    /// The shortcuts, ordered by their position in the list and with the app ids that are
+    /// missing calculated.
+    pub(crate) fn into_shortcuts(self) -> Vec<ShortcutOwned> {
+        self.shortcuts
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut shortcut)| {
+                shortcut.order = index.to_string();
+                if shortcut.app_id == 0 {
+                    shortcut.app_id = calculate_app_id(&shortcut.exe, &shortcut.app_name);
+                }
+                shortcut
+            })
+            .collect()
+    }
+}
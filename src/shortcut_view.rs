@@ -0,0 +1,321 @@
+//! A view of the shortcuts in a shortcuts.vdf file that decodes fields when they are read.
+//!
+//! [parse_shortcut_views] only finds where the keys and values of each shortcut are. The UTF-8
+//! check of a text and the conversion of a number happen when a field is read, so tools that
+//! look at one or two fields of every shortcut skip most of the work of
+//! [parse_shortcuts](crate::parse_shortcuts).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::str::Utf8Error;
+
+use crate::bytes::{tag, take, take_through, PResult, BS, NULL, SOH, STX};
+use crate::shortcut::{Shortcut, Tags};
+use crate::shortcuts_parser::shotcut_content;
+
+/// A shortcut in a shortcuts.vdf file, with its fields decoded when they are read.
+///
+/// The getters give the same values as the fields of the [Shortcut] that
+/// [parse_shortcuts](crate::parse_shortcuts) returns, missing keys give an empty text or zero.
+/// Texts that are not valid UTF-8 give an error, without affecting the other fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutView<'a> {
+    order: &'a [u8],
+    lines: Vec<Line<'a>>,
+    tags: &'a [u8],
+}
+
+/// A key of a shortcut and the bytes of its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Line<'a> {
+    key: &'a [u8],
+    value: RawValue<'a>,
+    /// Keys after the tags only count if they have a value
+    after_tags: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RawValue<'a> {
+    Text(&'a [u8]),
+    Number(&'a [u8]),
+}
+
+/// Find the shortcuts in the bytes of a shortcuts.vdf file, without decoding their fields.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::parse_shortcut_views;
+///
+/// let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+/// let views = parse_shortcut_views(&content).unwrap();
+/// assert_eq!(Ok("Celeste"), views[0].app_name());
+/// assert_eq!(2365067149, views[0].app_id());
+/// ```
+pub fn parse_shortcut_views(bytes: &[u8]) -> Result<Vec<ShortcutView<'_>>, String> {
+    let (mut i, _) = shotcut_content(bytes).map_err(|err| format!("{}", err))?;
+    let mut views = vec![];
+    while let Ok((rest, view)) = scan_shortcut(i) {
+        views.push(view);
+        i = rest;
+    }
+    Ok(views)
+}
+
+impl<'a> ShortcutView<'a> {
+    /// The key of the shortcut in the list, see [Shortcut::order].
+    pub fn order(&self) -> Result<&'a str, Utf8Error> {
+        core::str::from_utf8(self.order)
+    }
+
+    /// See [Shortcut::app_id].
+    pub fn app_id(&self) -> u32 {
+        match self.number("app_id") {
+            0 => self.number("appid"),
+            app_id => app_id,
+        }
+    }
+
+    /// See [Shortcut::app_name].
+    pub fn app_name(&self) -> Result<&'a str, Utf8Error> {
+        self.text("AppName")
+    }
+
+    /// See [Shortcut::exe].
+    pub fn exe(&self) -> Result<&'a str, Utf8Error> {
+        self.text("Exe")
+    }
+
+    /// See [Shortcut::start_dir].
+    pub fn start_dir(&self) -> Result<&'a str, Utf8Error> {
+        self.text("StartDir")
+    }
+
+    /// See [Shortcut::icon].
+    pub fn icon(&self) -> Result<&'a str, Utf8Error> {
+        self.text("icon")
+    }
+
+    /// See [Shortcut::shortcut_path].
+    pub fn shortcut_path(&self) -> Result<&'a str, Utf8Error> {
+        self.text("ShortcutPath")
+    }
+
+    /// See [Shortcut::launch_options].
+    pub fn launch_options(&self) -> Result<&'a str, Utf8Error> {
+        self.text("LaunchOptions")
+    }
+
+    /// See [Shortcut::is_hidden].
+    pub fn is_hidden(&self) -> bool {
+        self.number("IsHidden") != 0
+    }
+
+    /// See [Shortcut::allow_desktop_config].
+    pub fn allow_desktop_config(&self) -> bool {
+        self.number("AllowDesktopConfig") != 0
+    }
+
+    /// See [Shortcut::allow_overlay].
+    pub fn allow_overlay(&self) -> bool {
+        self.number("AllowOverlay") != 0
+    }
+
+    /// See [Shortcut::open_vr].
+    pub fn open_vr(&self) -> u32 {
+        self.number("openvr")
+    }
+
+    /// See [Shortcut::dev_kit].
+    pub fn dev_kit(&self) -> u32 {
+        self.number("Devkit")
+    }
+
+    /// See [Shortcut::dev_kit_game_id].
+    pub fn dev_kit_game_id(&self) -> Result<&'a str, Utf8Error> {
+        self.text("DevkitGameID")
+    }
+
+    /// See [Shortcut::dev_kit_overrite_app_id].
+    pub fn dev_kit_overrite_app_id(&self) -> u32 {
+        self.number("DevkitOverrideAppID")
+    }
+
+    /// See [Shortcut::last_play_time].
+    pub fn last_play_time(&self) -> u32 {
+        self.number("LastPlayTime")
+    }
+
+    /// See [Shortcut::last_played].
+    pub fn last_played(&self) -> Option<i64> {
+        match self.last_play_time() {
+            0 => None,
+            time => Some(time as i64),
+        }
+    }
+
+    /// See [Shortcut::flatpak_app_id].
+    pub fn flatpak_app_id(&self) -> Result<&'a str, Utf8Error> {
+        self.text("FlatpakAppID")
+    }
+
+    /// The tags of the shortcut, each decoded when the iterator reaches it.
+    pub fn tags(&self) -> impl Iterator<Item = Result<&'a str, Utf8Error>> + 'a {
+        let mut i = self.tags;
+        core::iter::from_fn(move || {
+            let (rest, tag_name) = scan_tag(i).ok()?;
+            i = rest;
+            Some(core::str::from_utf8(tag_name))
+        })
+    }
+
+    /// Decode all fields, which gives the same shortcut as [parse_shortcuts](crate::parse_shortcuts).
+    pub fn to_shortcut(&self) -> Result<Shortcut<'a>, Utf8Error> {
+        Ok(Shortcut {
+            order: self.order()?,
+            app_id: self.app_id(),
+            app_name: self.app_name()?,
+            exe: self.exe()?,
+            start_dir: self.start_dir()?,
+            icon: self.icon()?,
+            shortcut_path: self.shortcut_path()?,
+            launch_options: self.launch_options()?,
+            is_hidden: self.is_hidden(),
+            allow_desktop_config: self.allow_desktop_config(),
+            allow_overlay: self.allow_overlay(),
+            open_vr: self.open_vr(),
+            dev_kit: self.dev_kit(),
+            dev_kit_game_id: self.dev_kit_game_id()?,
+            dev_kit_overrite_app_id: self.dev_kit_overrite_app_id(),
+            last_play_time: self.last_play_time(),
+            flatpak_app_id: self.flatpak_app_id()?,
+            tags: self.tags().collect::<Result<Tags, _>>()?,
+        })
+    }
+
+    /// The lines with this key, matched like the parser does.
+    fn lines_with<'s>(&'s self, key: &'s str) -> impl Iterator<Item = &'s Line<'a>> {
+        self.lines
+            .iter()
+            .filter(move |line| line.key.eq_ignore_ascii_case(key.as_bytes()))
+    }
+
+    fn text(&self, key: &str) -> Result<&'a str, Utf8Error> {
+        let mut value: &'a [u8] = b"";
+        for line in self.lines_with(key) {
+            let text = match line.value {
+                RawValue::Text(text) => text,
+                RawValue::Number(_) => b"",
+            };
+            if !line.after_tags || !text.is_empty() {
+                value = text;
+            }
+        }
+        core::str::from_utf8(value)
+    }
+
+    fn number(&self, key: &str) -> u32 {
+        let mut value = 0;
+        for line in self.lines_with(key) {
+            let number = match line.value {
+                RawValue::Number(bytes) => decode_u32(bytes),
+                RawValue::Text(_) => 0,
+            };
+            if !line.after_tags || number != 0 {
+                value = number;
+            }
+        }
+        value
+    }
+}
+
+/// Decode the 4 little endian bytes of a number.
+pub(crate) fn decode_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn scan_shortcut(i: &[u8]) -> PResult<'_, ShortcutView<'_>> {
+    let (i, _) = tag(&[NULL], i)?;
+    let (i, order) = take_through(NULL, i)?;
+    let mut lines = vec![];
+    let i = scan_lines(i, &mut lines, false);
+    let (i, _) = tag(b"\0tags\0", i)?;
+    let (i, tags) = take_through(BS, i)?;
+    let i = scan_lines(i, &mut lines, true);
+    let (i, _) = tag(&[BS], i)?;
+    Ok((i, ShortcutView { order, lines, tags }))
+}
+
+fn scan_lines<'a>(mut i: &'a [u8], lines: &mut Vec<Line<'a>>, after_tags: bool) -> &'a [u8] {
+    while let Ok((rest, (key, value))) = scan_line(i) {
+        lines.push(Line {
+            key,
+            value,
+            after_tags,
+        });
+        i = rest;
+    }
+    i
+}
+
+pub(crate) fn scan_line(i: &[u8]) -> PResult<'_, (&[u8], RawValue<'_>)> {
+    if let Ok((i, _)) = tag(&[SOH], i) {
+        let (i, key) = take_through(NULL, i)?;
+        let (i, value) = take_through(NULL, i)?;
+        return Ok((i, (key, RawValue::Text(value))));
+    }
+    let (i, _) = tag(&[STX], i)?;
+    let (i, key) = take_through(NULL, i)?;
+    let (i, value) = take(4, i)?;
+    Ok((i, (key, RawValue::Number(value))))
+}
+
+pub(crate) fn scan_tag(i: &[u8]) -> PResult<'_, &[u8]> {
+    let (i, _) = tag(&[SOH], i)?;
+    let (i, _) = take_through(NULL, i)?;
+    take_through(NULL, i)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn views_decode_like_the_parser() {
+        for path in [
+            "src/testdata/shortcuts.vdf",
+            "src/testdata/shortcuts2.vdf",
+            "src/testdata/shortcuts_broken.vdf",
+            "src/testdata/shortcutsfirefox.vdf",
+            "src/testdata/shortcutsspore.vdf",
+            "src/testdata/linux_shortcut.vdf",
+            "src/testdata/failing.vdf",
+            "src/testdata/steam_rom_manager.vdf",
+        ] {
+            let content = std::fs::read(path).unwrap();
+            let shortcuts = crate::parse_shortcuts(&content).unwrap();
+            let views = parse_shortcut_views(&content).unwrap();
+            let decoded = views
+                .iter()
+                .map(|view| view.to_shortcut().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(shortcuts, decoded, "{}", path);
+        }
+    }
+
+    #[test]
+    fn invalid_text_only_fails_its_field() {
+        let mut data = b"\x00shortcuts\x00\x000\x00".to_vec();
+        data.extend_from_slice(b"\x01AppName\x00Celeste\x00\x01Exe\x00\xff\xfe\x00");
+        data.extend_from_slice(b"\x02LastPlayTime\x00\x07\x00\x00\x00");
+        data.extend_from_slice(b"\x00tags\x00\x01\x30\x00Indie\x00\x08\x08\x08");
+        let views = parse_shortcut_views(&data).unwrap();
+        assert_eq!(Ok("Celeste"), views[0].app_name());
+        assert!(views[0].exe().is_err());
+        assert!(views[0].to_shortcut().is_err());
+        assert_eq!(7, views[0].last_play_time());
+        assert_eq!(vec![Ok("Indie")], views[0].tags().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,604 @@
+//! A list of shortcuts with the lookups and edits most tools need.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::{Index, IndexMut};
+
+use crate::shortcut::ShortcutOwned;
+
+/// What makes a shortcut the same as one in [Shortcuts], for [Shortcuts::contains].
+pub use crate::matching::MatchBy;
+
+/// Iterator over the shortcuts of [Shortcuts].
+pub type Iter<'a> = core::slice::Iter<'a, ShortcutOwned>;
+/// Iterator over the shortcuts of [Shortcuts], for editing.
+pub type IterMut<'a> = core::slice::IterMut<'a, ShortcutOwned>;
+/// Iterator that moves the shortcuts out of [Shortcuts].
+pub type IntoIter = alloc::vec::IntoIter<ShortcutOwned>;
+
+/// How [Shortcuts::sort_by] orders shortcuts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// By app name, ignoring case
+    NameCaseInsensitive,
+    /// The shortcut played last first, then those never played
+    LastPlayed,
+    /// By app id, lowest first
+    AppId,
+    /// The shortcut added last first, by the number of its order (its place in the list if the
+    /// order is not a number)
+    RecentlyAdded,
+}
+
+/// Picks the shortcuts a bulk edit like [Shortcuts::set_hidden] changes.
+///
+/// It is implemented for closures that take a shortcut and return if it should be picked, and
+/// for [Tag].
+pub trait ShortcutFilter {
+    /// If the shortcut is picked.
+    fn matches(&mut self, shortcut: &ShortcutOwned) -> bool;
+}
+
+impl<F: FnMut(&ShortcutOwned) -> bool> ShortcutFilter for F {
+    fn matches(&mut self, shortcut: &ShortcutOwned) -> bool {
+        self(shortcut)
+    }
+}
+
+/// Picks the shortcuts that have this tag, the case must match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tag<'a>(pub &'a str);
+
+impl ShortcutFilter for Tag<'_> {
+    fn matches(&mut self, shortcut: &ShortcutOwned) -> bool {
+        shortcut.tags.iter().any(|tag| tag == self.0)
+    }
+}
+
+/// An order [Shortcuts::renumber] changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderChange {
+    /// The place of the shortcut in the list, which is its new order
+    pub index: usize,
+    /// The order it had before
+    pub old_order: String,
+}
+
+/// The shortcuts of a shortcuts.vdf file, in the order they are written.
+///
+/// Edits that add or remove shortcuts renumber the order of every shortcut from 0, as steam
+/// writes them.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{Shortcut, Shortcuts};
+///
+/// let mut shortcuts = Shortcuts::new();
+/// shortcuts.push_new(Shortcut::new("", "Celeste", "celeste.exe", "", "", "", "").to_owned());
+/// shortcuts.push_new(Shortcut::new("", "Spore", "spore.exe", "", "", "", "").to_owned());
+///
+/// let spore = shortcuts.find_by_name("Spore").unwrap();
+/// assert_eq!("1", spore.order);
+/// let app_id = shortcuts.find_by_name("Celeste").unwrap().app_id;
+/// shortcuts.remove_by_app_id(app_id);
+/// assert_eq!("0", shortcuts.find_by_name("Spore").unwrap().order);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shortcuts {
+    shortcuts: Vec<ShortcutOwned>,
+}
+
+impl Shortcuts {
+    /// An empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of shortcuts.
+    pub fn len(&self) -> usize {
+        self.shortcuts.len()
+    }
+
+    /// Are there no shortcuts?
+    pub fn is_empty(&self) -> bool {
+        self.shortcuts.is_empty()
+    }
+
+    /// The shortcuts, in order.
+    pub fn iter(&self) -> Iter<'_> {
+        self.shortcuts.iter()
+    }
+
+    /// The shortcuts, in order, for editing.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        self.shortcuts.iter_mut()
+    }
+
+    /// The shortcuts as a slice.
+    pub fn as_slice(&self) -> &[ShortcutOwned] {
+        &self.shortcuts
+    }
+
+    /// The list of shortcuts.
+    pub fn into_vec(self) -> Vec<ShortcutOwned> {
+        self.shortcuts
+    }
+
+    /// The first shortcut with this app name.
+    pub fn find_by_name(&self, app_name: &str) -> Option<&ShortcutOwned> {
+        self.shortcuts.iter().find(|s| s.app_name == app_name)
+    }
+
+    /// The first shortcut with this app name, for editing.
+    pub fn find_by_name_mut(&mut self, app_name: &str) -> Option<&mut ShortcutOwned> {
+        self.shortcuts.iter_mut().find(|s| s.app_name == app_name)
+    }
+
+    /// The first shortcut with this app id.
+    pub fn find_by_app_id(&self, app_id: u32) -> Option<&ShortcutOwned> {
+        self.shortcuts.iter().find(|s| s.app_id == app_id)
+    }
+
+    /// The first shortcut with this app id, for editing.
+    pub fn find_by_app_id_mut(&mut self, app_id: u32) -> Option<&mut ShortcutOwned> {
+        self.shortcuts.iter_mut().find(|s| s.app_id == app_id)
+    }
+
+    /// A map from app id to shortcut, for looking up many app ids (requires the `std` feature).
+    ///
+    /// The map is built when it is called, so build it again after editing the shortcuts. If
+    /// shortcuts share an app id the first one is in the map, as with [find_by_app_id](Self::find_by_app_id).
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts = Shortcuts::new();
+    /// let app_id = shortcuts
+    ///     .push_new(Shortcut::new("", "Celeste", "celeste.exe", "", "", "", "").to_owned())
+    ///     .app_id;
+    /// let by_app_id = shortcuts.by_app_id();
+    /// assert_eq!("Celeste", by_app_id[&app_id].app_name);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn by_app_id(&self) -> std::collections::HashMap<u32, &ShortcutOwned> {
+        let mut map = std::collections::HashMap::with_capacity(self.shortcuts.len());
+        for shortcut in self.shortcuts.iter() {
+            map.entry(shortcut.app_id).or_insert(shortcut);
+        }
+        map
+    }
+
+    /// Add a shortcut at the end, with the next order.
+    pub fn push_new(&mut self, mut shortcut: ShortcutOwned) -> &mut ShortcutOwned {
+        shortcut.order = self.shortcuts.len().to_string();
+        self.shortcuts.push(shortcut);
+        let last = self.shortcuts.len() - 1;
+        &mut self.shortcuts[last]
+    }
+
+    /// Is there a shortcut that is the same as `candidate`?
+    ///
+    /// Cheaper than a full [diff](crate::diff) when an importer only needs to skip games that
+    /// are already there.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::shortcuts::MatchBy;
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts = Shortcuts::new();
+    /// shortcuts.push_new(Shortcut::new("", "Celeste™", "celeste.exe", "", "", "", "").to_owned());
+    /// let candidate = Shortcut::new("", "celeste", "other.exe", "", "", "", "").to_owned();
+    /// assert!(shortcuts.contains(&candidate, MatchBy::NormalizedName));
+    /// assert!(!shortcuts.contains(&candidate, MatchBy::ExeAndName));
+    /// assert!(!shortcuts.contains(&candidate, MatchBy::AppId));
+    /// ```
+    pub fn contains(&self, candidate: &ShortcutOwned, by: MatchBy) -> bool {
+        let key = by.key(candidate);
+        self.shortcuts.iter().any(|s| by.key(s) == key)
+    }
+
+    /// Insert a shortcut at `index`, the shortcuts after it move down and get the next order.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is more than the number of shortcuts, as [Vec::insert].
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts: Shortcuts = ["Celeste", "Spore"]
+    ///     .iter()
+    ///     .map(|name| Shortcut::new("", name, "", "", "", "", "").to_owned())
+    ///     .collect();
+    /// let celeste = shortcuts.iter().position(|s| s.app_name == "Celeste").unwrap();
+    /// shortcuts.insert_at(celeste + 1, Shortcut::new("", "Hades", "", "", "", "", "").to_owned());
+    /// assert_eq!("1", shortcuts.find_by_name("Hades").unwrap().order);
+    /// assert_eq!("2", shortcuts.find_by_name("Spore").unwrap().order);
+    /// ```
+    pub fn insert_at(&mut self, index: usize, shortcut: ShortcutOwned) -> &mut ShortcutOwned {
+        self.shortcuts.insert(index, shortcut);
+        self.renumber();
+        &mut self.shortcuts[index]
+    }
+
+    /// Remove the shortcut at `index`, the shortcuts after it move up and get the order before.
+    ///
+    /// Returns `None` if there is no shortcut at `index`.
+    pub fn remove_at(&mut self, index: usize) -> Option<ShortcutOwned> {
+        if index >= self.shortcuts.len() {
+            return None;
+        }
+        let removed = self.shortcuts.remove(index);
+        self.renumber();
+        Some(removed)
+    }
+
+    /// Remove every shortcut with this app id, returns the removed shortcuts.
+    pub fn remove_by_app_id(&mut self, app_id: u32) -> Vec<ShortcutOwned> {
+        let (removed, kept) = core::mem::take(&mut self.shortcuts)
+            .into_iter()
+            .partition(|s| s.app_id == app_id);
+        self.shortcuts = kept;
+        self.renumber();
+        removed
+    }
+
+    /// Keep only the shortcuts for which `keep` returns true.
+    pub fn retain<F: FnMut(&ShortcutOwned) -> bool>(&mut self, keep: F) {
+        self.shortcuts.retain(keep);
+        self.renumber();
+    }
+
+    /// Mark the shortcuts `filter` returns true for as played now (requires the `std` feature).
+    ///
+    /// Returns the number of shortcuts marked, 0 if the clock is before 1970 or after 2106.
+    #[cfg(feature = "std")]
+    pub fn touch_last_played<F: FnMut(&ShortcutOwned) -> bool>(&mut self, filter: F) -> usize {
+        match crate::shortcut::now() {
+            Some(now) => self.touch_last_played_at(filter, now).unwrap_or_default(),
+            None => 0,
+        }
+    }
+
+    /// Mark the shortcuts `filter` returns true for as played at `time`, in seconds since the
+    /// unix epoch. Returns the number of shortcuts marked.
+    ///
+    /// Fails, without changing any shortcut, if steam can not store the time.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts: Shortcuts = ["Celeste", "Hades"]
+    ///     .iter()
+    ///     .map(|name| Shortcut::new("", name, "", "", "", "", "").to_owned())
+    ///     .collect();
+    /// let touched = shortcuts.touch_last_played_at(|s| s.app_name == "Hades", 1_700_000_000);
+    /// assert_eq!(Ok(1), touched);
+    /// assert_eq!(0, shortcuts[0].last_play_time);
+    /// assert_eq!(1_700_000_000, shortcuts[1].last_play_time);
+    /// ```
+    pub fn touch_last_played_at<F: FnMut(&ShortcutOwned) -> bool>(
+        &mut self,
+        mut filter: F,
+        time: i64,
+    ) -> Result<usize, alloc::string::String> {
+        let mut touched = 0;
+        for shortcut in self.shortcuts.iter_mut() {
+            if filter(shortcut) {
+                shortcut.touch_last_played_at(time)?;
+                touched += 1;
+            }
+        }
+        Ok(touched)
+    }
+
+    /// Hide or show every shortcut the filter picks, returns the number of shortcuts changed.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::shortcuts::Tag;
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts: Shortcuts = ["Celeste", "Mario"]
+    ///     .iter()
+    ///     .map(|name| Shortcut::new("", name, "", "", "", "", "").to_owned())
+    ///     .collect();
+    /// shortcuts[1].tags.push("Emulators".to_string());
+    /// assert_eq!(1, shortcuts.set_hidden(Tag("Emulators"), true));
+    /// assert!(shortcuts[1].is_hidden);
+    /// assert_eq!(1, shortcuts.set_hidden(|s: &_| true, false));
+    /// assert!(!shortcuts[1].is_hidden);
+    /// ```
+    pub fn set_hidden<F: ShortcutFilter>(&mut self, mut filter: F, hidden: bool) -> usize {
+        let mut changed = 0;
+        for shortcut in self.shortcuts.iter_mut() {
+            if shortcut.is_hidden != hidden && filter.matches(shortcut) {
+                shortcut.is_hidden = hidden;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Sort the shortcuts and renumber their order.
+    ///
+    /// The sort is stable, shortcuts that are equal for `by` keep the order they had.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::shortcuts::SortBy;
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts: Shortcuts = ["spore", "Celeste", "hades"]
+    ///     .iter()
+    ///     .map(|name| Shortcut::new("", name, "", "", "", "", "").to_owned())
+    ///     .collect();
+    /// shortcuts.sort_by(SortBy::NameCaseInsensitive);
+    /// let names: Vec<&str> = shortcuts.iter().map(|s| s.app_name.as_str()).collect();
+    /// assert_eq!(vec!["Celeste", "hades", "spore"], names);
+    /// assert_eq!("0", shortcuts[0].order);
+    /// ```
+    pub fn sort_by(&mut self, by: SortBy) {
+        match by {
+            SortBy::NameCaseInsensitive => self.shortcuts.sort_by_cached_key(|s| {
+                s.app_name
+                    .chars()
+                    .flat_map(char::to_lowercase)
+                    .collect::<alloc::string::String>()
+            }),
+            SortBy::LastPlayed => self
+                .shortcuts
+                .sort_by_key(|s| core::cmp::Reverse(s.last_play_time)),
+            SortBy::AppId => self.shortcuts.sort_by_key(|s| s.app_id),
+            SortBy::RecentlyAdded => {
+                let mut added: Vec<(usize, ShortcutOwned)> = core::mem::take(&mut self.shortcuts)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, s)| (s.order.parse().unwrap_or(index), s))
+                    .collect();
+                added.sort_by_key(|(added, _)| core::cmp::Reverse(*added));
+                self.shortcuts = added.into_iter().map(|(_, s)| s).collect();
+            }
+        }
+        self.renumber();
+    }
+
+    /// Number the orders from 0 by the place of the shortcuts in the list, returns the orders
+    /// that changed.
+    ///
+    /// Files edited by several tools can have gaps or the same order more than once, which steam
+    /// may not read as intended. The shortcuts are not moved, only their orders are fixed.
+    ///
+    /// ### Examples
+    /// ```
+    /// use steam_shortcuts_util::shortcuts::OrderChange;
+    /// use steam_shortcuts_util::{Shortcut, Shortcuts};
+    ///
+    /// let mut shortcuts = Shortcuts::from(vec![
+    ///     Shortcut::new("0", "Celeste", "", "", "", "", "").to_owned(),
+    ///     Shortcut::new("0", "Hades", "", "", "", "", "").to_owned(),
+    /// ]);
+    /// let changes = shortcuts.renumber();
+    /// let change = OrderChange { index: 1, old_order: "0".to_string() };
+    /// assert_eq!(vec![change], changes);
+    /// assert_eq!("1", shortcuts[1].order);
+    /// ```
+    pub fn renumber(&mut self) -> Vec<OrderChange> {
+        let mut changes = Vec::new();
+        for (index, shortcut) in self.shortcuts.iter_mut().enumerate() {
+            let order = index.to_string();
+            if shortcut.order != order {
+                let old_order = core::mem::replace(&mut shortcut.order, order);
+                changes.push(OrderChange { index, old_order });
+            }
+        }
+        changes
+    }
+}
+
+impl From<Vec<ShortcutOwned>> for Shortcuts {
+    /// Keep the shortcuts with the order they have.
+    fn from(shortcuts: Vec<ShortcutOwned>) -> Self {
+        Self { shortcuts }
+    }
+}
+
+/// Collect shortcuts into a list, numbering them in the order they come, as
+/// [push_new](Shortcuts::push_new) does.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{Shortcut, Shortcuts};
+///
+/// let names = ["Celeste", "Spore"];
+/// let shortcuts: Shortcuts = names
+///     .iter()
+///     .map(|name| Shortcut::new("", name, "", "", "", "", "").to_owned())
+///     .collect();
+/// assert_eq!("Spore", shortcuts[1].app_name);
+/// assert_eq!("1", shortcuts[1].order);
+/// ```
+impl FromIterator<ShortcutOwned> for Shortcuts {
+    fn from_iter<I: IntoIterator<Item = ShortcutOwned>>(iter: I) -> Self {
+        let mut shortcuts = Self::new();
+        shortcuts.extend(iter);
+        shortcuts
+    }
+}
+
+/// Add shortcuts at the end, as [push_new](Shortcuts::push_new) does.
+impl Extend<ShortcutOwned> for Shortcuts {
+    fn extend<I: IntoIterator<Item = ShortcutOwned>>(&mut self, iter: I) {
+        for shortcut in iter {
+            self.push_new(shortcut);
+        }
+    }
+}
+
+impl IntoIterator for Shortcuts {
+    type Item = ShortcutOwned;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.shortcuts.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Shortcuts {
+    type Item = &'a ShortcutOwned;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Shortcuts {
+    type Item = &'a mut ShortcutOwned;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl Index<usize> for Shortcuts {
+    type Output = ShortcutOwned;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.shortcuts[index]
+    }
+}
+
+impl IndexMut<usize> for Shortcuts {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.shortcuts[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+
+    fn shortcut(name: &str, exe: &str) -> ShortcutOwned {
+        Shortcut::new("", name, exe, "", "", "", "").to_owned()
+    }
+
+    #[test]
+    fn edits_renumber() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.push_new(shortcut("Celeste", "celeste.exe"));
+        shortcuts.push_new(shortcut("Celeste", "other.exe")).app_id = 7;
+        shortcuts.push_new(shortcut("Spore", "spore.exe"));
+        shortcuts.push_new(shortcut("Hades", "hades.exe")).app_id = 7;
+
+        let removed = shortcuts.remove_by_app_id(7);
+        assert_eq!(2, removed.len());
+        shortcuts.retain(|s| s.app_name != "Celeste");
+        assert_eq!(1, shortcuts.len());
+        let spore = shortcuts.find_by_name("Spore").unwrap();
+        assert_eq!("0", spore.order);
+        assert_eq!(Some(spore), shortcuts.find_by_app_id(spore.app_id));
+        assert!(shortcuts.find_by_name("spore").is_none());
+
+        shortcuts.insert_at(0, shortcut("Hades", "hades.exe"));
+        assert!(shortcuts.remove_at(2).is_none());
+        assert_eq!("Hades", shortcuts.remove_at(0).unwrap().app_name);
+        assert_eq!("0", shortcuts[0].order);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn by_app_id_keeps_first() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts
+            .push_new(shortcut("Celeste", "celeste.exe"))
+            .app_id = 7;
+        shortcuts.push_new(shortcut("Spore", "spore.exe")).app_id = 7;
+        shortcuts.push_new(shortcut("Hades", "hades.exe")).app_id = 8;
+        let map = shortcuts.by_app_id();
+        assert_eq!(2, map.len());
+        assert_eq!("Celeste", map[&7].app_name);
+        assert_eq!("Hades", map[&8].app_name);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn touch_last_played_is_now() {
+        let mut shortcuts: Shortcuts = vec![shortcut("Celeste", ""), shortcut("Hades", "")]
+            .into_iter()
+            .collect();
+        let before = crate::shortcut::now().unwrap();
+        assert_eq!(2, shortcuts.touch_last_played(|_| true));
+        for shortcut in shortcuts.iter() {
+            assert!(shortcut.last_play_time as i64 >= before);
+        }
+        assert!(shortcuts.touch_last_played_at(|_| true, -1).is_err());
+        assert!(shortcuts.iter().all(|s| s.last_play_time as i64 >= before));
+    }
+
+    #[test]
+    fn hidden_shortcuts_are_saved() {
+        let mut shortcuts: Shortcuts = vec![shortcut("Celeste", ""), shortcut("Mario", "")]
+            .into_iter()
+            .collect();
+        shortcuts[1].tags.push("Emulators".to_string());
+        assert_eq!(1, shortcuts.set_hidden(Tag("Emulators"), true));
+
+        let borrowed: Vec<Shortcut> = shortcuts.iter().map(|s| s.borrow()).collect();
+        let mut bytes = vec![];
+        crate::shortcuts_writer::write_shortcuts_into(&mut bytes, &borrowed);
+        let loaded = crate::shortcuts_parser::parse_shortcuts(&bytes).unwrap();
+        assert!(!loaded[0].is_hidden);
+        assert!(loaded[1].is_hidden);
+    }
+
+    #[test]
+    fn sorts_are_stable() {
+        let mut shortcuts: Shortcuts = [("b", 5, 2), ("A", 0, 1), ("a", 9, 1), ("c", 5, 0)]
+            .iter()
+            .map(|(name, played, app_id)| {
+                let mut shortcut = shortcut(name, "");
+                shortcut.last_play_time = *played;
+                shortcut.app_id = *app_id;
+                shortcut
+            })
+            .collect();
+        let names = |shortcuts: &Shortcuts| -> Vec<String> {
+            shortcuts.iter().map(|s| s.app_name.clone()).collect()
+        };
+        shortcuts.sort_by(SortBy::LastPlayed);
+        assert_eq!(vec!["a", "b", "c", "A"], names(&shortcuts));
+        shortcuts.sort_by(SortBy::NameCaseInsensitive);
+        assert_eq!(vec!["a", "A", "b", "c"], names(&shortcuts));
+        shortcuts.sort_by(SortBy::AppId);
+        assert_eq!(vec!["c", "a", "A", "b"], names(&shortcuts));
+        shortcuts.sort_by(SortBy::RecentlyAdded);
+        assert_eq!(vec!["b", "A", "a", "c"], names(&shortcuts));
+        let orders: Vec<&str> = shortcuts.iter().map(|s| s.order.as_str()).collect();
+        assert_eq!(vec!["0", "1", "2", "3"], orders);
+    }
+
+    #[test]
+    fn works_with_iterators() {
+        let mut shortcuts: Shortcuts = vec![shortcut("Celeste", "celeste.exe")]
+            .into_iter()
+            .collect();
+        shortcuts.extend(vec![shortcut("Spore", "spore.exe")]);
+        for shortcut in &mut shortcuts {
+            shortcut.launch_options = "-novid".to_string();
+        }
+        shortcuts[0].icon = "celeste.ico".to_string();
+        let orders: Vec<&str> = (&shortcuts).into_iter().map(|s| s.order.as_str()).collect();
+        assert_eq!(vec!["0", "1"], orders);
+        let names: Vec<String> = shortcuts.into_iter().map(|s| s.app_name).collect();
+        assert_eq!(vec!["Celeste", "Spore"], names);
+    }
+}
@@ -0,0 +1,385 @@
+use std::path::{Path, PathBuf};
+
+use crate::diff::MATCH_BY;
+use crate::matching::{Identity, Matcher};
+use crate::shortcut::{KeySpelling, ShortcutOwned};
+use crate::{parse_shortcuts_with, shortcuts_to_bytes_with_spelling, ParseOptions, WriteOptions};
+use crate::{steam_dirs, steam_process};
+
+/// Error from loading or saving a [ShortcutsFile].
+#[derive(Debug)]
+pub enum ShortcutsFileError {
+    /// The file could not be read or written
+    Io(std::io::Error),
+    /// The content of the file could not be parsed
+    Parse(String),
+    /// The steam installation or user to load the file for could not be found
+    NotFound(String),
+    /// The file was not saved because steam is running
+    SteamRunning,
+}
+
+impl std::fmt::Display for ShortcutsFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutsFileError::Io(err) => write!(f, "Could not access shortcuts file: {}", err),
+            ShortcutsFileError::Parse(err) => write!(f, "Could not parse shortcuts file: {}", err),
+            ShortcutsFileError::NotFound(err) => {
+                write!(f, "Could not find shortcuts file: {}", err)
+            }
+            ShortcutsFileError::SteamRunning => write!(
+                f,
+                "Steam is running, close it before changing the shortcuts file"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutsFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShortcutsFileError::Io(err) => Some(err),
+            ShortcutsFileError::Parse(_)
+            | ShortcutsFileError::NotFound(_)
+            | ShortcutsFileError::SteamRunning => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ShortcutsFileError {
+    fn from(err: std::io::Error) -> Self {
+        ShortcutsFileError::Io(err)
+    }
+}
+
+/// A shortcuts.vdf file loaded into memory, that can be edited and saved back.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::{Shortcut, ShortcutsFile};
+///
+/// fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut file = ShortcutsFile::load("path/to/shortcuts.vdf")?;
+///     let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+///     file.shortcuts_mut().push(shortcut.to_owned());
+///     file.save()?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShortcutsFile {
+    path: PathBuf,
+    shortcuts: Vec<ShortcutOwned>,
+    /// The spelling of the keys in the loaded file, with the shortcut that used it
+    key_spellings: Vec<Spelled>,
+}
+
+/// The spelling of the keys of a loaded shortcut, found again by the app id, or else the exe and
+/// app_name, of the shortcut so it stays with the shortcut when shortcuts are removed or moved.
+#[derive(Debug, Clone)]
+struct Spelled {
+    app_id: u32,
+    exe: String,
+    app_name: String,
+    spelling: KeySpelling<'static>,
+}
+
+impl Identity for Spelled {
+    fn app_id(&self) -> u32 {
+        self.app_id
+    }
+
+    fn exe(&self) -> &str {
+        &self.exe
+    }
+
+    fn app_name(&self) -> &str {
+        &self.app_name
+    }
+}
+
+impl ShortcutsFile {
+    /// Create a file with no shortcuts, that will be written to the given path when saved.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            shortcuts: vec![],
+            key_spellings: vec![],
+        }
+    }
+
+    /// Read and parse the shortcuts file at the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ShortcutsFileError> {
+        let path = path.as_ref().to_path_buf();
+        let content = std::fs::read(&path)?;
+        let parsed = parse_shortcuts_with(content.as_slice(), &ParseOptions::default())
+            .map_err(ShortcutsFileError::Parse)?;
+        let shortcuts = parsed.shortcuts.iter().map(|s| s.to_owned()).collect();
+        let key_spellings = parsed
+            .shortcuts
+            .iter()
+            .zip(parsed.key_spellings.iter())
+            .map(|(shortcut, spelling)| Spelled {
+                app_id: shortcut.app_id,
+                exe: shortcut.exe.to_string(),
+                app_name: shortcut.app_name.to_string(),
+                spelling: spelling.to_owned(),
+            })
+            .collect();
+        Ok(Self {
+            path,
+            shortcuts,
+            key_spellings,
+        })
+    }
+
+    /// Load the shortcuts file of the steam account that logged in most recently.
+    ///
+    /// The steam installation is found with [steam_dirs::locate] and the account
+    /// is read from its loginusers.vdf. If the account has no shortcuts file yet,
+    /// an empty one is returned, which is created when saved.
+    pub fn load_for_most_recent_user() -> Result<Self, ShortcutsFileError> {
+        let steam_root = steam_dirs::locate().ok_or_else(|| {
+            ShortcutsFileError::NotFound("no steam installation found".to_string())
+        })?;
+        Self::load_for_most_recent_user_in(&steam_root)
+    }
+
+    fn load_for_most_recent_user_in(steam_root: &Path) -> Result<Self, ShortcutsFileError> {
+        let user = steam_dirs::most_recent_user(steam_root).ok_or_else(|| {
+            ShortcutsFileError::NotFound(format!(
+                "no logged in user found in {}",
+                steam_root.display()
+            ))
+        })?;
+        Self::load_for_user(steam_root, user.account_id)
+    }
+
+    /// Load the shortcuts file of the account that logged in most recently to a steam
+    /// installation found by `steamlocate` (requires the `steamlocate` feature).
+    #[cfg(feature = "steamlocate")]
+    pub fn load_for_most_recent_user_of(
+        steam_dir: &steamlocate::SteamDir,
+    ) -> Result<Self, ShortcutsFileError> {
+        Self::load_for_most_recent_user_in(steam_dir.path())
+    }
+
+    /// Load the shortcuts file of a user in a steam installation found by `steamlocate`
+    /// (requires the `steamlocate` feature).
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use steam_shortcuts_util::ShortcutsFile;
+    ///
+    /// fn example() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let steam_dir = steamlocate::SteamDir::locate()?;
+    ///     let file = ShortcutsFile::load_for_steam_dir(&steam_dir, 12345u32)?;
+    ///     println!("{} shortcuts", file.shortcuts().len());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "steamlocate")]
+    pub fn load_for_steam_dir<U: std::fmt::Display>(
+        steam_dir: &steamlocate::SteamDir,
+        user_id: U,
+    ) -> Result<Self, ShortcutsFileError> {
+        Self::load_for_user(steam_dir.path(), user_id)
+    }
+
+    /// Load the shortcuts file of a user in a steam installation.
+    ///
+    /// The user id is the account id, as a number or text (see [steam_dirs::shortcuts_path]).
+    /// If the user has no shortcuts file yet, an empty one is returned, which is created when saved.
+    pub fn load_for_user<P: AsRef<Path>, U: std::fmt::Display>(
+        steam_root: P,
+        user_id: U,
+    ) -> Result<Self, ShortcutsFileError> {
+        let path = steam_dirs::shortcuts_path(steam_root, user_id);
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::new(path))
+        }
+    }
+
+    /// The path this file was loaded from and will be saved to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The shortcuts in this file.
+    pub fn shortcuts(&self) -> &[ShortcutOwned] {
+        &self.shortcuts
+    }
+
+    /// The shortcuts in this file, for editing.
+    pub fn shortcuts_mut(&mut self) -> &mut Vec<ShortcutOwned> {
+        &mut self.shortcuts
+    }
+
+    /// Serialize the shortcuts to the bytes that [save](Self::save) would write.
+    ///
+    /// The keys of each shortcut are spelled as they were in the loaded file, so a file that was
+    /// not changed is written back byte for byte. A loaded shortcut is found again by its app id,
+    /// or else its exe and app_name, so removing, inserting or sorting shortcuts keeps the
+    /// spelling of the others.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let borrowed: Vec<_> = self.shortcuts.iter().map(|s| s.borrow()).collect();
+        let mut matcher = Matcher::new(&self.key_spellings, &MATCH_BY);
+        let spellings: Vec<KeySpelling> = self
+            .shortcuts
+            .iter()
+            .map(|shortcut| match matcher.take(shortcut) {
+                Some(index) => self.key_spellings[index].spelling.clone(),
+                None => KeySpelling::default(),
+            })
+            .collect();
+        shortcuts_to_bytes_with_spelling(&borrowed, &spellings, &WriteOptions::default())
+    }
+
+    /// Write the shortcuts back to the path of this file.
+    ///
+    /// The config folder is created if it does not exist yet.
+    pub fn save(&self) -> Result<(), ShortcutsFileError> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(&self.path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write the shortcuts back to the path of this file, unless steam is running.
+    ///
+    /// Steam overwrites shortcuts.vdf when it exits, so changes saved while it runs are lost.
+    /// Returns [ShortcutsFileError::SteamRunning] without writing anything if
+    /// [steam_is_running](crate::steam_process::steam_is_running).
+    pub fn save_checked(&self) -> Result<(), ShortcutsFileError> {
+        if steam_process::steam_is_running() {
+            return Err(ShortcutsFileError::SteamRunning);
+        }
+        self.save()
+    }
+}
+
+/// Load many shortcuts files in parallel, e.g. those of every user or a set of backups.
+///
+/// The results are in the same order as the paths (requires the `rayon` feature).
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::parse_many;
+///
+/// let results = parse_many(&["userdata/1/config/shortcuts.vdf", "userdata/2/config/shortcuts.vdf"]);
+/// for file in results.iter().flatten() {
+///     println!("{}: {} shortcuts", file.path().display(), file.shortcuts().len());
+/// }
+/// ```
+#[cfg(feature = "rayon")]
+pub fn parse_many<P: AsRef<Path> + Sync>(
+    paths: &[P],
+) -> Vec<Result<ShortcutsFile, ShortcutsFileError>> {
+    use rayon::prelude::*;
+
+    paths.par_iter().map(ShortcutsFile::load).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+    use crate::{parse_shortcuts, Shortcut};
+
+    #[test]
+    fn load_edit_save() {
+        let temp = temp_dir();
+        let path = temp.path().join("shortcuts.vdf");
+        std::fs::copy("src/testdata/shortcuts.vdf", &path).unwrap();
+
+        let mut file = ShortcutsFile::load(&path).unwrap();
+        assert_eq!(42, file.shortcuts().len());
+        let shortcut = Shortcut::new("42", "Spore", "spore.exe", "", "", "", "");
+        file.shortcuts_mut().push(shortcut.to_owned());
+        file.save().unwrap();
+
+        let reloaded = ShortcutsFile::load(&path).unwrap();
+        assert_eq!(43, reloaded.shortcuts().len());
+        assert_eq!("Spore", reloaded.shortcuts()[42].app_name);
+    }
+
+    #[test]
+    fn to_bytes_parses_to_same_shortcuts() {
+        let file = ShortcutsFile::load("src/testdata/shortcuts.vdf").unwrap();
+        let bytes = file.to_bytes();
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = parse_shortcuts(content.as_slice()).unwrap();
+        assert_eq!(shortcuts, parse_shortcuts(bytes.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn to_bytes_keeps_key_spelling() {
+        let file = ShortcutsFile::load("src/testdata/shortcutsspore.vdf").unwrap();
+        let content = std::fs::read("src/testdata/shortcutsspore.vdf").unwrap();
+        assert_eq!(content, file.to_bytes());
+    }
+
+    #[test]
+    fn removing_a_shortcut_keeps_the_spelling_of_the_others() {
+        let spore = std::fs::read("src/testdata/shortcutsspore.vdf").unwrap();
+        let spore = parse_shortcuts_with(&spore, &ParseOptions::default()).unwrap();
+        let firefox = std::fs::read("src/testdata/shortcutsfirefox.vdf").unwrap();
+        let firefox = parse_shortcuts_with(&firefox, &ParseOptions::default()).unwrap();
+        let mut second = firefox.shortcuts[0].clone();
+        second.order = "1";
+        let spellings = [
+            spore.key_spellings[0].clone(),
+            firefox.key_spellings[0].clone(),
+        ];
+        assert_ne!(spellings[0], spellings[1]);
+        let options = WriteOptions::default();
+        let shortcuts = [spore.shortcuts[0].clone(), second];
+        let mixed = shortcuts_to_bytes_with_spelling(&shortcuts, &spellings, &options);
+
+        let temp = temp_dir();
+        let path = temp.path().join("shortcuts.vdf");
+        std::fs::write(&path, &mixed).unwrap();
+        let mut file = ShortcutsFile::load(&path).unwrap();
+        assert_eq!(mixed, file.to_bytes());
+        file.shortcuts_mut().remove(0);
+        let expected = shortcuts_to_bytes_with_spelling(&shortcuts[1..], &spellings[1..], &options);
+        assert_eq!(expected, file.to_bytes());
+    }
+
+    #[test]
+    fn load_missing_file_is_io_error() {
+        let res = ShortcutsFile::load("src/testdata/does_not_exist.vdf");
+        assert!(matches!(res, Err(ShortcutsFileError::Io(_))));
+    }
+
+    #[test]
+    fn load_for_user_without_file_is_empty() {
+        let temp = temp_dir();
+        let steam_root = temp.path();
+        let file = ShortcutsFile::load_for_user(steam_root, "12345").unwrap();
+        assert!(file.shortcuts().is_empty());
+        assert!(file.path().ends_with("userdata/12345/config/shortcuts.vdf"));
+        let by_account_id = ShortcutsFile::load_for_user(steam_root, 12345u32).unwrap();
+        assert_eq!(file.path(), by_account_id.path());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parse_many_keeps_order() {
+        let paths = vec![
+            "src/testdata/shortcuts.vdf",
+            "src/testdata/does_not_exist.vdf",
+            "src/testdata/shortcutsfirefox.vdf",
+        ];
+        let results = parse_many(&paths);
+        assert_eq!(42, results[0].as_ref().unwrap().shortcuts().len());
+        assert!(matches!(results[1], Err(ShortcutsFileError::Io(_))));
+        assert_eq!(70, results[2].as_ref().unwrap().shortcuts().len());
+    }
+}
@@ -0,0 +1,117 @@
+//! Exporting and importing shortcuts as JSON (requires the `json` feature).
+//!
+//! This lets users edit their shortcuts in a text editor or with tools like `jq`,
+//! and write them back to shortcuts.vdf.
+//!
+//! ## Schema (version 1)
+//!
+//! The shortcuts have the serde representation of [Shortcut], with the keys of shortcuts.vdf:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "shortcuts": [
+//!     {
+//!       "appid": 2365067149,
+//!       "AppName": "Celeste",
+//!       "Exe": "\"C:\\Games\\Celeste.exe\"",
+//!       "StartDir": "\"C:\\Games\\\"",
+//!       "icon": "",
+//!       "ShortcutPath": "",
+//!       "LaunchOptions": "",
+//!       "IsHidden": false,
+//!       "AllowDesktopConfig": true,
+//!       "AllowOverlay": true,
+//!       "openvr": 0,
+//!       "Devkit": 0,
+//!       "DevkitGameID": "",
+//!       "DevkitOverrideAppID": 0,
+//!       "LastPlayTime": 0,
+//!       "FlatpakAppID": "",
+//!       "tags": ["favorite"]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Fields that are left out when importing are empty, 0 or false, and a missing `appid` is
+//! calculated from the exe and app name. The keys are also read in lowercase, as some steam
+//! clients write them. The order of the shortcuts is their position in the list.
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+use crate::shortcut_list::{List, ListRef, Version};
+
+pub use crate::shortcut_list::SCHEMA_VERSION;
+
+/// Export shortcuts to pretty printed JSON, in the schema described in the [module docs](self).
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{from_json, to_json, Shortcut};
+///
+/// let shortcuts = vec![Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "")];
+/// let json = to_json(&shortcuts);
+/// let imported = from_json(&json).unwrap();
+/// assert_eq!("Celeste", imported[0].app_name);
+/// ```
+pub fn to_json(shortcuts: &[Shortcut]) -> String {
+    serde_json::to_string_pretty(&ListRef::new(shortcuts)).unwrap_or_default()
+}
+
+/// Import shortcuts from JSON in the schema described in the [module docs](self).
+///
+/// Returns an error if the JSON is invalid, is from a newer schema version,
+/// or a field has the wrong type.
+pub fn from_json(json: &str) -> Result<Vec<ShortcutOwned>, String> {
+    let version: Version = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    version.check()?;
+    let list: List = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    Ok(list.into_shortcuts())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip_file() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(content.as_slice()).unwrap();
+        let imported = from_json(&to_json(&shortcuts)).unwrap();
+        let borrowed: Vec<_> = imported.iter().map(|s| s.borrow()).collect();
+        assert_eq!(shortcuts.len(), borrowed.len());
+        for (original, imported) in shortcuts.iter().zip(&borrowed) {
+            assert!(original.semantically_eq(imported));
+        }
+    }
+
+    #[test]
+    fn minimal_shortcut_gets_defaults() {
+        let json = r#"{"version": 1, "shortcuts": [{"AppName": "Celeste", "exe": "celeste.exe"}]}"#;
+        let imported = from_json(json).unwrap();
+        let mut expected = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        expected.allow_desktop_config = false;
+        expected.allow_overlay = false;
+        assert!(expected.semantically_eq(&imported[0].borrow()));
+    }
+
+    #[test]
+    fn uses_the_serde_names() {
+        let shortcuts = vec![Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "")];
+        let json: serde_json::Value = serde_json::from_str(&to_json(&shortcuts)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&shortcuts[0]).unwrap(),
+            json["shortcuts"][0]
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        assert!(from_json(r#"{"version": 2, "shortcuts": []}"#).is_err());
+        assert!(from_json(r#"{"shortcuts": []}"#).is_err());
+        let wrong_type =
+            r#"{"version": 1, "shortcuts": [{"AppName": "a", "Exe": "a", "openvr": "1"}]}"#;
+        assert!(from_json(wrong_type).is_err());
+    }
+}
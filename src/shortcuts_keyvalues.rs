@@ -0,0 +1,142 @@
+//! Conversions between shortcuts and the text KeyValues of the `keyvalues-parser` crate
+//! (requires the `keyvalues_parser` feature).
+//!
+//! A shortcut becomes an [Obj] with the same keys and values as the sections of
+//! [shortcuts_text_vdf](crate::shortcuts_text_vdf), so projects that already parse and render
+//! text VDF with `keyvalues-parser` can read and write shortcuts without mapping the fields
+//! by hand.
+//!
+//! ### Examples
+//! ```
+//! use steam_shortcuts_util::shortcuts_keyvalues::{shortcuts_from_vdf, shortcuts_to_vdf};
+//! use steam_shortcuts_util::Shortcut;
+//!
+//! let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+//! let text = shortcuts_to_vdf(&[shortcut]).to_string();
+//!
+//! let vdf = keyvalues_parser::parse(&text).unwrap().into_vdf();
+//! let shortcuts = shortcuts_from_vdf(&vdf).unwrap();
+//! assert_eq!("Celeste", shortcuts[0].app_name);
+//! ```
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use keyvalues_parser::{Obj, Value as KvValue, Vdf};
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+use crate::shortcuts_text_vdf::{shortcuts_from_section, shortcuts_to_section};
+use crate::text_vdf::{Section, Value};
+
+impl From<&Shortcut<'_>> for Obj<'static> {
+    fn from(shortcut: &Shortcut<'_>) -> Self {
+        obj_of(&Section::from(shortcut))
+    }
+}
+
+impl From<&ShortcutOwned> for Obj<'static> {
+    fn from(shortcut: &ShortcutOwned) -> Self {
+        Obj::from(&shortcut.borrow())
+    }
+}
+
+/// Read a shortcut from an object, the order is left empty.
+///
+/// Keys are matched case insensitively and missing keys get their default value.
+/// Fails if a number or boolean is not valid, or `tags` is not an object.
+impl TryFrom<&Obj<'_>> for ShortcutOwned {
+    type Error = String;
+
+    fn try_from(obj: &Obj<'_>) -> Result<Self, Self::Error> {
+        ShortcutOwned::try_from(&section_of(obj))
+    }
+}
+
+/// Convert shortcuts to a `shortcuts` object, like the root of shortcuts.vdf.
+pub fn shortcuts_to_vdf(shortcuts: &[Shortcut]) -> Vdf<'static> {
+    let root = shortcuts_to_section(shortcuts);
+    let list = root.get_section("shortcuts").cloned().unwrap_or_default();
+    Vdf::new(Cow::from("shortcuts"), KvValue::Obj(obj_of(&list)))
+}
+
+/// Read shortcuts from a `shortcuts` object made by [shortcuts_to_vdf].
+///
+/// The key of each shortcut becomes its order, and the shortcuts are sorted by it.
+pub fn shortcuts_from_vdf(vdf: &Vdf) -> Result<Vec<ShortcutOwned>, String> {
+    if !vdf.key.eq_ignore_ascii_case("shortcuts") {
+        return Err(format!("expected shortcuts, found {}", vdf.key));
+    }
+    let list = match &vdf.value {
+        KvValue::Obj(list) => section_of(list),
+        KvValue::Str(_) => return Err("shortcuts is not an object".to_string()),
+    };
+    let mut root = Section::new();
+    root.set_section("shortcuts", list);
+    shortcuts_from_section(&root)
+}
+
+fn obj_of(section: &Section) -> Obj<'static> {
+    let mut obj = Obj::new();
+    for (key, value) in section.entries.iter() {
+        let value = match value {
+            Value::Text(text) => KvValue::Str(Cow::from(text.clone())),
+            Value::Section(section) => KvValue::Obj(obj_of(section)),
+        };
+        obj.entry(Cow::from(key.clone())).or_default().push(value);
+    }
+    obj
+}
+
+fn section_of(obj: &Obj) -> Section {
+    let mut section = Section::new();
+    for (key, values) in obj.iter() {
+        for value in values {
+            let value = match value {
+                KvValue::Str(text) => Value::Text(text.to_string()),
+                KvValue::Obj(obj) => Value::Section(section_of(obj)),
+            };
+            section.entries.push((key.to_string(), value));
+        }
+    }
+    // The keys of an Obj are sorted as text, shortcuts and tags are listed by number
+    section
+        .entries
+        .sort_by_key(|(key, _)| key.parse::<u64>().ok());
+    section
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn shortcuts_survive_keyvalues_parser() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(&content).unwrap();
+        let text = shortcuts_to_vdf(&shortcuts).to_string();
+        let vdf = keyvalues_parser::parse(&text).unwrap().into_vdf();
+        let read = shortcuts_from_vdf(&vdf).unwrap();
+        assert_eq!(shortcuts.len(), read.len());
+        for (shortcut, read) in shortcuts.iter().zip(read.iter()) {
+            assert_eq!(shortcut.order, read.order);
+            assert!(shortcut.semantically_eq(&read.borrow()));
+        }
+    }
+
+    #[test]
+    fn tags_keep_their_order() {
+        let mut shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+        shortcut.tags = (0..12).map(|_| "tag").collect();
+        shortcut.tags[2] = "third";
+        shortcut.tags[10] = "eleventh";
+        let read = ShortcutOwned::try_from(&Obj::from(&shortcut)).unwrap();
+        assert_eq!("third", read.tags[2]);
+        assert_eq!("eleventh", read.tags[10]);
+        assert!(shortcuts_from_vdf(&Vdf::new(
+            Cow::from("shortcuts"),
+            KvValue::Str(Cow::from("")),
+        ))
+        .is_err());
+    }
+}
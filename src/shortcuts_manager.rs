@@ -0,0 +1,144 @@
+//! A [ShortcutsFile] that can be shared between threads.
+//!
+//! GUI apps often show the shortcuts on one thread while another syncs them with other
+//! launchers. [ShortcutsManager] keeps the shortcuts behind a `RwLock`, so it can be put in an
+//! `Arc` and used from every thread, and tracks if they were edited since they were saved.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+use crate::shortcut::ShortcutOwned;
+use crate::shortcuts_file::{ShortcutsFile, ShortcutsFileError};
+
+/// A shortcuts file that can be read and edited from many threads.
+///
+/// The manager is `Send + Sync`. Any number of threads can [read](Self::read) at the same time,
+/// an [edit](Self::edit) waits for them and blocks new reads until it is done. A panic in an
+/// edit does not make the manager unusable, the shortcuts are left as the edit left them.
+///
+/// ### Examples
+/// ```no_run
+/// use std::sync::Arc;
+/// use steam_shortcuts_util::{Shortcut, ShortcutsManager};
+///
+/// fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let manager = Arc::new(ShortcutsManager::load("path/to/shortcuts.vdf")?);
+///     let sync = {
+///         let manager = manager.clone();
+///         std::thread::spawn(move || {
+///             let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+///             manager.edit(|shortcuts| shortcuts.push(shortcut.to_owned()));
+///         })
+///     };
+///     println!("{} shortcuts", manager.read().shortcuts().len());
+///     sync.join().unwrap();
+///     manager.flush()?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ShortcutsManager {
+    file: RwLock<ShortcutsFile>,
+    edited: AtomicBool,
+}
+
+impl ShortcutsManager {
+    /// Manage a file that is already loaded.
+    pub fn new(file: ShortcutsFile) -> Self {
+        Self {
+            file: RwLock::new(file),
+            edited: AtomicBool::new(false),
+        }
+    }
+
+    /// Read and parse the shortcuts file at the given path, see [ShortcutsFile::load].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ShortcutsFileError> {
+        Ok(Self::new(ShortcutsFile::load(path)?))
+    }
+
+    /// The file, which can not be edited until the guard is dropped.
+    pub fn read(&self) -> RwLockReadGuard<'_, ShortcutsFile> {
+        self.file.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Edit the shortcuts, no other thread can read or edit them until `edit` returns.
+    ///
+    /// The shortcuts are marked as edited, so the next [flush](Self::flush) saves them.
+    pub fn edit<R, F: FnOnce(&mut Vec<ShortcutOwned>) -> R>(&self, edit: F) -> R {
+        let mut file = self.file.write().unwrap_or_else(PoisonError::into_inner);
+        self.edited.store(true, Ordering::SeqCst);
+        edit(file.shortcuts_mut())
+    }
+
+    /// If the shortcuts were edited since they were loaded or last flushed.
+    pub fn is_edited(&self) -> bool {
+        self.edited.load(Ordering::SeqCst)
+    }
+
+    /// Save the shortcuts if they were edited, returns if the file was written.
+    ///
+    /// Edits wait until the file is written. If saving fails the shortcuts stay marked as
+    /// edited, so the next flush tries again.
+    pub fn flush(&self) -> Result<bool, ShortcutsFileError> {
+        let file = self.read();
+        if !self.edited.swap(false, Ordering::SeqCst) {
+            return Ok(false);
+        }
+        if let Err(err) = file.save() {
+            self.edited.store(true, Ordering::SeqCst);
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    /// Stop managing the file, e.g. to save it with [ShortcutsFile::save_checked].
+    pub fn into_inner(self) -> ShortcutsFile {
+        self.file
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::sync::Arc;
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ShortcutsManager>();
+    }
+
+    #[test]
+    fn edits_from_threads_are_flushed() {
+        let temp = temp_dir();
+        let path = temp.path().join("shortcuts.vdf");
+        let manager = Arc::new(ShortcutsManager::new(ShortcutsFile::new(&path)));
+        assert!(!manager.flush().unwrap());
+
+        let threads: Vec<_> = (0..4)
+            .map(|index| {
+                let manager = manager.clone();
+                std::thread::spawn(move || {
+                    let order = index.to_string();
+                    let shortcut = crate::Shortcut::new(&order, "Celeste", "", "", "", "", "");
+                    manager.edit(|shortcuts| shortcuts.push(shortcut.to_owned()));
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert!(manager.is_edited());
+        assert!(manager.flush().unwrap());
+        assert!(!manager.is_edited());
+
+        let saved = ShortcutsFile::load(&path).unwrap();
+        assert_eq!(4, saved.shortcuts().len());
+        assert_eq!(4, manager.read().shortcuts().len());
+    }
+}
@@ -1,10 +1,11 @@
-use std::collections::HashMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 
-use nom::bytes::complete::{tag, take, take_till};
-use nom::multi::{many0, many1};
-use nom::IResult;
-
-use crate::shortcut::Shortcut;
+use crate::bytes::{fail, tag, take, take_through, PResult, BS, NULL, SOH, STX};
+use crate::shortcut::{KeySpelling, Shortcut, Tags};
+use crate::shortcut_view::{decode_u32, scan_line, scan_tag, RawValue};
 
 /// Parse bytes to shortcuts, if the bytes are in a format of the shortcuts.vdf file.
 ///
@@ -30,134 +31,596 @@ pub fn parse_shortcuts<'a>(shortcuts_bytes: &'a [u8]) -> Result<Vec<Shortcut<'a>
     }
 }
 
-fn get_shortcut<'a>(i: &'a [u8]) -> nom::IResult<&[u8], Shortcut<'a>> {
-    let (i, order) = get_order(i)?;
-
-    let (i, lines) = parse_all_lines(i)?;
+/// Options for how [parse_shortcuts_with] reads a file.
+///
+/// The default reads files like [parse_shortcuts] does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Which value counts when a key appears more than once in a shortcut
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Caps on the size of the file and its shortcuts
+    pub limits: Limits,
+    /// What to do with a shortcut whose order is not a number
+    pub invalid_order: InvalidOrderPolicy,
+    /// What to do with bytes after the end of the shortcuts
+    pub trailing_bytes: TrailingBytesPolicy,
+}
 
-    let numeric_value = |name: &str| {
-        lines
-            .get(name.to_lowercase().as_str())
-            .map(|l| l.num_value())
-            .unwrap_or_default()
-    };
-    let text_value = |name: &str| {
-        lines
-            .get(name.to_lowercase().as_str())
-            .map(|l| l.text_value())
-            .unwrap_or_default()
-    };
+/// What to do with a shortcut whose order is not a number, as some tools write them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidOrderPolicy {
+    /// Keep the shortcut at its place in the file. The writers number shortcuts by their
+    /// position, so the order is fixed when the file is written again.
+    #[default]
+    Keep,
+    /// Fail to parse the file.
+    Error,
+}
 
-    let app_id = numeric_value("app_id");
-    let app_id = if app_id == 0 {
-        numeric_value("appid")
-    } else {
-        app_id
-    };
-    let mut app_name = text_value("AppName");
-    let mut exe = text_value("Exe");
-    let mut start_dir = text_value("StartDir");
-    let mut icon = text_value("icon");
-    let mut shortcut_path = text_value("ShortcutPath");
-    let mut launch_options = text_value("LaunchOptions");
-    let mut is_hidden = numeric_value("IsHidden") != 0;
-    let mut allow_desktop_config = numeric_value("AllowDesktopConfig") != 0;
-    let mut allow_overlay = numeric_value("AllowOverlay") != 0;
-    let mut open_vr = numeric_value("openvr");
-    let mut dev_kit = numeric_value("Devkit");
-
-    let mut dev_kit_game_id = text_value("DevkitGameID");
-    let mut dev_kit_overrite_app_id = numeric_value("DevkitOverrideAppID");
-    let mut last_play_time = numeric_value("LastPlayTime");
-
-    let (i, tags) = get_tags(i)?;
-    let (i, lines) = parse_all_lines(i)?;
-    let text_value = |name: &str| {
-        lines
-            .get(name.to_lowercase().as_str())
-            .map(|l| l.text_value())
-            .unwrap_or_default()
-    };
+/// Caps on the size of a file, for parsing files that are not from a steam install.
+///
+/// A file over a cap fails to parse, before anything is done with the shortcuts. Texts and tags
+/// are checked as they are read, so an oversized shortcut is never built. The default has no
+/// caps.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_parser::{parse_shortcuts_with, Limits, ParseOptions};
+///
+/// let options = ParseOptions {
+///     limits: Limits {
+///         max_shortcuts: 1,
+///         ..Limits::default()
+///     },
+///     ..ParseOptions::default()
+/// };
+/// let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+/// assert!(parse_shortcuts_with(&content, &options).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The most bytes in the file
+    pub max_bytes: usize,
+    /// The most shortcuts in the file
+    pub max_shortcuts: usize,
+    /// The most tags of a shortcut
+    pub max_tags: usize,
+    /// The most bytes in the order, a text or a tag of a shortcut
+    pub max_text_len: usize,
+}
 
-    if text_value("AppName") != "" {
-        app_name = text_value("AppName");
-    }
-    if text_value("Exe") != "" {
-        exe = text_value("Exe");
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_bytes: usize::MAX,
+            max_shortcuts: usize::MAX,
+            max_tags: usize::MAX,
+            max_text_len: usize::MAX,
+        }
     }
-    if text_value("StartDir") != "" {
-        start_dir = text_value("StartDir");
+}
+
+/// Checks the texts and tags of the shortcut that is parsed against the [Limits], as they are
+/// read.
+struct Caps<'k> {
+    index: usize,
+    limits: &'k Limits,
+    /// Why the shortcut is over a cap, set when a check fails
+    exceeded: &'k mut Option<String>,
+}
+
+impl Caps<'_> {
+    /// Returns false if the text is longer than the cap.
+    fn text(&mut self, text: &str) -> bool {
+        if text.len() > self.limits.max_text_len {
+            *self.exceeded = Some(format!(
+                "Shortcut {} has a text longer than {} bytes",
+                self.index, self.limits.max_text_len
+            ));
+            return false;
+        }
+        true
     }
-    if text_value("icon") != "" {
-        icon = text_value("icon");
+
+    /// Returns false if one more tag than `tags` is over the cap.
+    fn another_tag(&mut self, tags: usize) -> bool {
+        if tags >= self.limits.max_tags {
+            *self.exceeded = Some(format!(
+                "Shortcut {} has more than {} tags",
+                self.index, self.limits.max_tags
+            ));
+            return false;
+        }
+        true
     }
-    if text_value("ShortcutPath") != "" {
-        shortcut_path = text_value("ShortcutPath");
+}
+
+/// Which value counts when a key appears more than once in a shortcut.
+///
+/// Keys are compared case insensitively, so `AppName` and `appname` are the same key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the first value of the key.
+    First,
+    /// Keep the last value of the key, except that keys after the tags only count if they have a
+    /// value. This is what [parse_shortcuts] does.
+    #[default]
+    Last,
+    /// Fail to parse the file.
+    Error,
+}
+
+/// What to do with bytes after the end of the shortcuts.
+///
+/// Those are left by some tools, or are a shortcut that could not be parsed and the shortcuts
+/// after it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingBytesPolicy {
+    /// Ignore the bytes, as [parse_shortcuts] does.
+    #[default]
+    Ignore,
+    /// Fail to parse the file.
+    Error,
+}
+
+/// Something unusual in a file that could still be parsed, often left by other tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic<'a> {
+    /// A key appeared more than once in a shortcut
+    DuplicateKey {
+        /// The index of the shortcut in the file
+        shortcut: usize,
+        /// The key as it was spelled the second (or later) time
+        key: &'a str,
+    },
+    /// The order of a shortcut is not a number
+    InvalidOrder {
+        /// The index of the shortcut in the file
+        shortcut: usize,
+        /// The order as it is in the file
+        order: &'a str,
+    },
+    /// There are bytes after the end of the shortcuts
+    TrailingBytes {
+        /// Where the bytes start in the file
+        offset: usize,
+        /// The number of bytes
+        len: usize,
+    },
+}
+
+/// The shortcuts [parse_shortcuts_with] read, and what was unusual about the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<'a> {
+    pub shortcuts: Vec<Shortcut<'a>>,
+    /// The spelling of the keys of each shortcut, to write them back the same way with
+    /// [shortcuts_to_bytes_with_spelling](crate::shortcuts_writer::shortcuts_to_bytes_with_spelling)
+    pub key_spellings: Vec<KeySpelling<'a>>,
+    pub diagnostics: Vec<Diagnostic<'a>>,
+}
+
+/// Parse bytes to shortcuts with the given options, and report what was unusual about the file.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_parser::{
+///     parse_shortcuts_with, Diagnostic, DuplicateKeyPolicy, ParseOptions,
+/// };
+///
+/// let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+/// let options = ParseOptions {
+///     duplicate_keys: DuplicateKeyPolicy::First,
+///     ..ParseOptions::default()
+/// };
+/// let parsed = parse_shortcuts_with(&content, &options).unwrap();
+/// assert_eq!("Celeste", parsed.shortcuts[0].app_name);
+/// for diagnostic in parsed.diagnostics {
+///     match diagnostic {
+///         Diagnostic::DuplicateKey { shortcut, key } => {
+///             println!("{} is in shortcut {} more than once", key, shortcut)
+///         }
+///         Diagnostic::InvalidOrder { shortcut, order } => {
+///             println!("Shortcut {} has the order {}", shortcut, order)
+///         }
+///         Diagnostic::TrailingBytes { offset, len } => {
+///             println!("{} bytes after the shortcuts at {}", len, offset)
+///         }
+///     }
+/// }
+/// ```
+pub fn parse_shortcuts_with<'a>(
+    shortcuts_bytes: &'a [u8],
+    options: &ParseOptions,
+) -> Result<Parsed<'a>, String> {
+    let limits = &options.limits;
+    if shortcuts_bytes.len() > limits.max_bytes {
+        return Err(format!("More than {} bytes", limits.max_bytes));
     }
-    if text_value("LaunchOptions") != "" {
-        launch_options = text_value("LaunchOptions");
+    let mut diagnostics = vec![];
+    let (i, _) = shotcut_content(shortcuts_bytes).map_err(|err| format!("{}", err))?;
+    let mut shortcuts = vec![];
+    let mut key_spellings = vec![];
+    let mut i = i;
+    let mut exceeded = None;
+    while let Ok((rest, (shortcut, key_spelling))) = get_shortcut_with(
+        i,
+        shortcuts.len(),
+        options,
+        Some(&mut diagnostics),
+        &mut exceeded,
+    ) {
+        if shortcuts.len() >= limits.max_shortcuts {
+            return Err(format!("More than {} shortcuts", limits.max_shortcuts));
+        }
+        if !is_number(shortcut.order) {
+            if options.invalid_order == InvalidOrderPolicy::Error {
+                return Err(format!(
+                    "Order '{}' of shortcut {} is not a number",
+                    shortcut.order,
+                    shortcuts.len()
+                ));
+            }
+            diagnostics.push(Diagnostic::InvalidOrder {
+                shortcut: shortcuts.len(),
+                order: shortcut.order,
+            });
+        }
+        shortcuts.push(shortcut);
+        key_spellings.push(key_spelling);
+        i = rest;
     }
-    if numeric_value("IsHidden") != 0 {
-        is_hidden = numeric_value("IsHidden") != 0;
+    if let Some(exceeded) = exceeded {
+        return Err(exceeded);
     }
-    if numeric_value("AllowDesktopConfig") != 0 {
-        allow_desktop_config = numeric_value("AllowDesktopConfig") != 0;
+    // The end of the list of shortcuts and of the file
+    for _ in 0..2 {
+        if let Some(rest) = i.strip_prefix(&[BS]) {
+            i = rest;
+        }
     }
-    if numeric_value("AllowOverlay") != 0 {
-        allow_overlay = numeric_value("AllowOverlay") != 0;
+    if !i.is_empty() {
+        let offset = shortcuts_bytes.len() - i.len();
+        if options.trailing_bytes == TrailingBytesPolicy::Error {
+            return Err(format!(
+                "{} bytes after the shortcuts at {}",
+                i.len(),
+                offset
+            ));
+        }
+        diagnostics.push(Diagnostic::TrailingBytes {
+            offset,
+            len: i.len(),
+        });
     }
-    if numeric_value("openvr") != 0 {
-        open_vr = numeric_value("openvr");
+    if options.duplicate_keys == DuplicateKeyPolicy::Error {
+        let duplicate = diagnostics.iter().find_map(|diagnostic| match diagnostic {
+            Diagnostic::DuplicateKey { shortcut, key } => Some((shortcut, key)),
+            _ => None,
+        });
+        if let Some((shortcut, key)) = duplicate {
+            return Err(format!("Duplicate key {} in shortcut {}", key, shortcut));
+        }
     }
-    if numeric_value("Devkit") != 0 {
-        dev_kit = numeric_value("Devkit");
+    Ok(Parsed {
+        shortcuts,
+        key_spellings,
+        diagnostics,
+    })
+}
+
+/// Parse bytes to shortcuts that borrow their texts from an arena (requires the `bumpalo` feature).
+///
+/// The bytes are copied into the arena once, so the shortcuts outlive the buffer they were read
+/// into, without allocating a `String` for every text as [Shortcut::to_owned] does.
+///
+/// ### Examples
+/// ```
+/// use bumpalo::Bump;
+/// use steam_shortcuts_util::shortcuts_parser::parse_shortcuts_in;
+///
+/// let arena = Bump::new();
+/// let shortcuts = {
+///     let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+///     parse_shortcuts_in(&content, &arena).unwrap()
+/// };
+/// assert_eq!("Celeste", shortcuts[0].app_name);
+/// ```
+#[cfg(feature = "bumpalo")]
+pub fn parse_shortcuts_in<'b>(
+    shortcuts_bytes: &[u8],
+    arena: &'b bumpalo::Bump,
+) -> Result<Vec<Shortcut<'b>>, String> {
+    parse_shortcuts(arena.alloc_slice_copy(shortcuts_bytes))
+}
+
+fn get_shortcut<'a>(i: &'a [u8]) -> PResult<'a, Shortcut<'a>> {
+    let (i, (shortcut, _)) = get_shortcut_with(i, 0, &ParseOptions::default(), None, &mut None)?;
+    Ok((i, shortcut))
+}
+
+/// Parse a shortcut and the spelling of its keys, adding the duplicate keys to `diagnostics` if
+/// it is given.
+///
+/// Fails as soon as the shortcut is over one of the [Limits] of the options, with the reason in
+/// `exceeded`.
+fn get_shortcut_with<'a>(
+    i: &'a [u8],
+    index: usize,
+    options: &ParseOptions,
+    diagnostics: Option<&mut Vec<Diagnostic<'a>>>,
+    exceeded: &mut Option<String>,
+) -> PResult<'a, (Shortcut<'a>, KeySpelling<'a>)> {
+    let mut caps = Caps {
+        index,
+        limits: &options.limits,
+        exceeded,
+    };
+    let (i, order) = get_order(i)?;
+    if !caps.text(order) {
+        return fail(i);
     }
 
-    if text_value("DevkitGameID") != "" {
-        dev_kit_game_id = text_value("DevkitGameID");
+    let mut fields = Fields::default();
+    let mut key_names = vec![];
+    let mut keys = Keys {
+        index,
+        policy: options.duplicate_keys,
+        names: &mut key_names,
+        diagnostics,
+    };
+    let (i, _) = parse_fields(i, &mut fields, &mut keys, &mut caps, false)?;
+    let (i, tags) = get_tags(i, &mut caps)?;
+    // Some tools write keys after the tags, those only count if they have a value
+    let (i, _) = parse_fields(i, &mut fields, &mut keys, &mut caps, true)?;
+
+    let (i, _) = tag(&[BS], i)?;
+    let shortcut = fields.into_shortcut(order, tags);
+    Ok((i, (shortcut, KeySpelling::new(key_names))))
+}
+
+/// Parse bytes to shortcuts without checking that the texts are valid UTF-8.
+///
+/// This gives the same shortcuts as [parse_shortcuts], but is faster for large files. The
+/// structure of the file is still followed, so a broken file gives an error or fewer shortcuts.
+///
+/// # Safety
+///
+/// Every key, text and tag in `shortcuts_bytes` must be valid UTF-8, as it is in the files that
+/// steam and this crate write. Use [parse_shortcuts] for files from anywhere else.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_parser::parse_shortcuts_trusted;
+///
+/// let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+/// // The file was written by steam
+/// let shortcuts = unsafe { parse_shortcuts_trusted(&content) }.unwrap();
+/// assert_eq!("Celeste", shortcuts[0].app_name);
+/// ```
+pub unsafe fn parse_shortcuts_trusted(shortcuts_bytes: &[u8]) -> Result<Vec<Shortcut<'_>>, String> {
+    let (i, _) = shotcut_content(shortcuts_bytes).map_err(|err| format!("{}", err))?;
+    let mut shortcuts = vec![];
+    let mut i = i;
+    while let Ok((rest, shortcut)) = get_shortcut_trusted(i) {
+        shortcuts.push(shortcut);
+        i = rest;
     }
-    if numeric_value("DevkitOverrideAppID") != 0 {
-        dev_kit_overrite_app_id = numeric_value("DevkitOverrideAppID");
+    Ok(shortcuts)
+}
+
+/// [get_shortcut], with the texts taken as UTF-8 without checking them.
+unsafe fn get_shortcut_trusted(i: &[u8]) -> PResult<'_, Shortcut<'_>> {
+    let (i, _) = tag(&[NULL], i)?;
+    let (i, order) = take_through(NULL, i)?;
+
+    let mut fields = Fields::default();
+    let i = parse_fields_trusted(i, &mut fields, false);
+    let (i, _) = tag(b"\0tags\0", i)?;
+    let (i, mut tags_bytes) = take_through(BS, i)?;
+    let mut tags = Tags::new();
+    while let Ok((rest, tag_name)) = scan_tag(tags_bytes) {
+        tags.push(core::str::from_utf8_unchecked(tag_name));
+        tags_bytes = rest;
     }
-    if numeric_value("LastPlayTime") != 0 {
-        last_play_time = numeric_value("LastPlayTime");
+    let i = parse_fields_trusted(i, &mut fields, true);
+
+    let (i, _) = tag(&[BS], i)?;
+    let order = core::str::from_utf8_unchecked(order);
+    Ok((i, fields.into_shortcut(order, tags)))
+}
+
+/// [parse_fields], with the texts taken as UTF-8 without checking them.
+unsafe fn parse_fields_trusted<'a>(
+    mut i: &'a [u8],
+    fields: &mut Fields<'a>,
+    only_values: bool,
+) -> &'a [u8] {
+    while let Ok((rest, (key, value))) = scan_line(i) {
+        let name = core::str::from_utf8_unchecked(key);
+        let line = match value {
+            RawValue::Text(value) => LineType::Text {
+                name,
+                value: core::str::from_utf8_unchecked(value),
+            },
+            RawValue::Number(value) => LineType::Numeric {
+                name,
+                value: decode_u32(value),
+            },
+        };
+        fields.set(&line, only_values);
+        i = rest;
     }
+    i
+}
 
-    let bs = ascii::AsciiChar::BackSpace.as_byte();
-    let (i, _) = tag([bs])(i)?;
-    IResult::Ok((
-        i,
+/// The values of the known keys of a shortcut, as they are parsed.
+#[derive(Default)]
+struct Fields<'a> {
+    app_id: u32,
+    appid: u32,
+    app_name: &'a str,
+    exe: &'a str,
+    start_dir: &'a str,
+    icon: &'a str,
+    shortcut_path: &'a str,
+    launch_options: &'a str,
+    is_hidden: u32,
+    allow_desktop_config: u32,
+    allow_overlay: u32,
+    open_vr: u32,
+    dev_kit: u32,
+    dev_kit_game_id: &'a str,
+    dev_kit_overrite_app_id: u32,
+    last_play_time: u32,
+    flatpak_app_id: &'a str,
+}
+
+impl<'a> Fields<'a> {
+    fn into_shortcut(self, order: &'a str, tags: Tags<'a>) -> Shortcut<'a> {
+        let app_id = if self.app_id != 0 {
+            self.app_id
+        } else {
+            self.appid
+        };
         Shortcut {
             order,
             app_id,
-            app_name,
-            exe,
-            start_dir,
-            icon,
-            shortcut_path,
-            launch_options,
-            is_hidden,
-            allow_desktop_config,
-            allow_overlay,
-            open_vr,
-            dev_kit,
-            dev_kit_game_id,
-            last_play_time,
+            app_name: self.app_name,
+            exe: self.exe,
+            start_dir: self.start_dir,
+            icon: self.icon,
+            shortcut_path: self.shortcut_path,
+            launch_options: self.launch_options,
+            is_hidden: self.is_hidden != 0,
+            allow_desktop_config: self.allow_desktop_config != 0,
+            allow_overlay: self.allow_overlay != 0,
+            open_vr: self.open_vr,
+            dev_kit: self.dev_kit,
+            dev_kit_game_id: self.dev_kit_game_id,
+            last_play_time: self.last_play_time,
+            flatpak_app_id: self.flatpak_app_id,
             tags,
-            dev_kit_overrite_app_id,
-        },
-    ))
+            dev_kit_overrite_app_id: self.dev_kit_overrite_app_id,
+        }
+    }
+
+    /// Set the field of the key of the line, if it is a known key.
+    ///
+    /// With `only_values` empty texts and zeros do not replace what was parsed before.
+    fn set(&mut self, line: &LineType<'a>, only_values: bool) {
+        let text = |field: &mut &'a str| {
+            let value = line.text_value();
+            if !only_values || !value.is_empty() {
+                *field = value;
+            }
+        };
+        let number = |field: &mut u32| {
+            let value = line.num_value();
+            if !only_values || value != 0 {
+                *field = value;
+            }
+        };
+        let name = line.name();
+        let is = |key: &str| name.eq_ignore_ascii_case(key);
+        if is("app_id") {
+            number(&mut self.app_id)
+        } else if is("appid") {
+            number(&mut self.appid)
+        } else if is("AppName") {
+            text(&mut self.app_name)
+        } else if is("Exe") {
+            text(&mut self.exe)
+        } else if is("StartDir") {
+            text(&mut self.start_dir)
+        } else if is("icon") {
+            text(&mut self.icon)
+        } else if is("ShortcutPath") {
+            text(&mut self.shortcut_path)
+        } else if is("LaunchOptions") {
+            text(&mut self.launch_options)
+        } else if is("IsHidden") {
+            number(&mut self.is_hidden)
+        } else if is("AllowDesktopConfig") {
+            number(&mut self.allow_desktop_config)
+        } else if is("AllowOverlay") {
+            number(&mut self.allow_overlay)
+        } else if is("openvr") {
+            number(&mut self.open_vr)
+        } else if is("Devkit") {
+            number(&mut self.dev_kit)
+        } else if is("DevkitGameID") {
+            text(&mut self.dev_kit_game_id)
+        } else if is("DevkitOverrideAppID") {
+            number(&mut self.dev_kit_overrite_app_id)
+        } else if is("LastPlayTime") {
+            number(&mut self.last_play_time)
+        } else if is("FlatpakAppID") {
+            text(&mut self.flatpak_app_id)
+        }
+    }
 }
 
-fn parse_shortcuts_inner<'a>(shortcuts_bytes: &'a [u8]) -> nom::IResult<&[u8], Vec<Shortcut<'a>>> {
-    let (i, _) = shotcut_content(shortcuts_bytes)?;
-    let (i, list) = many0(get_shortcut)(i)?;
+/// The keys of the shortcut that is parsed, to find the keys that appear more than once.
+struct Keys<'k, 'a> {
+    index: usize,
+    policy: DuplicateKeyPolicy,
+    names: &'k mut Vec<&'a str>,
+    diagnostics: Option<&'k mut Vec<Diagnostic<'a>>>,
+}
 
-    let bs = ascii::AsciiChar::BackSpace.as_byte();
-    let bs_tag: nom::IResult<&[u8], _> = tag([bs])(i);
-    match bs_tag {
-        Ok((i, _bs)) => IResult::Ok((i, list)),
-        Err(_) => IResult::Ok((i, list)),
+impl<'a> Keys<'_, 'a> {
+    /// Record the key of a line, returns false if its value should be ignored.
+    fn add(&mut self, name: &'a str) -> bool {
+        let mut counts = true;
+        if self.diagnostics.is_some() || self.policy != DuplicateKeyPolicy::Last {
+            let seen = self.names.iter().any(|k| k.eq_ignore_ascii_case(name));
+            if seen {
+                if let Some(diagnostics) = self.diagnostics.as_deref_mut() {
+                    diagnostics.push(Diagnostic::DuplicateKey {
+                        shortcut: self.index,
+                        key: name,
+                    });
+                }
+                counts = self.policy != DuplicateKeyPolicy::First;
+            }
+        }
+        self.names.push(name);
+        counts
+    }
+}
+
+/// Parse lines until one does not parse, setting the fields and recording the key names.
+///
+/// Fails as soon as a text is over the cap.
+fn parse_fields<'a>(
+    mut i: &'a [u8],
+    fields: &mut Fields<'a>,
+    keys: &mut Keys<'_, 'a>,
+    caps: &mut Caps,
+    only_values: bool,
+) -> PResult<'a, ()> {
+    while let Ok((rest, line)) = parse_a_line(i) {
+        if !caps.text(line.text_value()) {
+            return fail(i);
+        }
+        if keys.add(line.name()) {
+            fields.set(&line, only_values);
+        }
+        i = rest;
+    }
+    Ok((i, ()))
+}
+
+fn parse_shortcuts_inner<'a>(shortcuts_bytes: &'a [u8]) -> PResult<'a, Vec<Shortcut<'a>>> {
+    let (mut i, _) = shotcut_content(shortcuts_bytes)?;
+    let mut list = vec![];
+    while let Ok((rest, shortcut)) = get_shortcut(i) {
+        list.push(shortcut);
+        i = rest;
+    }
+    match tag(&[BS], i) {
+        Ok((i, _bs)) => Ok((i, list)),
+        Err(_) => Ok((i, list)),
     }
 }
 
@@ -189,114 +652,82 @@ impl<'a> LineType<'a> {
     }
 }
 
-fn parse_all_lines<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], HashMap<String, LineType<'a>>> {
-    let (i, list) = many0(parse_a_line)(i)?;
-    let mut res = HashMap::new();
-    let list_iter = list.into_iter();
-    list_iter.for_each(|l| {
-        res.insert(l.name().to_lowercase(), l);
-    });
-    IResult::Ok((i, res))
-}
-
-fn parse_a_line<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], LineType<'a>> {
+pub(crate) fn parse_a_line<'a>(i: &'a [u8]) -> PResult<'a, LineType<'a>> {
     if let Ok((i, (name, value))) = parse_text_line(i) {
-        return IResult::Ok((i, LineType::Text { name, value }));
+        return Ok((i, LineType::Text { name, value }));
     }
     let (i, (name, value)) = parse_numeric_line(i)?;
-    return IResult::Ok((i, LineType::Numeric { name, value }));
+    Ok((i, LineType::Numeric { name, value }))
 }
 
-fn parse_numeric_line<'b>(i: &'b [u8]) -> nom::IResult<&'b [u8], (&'b str, u32)> {
-    let stx = ascii::AsciiChar::SOX.as_byte();
-
-    let (i, _) = tag([stx])(i)?;
+fn parse_numeric_line(i: &[u8]) -> PResult<'_, (&str, u32)> {
+    let (i, _) = tag(&[STX], i)?;
     let (i, key) = get_null_terminated_str(i)?;
     let (i, value) = get_a_u32(i)?;
-    IResult::Ok((i, (key, value)))
+    Ok((i, (key, value)))
 }
 
-fn parse_text_line<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], (&'a str, &'a str)> {
-    let soh = ascii::AsciiChar::SOH.as_byte();
-    let (i, _) = tag([soh])(i)?;
+fn parse_text_line(i: &[u8]) -> PResult<'_, (&str, &str)> {
+    let (i, _) = tag(&[SOH], i)?;
     let (i, key) = get_null_terminated_str(i)?;
     let (i, value) = get_null_terminated_str(i)?;
-    IResult::Ok((i, (key, value)))
-}
-
-fn get_a_u32<'b>(i: &'b [u8]) -> nom::IResult<&'b [u8], u32> {
-    use nom::branch::alt;
-    alt((get_soh_u32, get_normal_u32))(i)
+    Ok((i, (key, value)))
 }
 
-fn get_normal_u32<'b>(i: &'b [u8]) -> nom::IResult<&'b [u8], u32> {
-    let (i, app_bytes) = take(4usize)(i)?;
+fn get_a_u32(i: &[u8]) -> PResult<'_, u32> {
+    let (i, app_bytes) = take(4, i)?;
     let app_id_bytes_slized: [u8; 4] = [app_bytes[0], app_bytes[1], app_bytes[2], app_bytes[3]];
     let app_id = u32::from_le_bytes(app_id_bytes_slized);
-    IResult::Ok((i, app_id))
-}
-
-fn get_soh_u32<'b>(i: &'b [u8]) -> nom::IResult<&'b [u8], u32> {
-    let soh = ascii::AsciiChar::SOH.as_byte();
-    let (i, _) = tag([soh])(i)?;
-    let (i, app_id_bytes) = take(3usize)(i)?;
-    let app_id_bytes_slized: [u8; 4] = [0x00, app_id_bytes[0], app_id_bytes[1], app_id_bytes[2]];
-    let app_id = u32::from_le_bytes(app_id_bytes_slized);
-    IResult::Ok((i, app_id))
+    Ok((i, app_id))
 }
 
-fn get_null_terminated_str<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], &'a str> {
-    let null = ascii::AsciiChar::Null.as_byte();
-    let (i, str_bytes) = take_till(|cond| cond == null)(i)?;
-
-    //TODO Remove this unwrap
-    let str_res = std::str::from_utf8(str_bytes).unwrap();
-    let (i, _null) = tag([null])(i)?;
-    IResult::Ok((i, str_res))
+fn get_null_terminated_str(i: &[u8]) -> PResult<'_, &str> {
+    let (rest, str_bytes) = take_through(NULL, i)?;
+    match core::str::from_utf8(str_bytes) {
+        Ok(str_res) => Ok((rest, str_res)),
+        Err(_) => fail(i),
+    }
 }
 
-fn get_order<'a>(i: &'a [u8]) -> nom::IResult<&'a [u8], &'a str> {
-    let null = ascii::AsciiChar::Null.as_byte();
-    let (i, _) = tag([null])(i)?;
+pub(crate) fn get_order(i: &[u8]) -> PResult<'_, &str> {
+    let (i, _) = tag(&[NULL], i)?;
     let (i, order_string) = get_null_terminated_str(i)?;
-    IResult::Ok((i, order_string))
+    Ok((i, order_string))
 }
 
-fn get_tags(i: &[u8]) -> nom::IResult<&[u8], Vec<&str>> {
-    use nom::sequence::tuple;
-
-    let null = ascii::AsciiChar::Null.as_byte();
-    let bs = ascii::AsciiChar::BackSpace.as_byte();
-    let (i, (_, _, _, tags_bytes, _)) = tuple((
-        tag([null]),
-        tag("tags"),
-        tag([null]),
-        take_till(|c| c == bs),
-        tag([bs]),
-    ))(i)?;
-    let (_, tags) = many0(take_tag)(tags_bytes)?;
-    IResult::Ok((i, tags))
+fn is_number(order: &str) -> bool {
+    !order.is_empty() && order.bytes().all(|b| b.is_ascii_digit())
 }
 
-fn take_tag<'b>(i: &[u8]) -> nom::IResult<&[u8], &str> {
-    let soh = ascii::AsciiChar::SOH.as_byte();
+/// Parse the tags, failing as soon as there is one too many or one is over the cap.
+fn get_tags<'a>(i: &'a [u8], caps: &mut Caps) -> PResult<'a, Tags<'a>> {
+    let (i, _) = tag(b"\0tags\0", i)?;
+    let (i, tags_bytes) = take_through(BS, i)?;
+    let mut tags = Tags::new();
+    let mut tags_bytes = tags_bytes;
+    while let Ok((rest, tag_name)) = take_tag(tags_bytes) {
+        if !caps.another_tag(tags.len()) || !caps.text(tag_name) {
+            return fail(i);
+        }
+        tags.push(tag_name);
+        tags_bytes = rest;
+    }
+    Ok((i, tags))
+}
 
-    let (i, _) = tag([soh])(i)?;
+pub(crate) fn take_tag(i: &[u8]) -> PResult<'_, &str> {
+    let (i, _) = tag(&[SOH], i)?;
     let (i, _) = get_null_terminated_str(i)?;
     let (i, tag_name) = get_null_terminated_str(i)?;
-    IResult::Ok((i, tag_name))
+    Ok((i, tag_name))
 }
 
-fn shotcut_content(i: &[u8]) -> nom::IResult<&[u8], ()> {
-    use nom::character::complete::char;
-    use nom::sequence::tuple;
-    let null = ascii::AsciiChar::Null.as_char();
-    let mut start_sequence = tuple((char(null), tag("shortcuts"), char(null)));
-    let (x, _y) = start_sequence(i)?;
-    IResult::Ok((x, ()))
+pub(crate) fn shotcut_content(i: &[u8]) -> PResult<'_, ()> {
+    tag(b"\0shortcuts\0", i)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::get_first)]
 mod tests {
 
     use super::*;
@@ -422,6 +853,215 @@ mod tests {
         assert_eq!(4, shortcuts.len());
     }
 
+    #[test]
+    fn trusted_parses_the_same_shortcuts() {
+        for path in [
+            "src/testdata/shortcuts.vdf",
+            "src/testdata/shortcuts2.vdf",
+            "src/testdata/shortcutsfirefox.vdf",
+            "src/testdata/shortcutsspore.vdf",
+            "src/testdata/steam_rom_manager.vdf",
+        ] {
+            let content = std::fs::read(path).unwrap();
+            let trusted = unsafe { parse_shortcuts_trusted(&content) }.unwrap();
+            assert_eq!(parse_shortcuts(&content).unwrap(), trusted, "{}", path);
+        }
+        assert!(unsafe { parse_shortcuts_trusted(b"\x00short") }.is_err());
+    }
+
+    #[test]
+    fn duplicate_key_policies() {
+        let mut data = b"\x00shortcuts\x00\x000\x00".to_vec();
+        data.extend_from_slice(b"\x01AppName\x00Celeste\x00\x01appname\x00Hades\x00");
+        data.extend_from_slice(b"\x00tags\x00\x08\x08\x08");
+        let with = |duplicate_keys| {
+            let options = ParseOptions {
+                duplicate_keys,
+                ..ParseOptions::default()
+            };
+            parse_shortcuts_with(&data, &options)
+        };
+
+        let last = with(DuplicateKeyPolicy::Last).unwrap();
+        assert_eq!("Hades", last.shortcuts[0].app_name);
+        assert_eq!(
+            vec![Diagnostic::DuplicateKey {
+                shortcut: 0,
+                key: "appname"
+            }],
+            last.diagnostics
+        );
+        let first = with(DuplicateKeyPolicy::First).unwrap();
+        assert_eq!("Celeste", first.shortcuts[0].app_name);
+        assert!(with(DuplicateKeyPolicy::Error).is_err());
+
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let parsed = parse_shortcuts_with(&content, &ParseOptions::default()).unwrap();
+        assert_eq!(parse_shortcuts(&content).unwrap(), parsed.shortcuts);
+        assert!(parsed.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn duplicate_key_after_other_diagnostics() {
+        let mut data = b"\x00shortcuts\x00\x00first\x00\x00tags\x00\x08\x08\x001\x00".to_vec();
+        data.extend_from_slice(b"\x01AppName\x00Celeste\x00\x01appname\x00Hades\x00");
+        data.extend_from_slice(b"\x00tags\x00\x08\x08\x08");
+        let parsed = parse_shortcuts_with(&data, &ParseOptions::default()).unwrap();
+        assert_eq!(2, parsed.diagnostics.len());
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeyPolicy::Error,
+            ..ParseOptions::default()
+        };
+        let err = parse_shortcuts_with(&data, &options).unwrap_err();
+        assert_eq!("Duplicate key appname in shortcut 1", err);
+    }
+
+    #[test]
+    fn invalid_orders() {
+        let mut data = b"\x00shortcuts\x00".to_vec();
+        for order in ["first", ""] {
+            data.extend_from_slice(b"\x00");
+            data.extend_from_slice(order.as_bytes());
+            data.extend_from_slice(b"\x00\x01AppName\x00Celeste\x00\x00tags\x00\x08\x08");
+        }
+        data.push(b'\x08');
+        let parsed = parse_shortcuts_with(&data, &ParseOptions::default()).unwrap();
+        assert_eq!(2, parsed.shortcuts.len());
+        assert_eq!(
+            vec![
+                Diagnostic::InvalidOrder {
+                    shortcut: 0,
+                    order: "first"
+                },
+                Diagnostic::InvalidOrder {
+                    shortcut: 1,
+                    order: ""
+                }
+            ],
+            parsed.diagnostics
+        );
+        let options = ParseOptions {
+            invalid_order: InvalidOrderPolicy::Error,
+            ..ParseOptions::default()
+        };
+        assert!(parse_shortcuts_with(&data, &options).is_err());
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let mut content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let options = ParseOptions::default();
+        assert!(parse_shortcuts_with(&content, &options)
+            .unwrap()
+            .diagnostics
+            .is_empty());
+
+        let end = content.len();
+        content.extend_from_slice(b"junk");
+        let parsed = parse_shortcuts_with(&content, &ParseOptions::default()).unwrap();
+        assert_eq!(parse_shortcuts(&content).unwrap(), parsed.shortcuts);
+        assert_eq!(
+            vec![Diagnostic::TrailingBytes {
+                offset: end,
+                len: 4
+            }],
+            parsed.diagnostics
+        );
+        let options = ParseOptions {
+            trailing_bytes: TrailingBytesPolicy::Error,
+            ..ParseOptions::default()
+        };
+        assert!(parse_shortcuts_with(&content, &options).is_err());
+    }
+
+    #[test]
+    fn limits() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let with = |limits| {
+            let options = ParseOptions {
+                limits,
+                ..ParseOptions::default()
+            };
+            parse_shortcuts_with(&content, &options)
+        };
+        let shortcuts = parse_shortcuts(&content).unwrap();
+        let most_tags = shortcuts.iter().map(|s| s.tags.len()).max().unwrap();
+        let fits = Limits {
+            max_bytes: content.len(),
+            max_shortcuts: shortcuts.len(),
+            max_tags: most_tags,
+            max_text_len: 1000,
+        };
+        assert_eq!(shortcuts, with(fits).unwrap().shortcuts);
+
+        let caps = [
+            Limits {
+                max_bytes: content.len() - 1,
+                ..fits
+            },
+            Limits {
+                max_shortcuts: shortcuts.len() - 1,
+                ..fits
+            },
+            Limits {
+                max_tags: most_tags - 1,
+                ..fits
+            },
+            Limits {
+                max_text_len: 5,
+                ..fits
+            },
+        ];
+        for limits in caps {
+            assert!(with(limits).is_err(), "{:?}", limits);
+        }
+    }
+
+    #[test]
+    fn tags_over_the_limit_fail_while_they_are_read() {
+        // The shortcut ends after its tags, so it is never built
+        let mut data = b"\x00shortcuts\x00\x000\x00\x01AppName\x00Celeste\x00\x00tags\x00".to_vec();
+        for tag in 0..3 {
+            data.extend_from_slice(format!("\x01{}\x00tag\x00", tag).as_bytes());
+        }
+        data.push(BS);
+        assert!(parse_shortcuts_with(&data, &ParseOptions::default())
+            .unwrap()
+            .shortcuts
+            .is_empty());
+
+        let options = ParseOptions {
+            limits: Limits {
+                max_tags: 2,
+                ..Limits::default()
+            },
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Err("Shortcut 0 has more than 2 tags".to_string()),
+            parse_shortcuts_with(&data, &options)
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_not_parsed() {
+        let data = b"\x00shortcuts\x00\x000\x00\x01AppName\x00\xC3\x28\x00\x00tags\x00\x08\x08\x08";
+        assert_eq!(Ok(vec![]), parse_shortcuts(data));
+    }
+
+    #[test]
+    fn keys_after_tags_fill_empty_values() {
+        let mut data = b"\x00shortcuts\x00\x000\x00".to_vec();
+        data.extend_from_slice(b"\x01AppName\x00\x00\x02LastPlayTime\x00\x07\x00\x00\x00");
+        data.extend_from_slice(b"\x00tags\x00\x08");
+        data.extend_from_slice(b"\x01AppName\x00Celeste\x00\x02LastPlayTime\x00\x00\x00\x00\x00");
+        data.extend_from_slice(b"\x02openvr\x00\x02\x00\x00\x00\x08\x08\x08");
+        let shortcuts = parse_shortcuts(&data).unwrap();
+        assert_eq!("Celeste", shortcuts[0].app_name);
+        assert_eq!(7, shortcuts[0].last_play_time);
+        assert_eq!(2, shortcuts[0].open_vr);
+    }
+
     #[test]
     fn get_exe_name_test() {
         const DATA: [u8; 44] = [
@@ -463,7 +1103,22 @@ mod tests {
 
         let i = DATA;
         let (_r, id) = parse_a_line(&i).unwrap();
-        assert_eq!(false, id.num_value() != 0);
+        // Read as the 3 byte number 0 before 4 byte numbers, see CHANGELOG.md
+        assert_eq!(1, id.num_value());
+    }
+
+    #[test]
+    fn numbers_are_4_little_endian_bytes() {
+        let line = |value: [u8; 4]| {
+            let mut bytes = b"\x02AllowOverlay\0".to_vec();
+            bytes.extend_from_slice(&value);
+            parse_a_line(&bytes).unwrap().1.num_value()
+        };
+        assert_eq!(0, line([0x00, 0x00, 0x00, 0x00]));
+        assert_eq!(1, line([0x01, 0x00, 0x00, 0x00]));
+        // What earlier releases wrote for true
+        assert_eq!(0x0100_0001, line([0x01, 0x00, 0x00, 0x01]));
+        assert_eq!(0x0403_0201, line([0x01, 0x02, 0x03, 0x04]));
     }
 
     #[test]
@@ -477,7 +1132,13 @@ mod tests {
         ];
 
         let i = DATA;
-        let res = get_tags(&i);
+        let limits = Limits::default();
+        let mut caps = Caps {
+            index: 0,
+            limits: &limits,
+            exceeded: &mut None,
+        };
+        let res = get_tags(&i, &mut caps);
         let res_unwrapped = res.unwrap();
         assert_eq!(
             vec!["favorite", "Installed", "Ready TO Play"],
@@ -0,0 +1,216 @@
+//! Conversions between shortcuts and the text KeyValues format of [text_vdf].
+//!
+//! A shortcut becomes a section with the keys steam uses in shortcuts.vdf, numbers and
+//! booleans as decimal text, and the tags as a nested section indexed from `"0"`:
+//!
+//! ```text
+//! "shortcuts"
+//! {
+//!     "0"
+//!     {
+//!         "appid"     "2365067149"
+//!         "AppName"   "Celeste"
+//!         "Exe"       "\"C:\\Games\\Celeste.exe\""
+//!         ...
+//!         "tags"
+//!         {
+//!             "0"     "Installed"
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! This lets tools that already work with text VDF trees (or convert them from other
+//! KeyValues crates) read and write shortcuts without mapping the fields by hand.
+
+use std::convert::TryFrom;
+
+use crate::shortcut::{Shortcut, ShortcutOwned, Tags};
+use crate::text_vdf::{Section, Value};
+
+impl From<&Shortcut<'_>> for Section {
+    fn from(shortcut: &Shortcut<'_>) -> Self {
+        let mut section = Section::new();
+        section.set_text("appid", &shortcut.app_id.to_string());
+        section.set_text("AppName", shortcut.app_name);
+        section.set_text("Exe", shortcut.exe);
+        section.set_text("StartDir", shortcut.start_dir);
+        section.set_text("icon", shortcut.icon);
+        section.set_text("ShortcutPath", shortcut.shortcut_path);
+        section.set_text("LaunchOptions", shortcut.launch_options);
+        section.set_text("IsHidden", bool_text(shortcut.is_hidden));
+        section.set_text(
+            "AllowDesktopConfig",
+            bool_text(shortcut.allow_desktop_config),
+        );
+        section.set_text("AllowOverlay", bool_text(shortcut.allow_overlay));
+        section.set_text("openvr", &shortcut.open_vr.to_string());
+        section.set_text("Devkit", &shortcut.dev_kit.to_string());
+        section.set_text("DevkitGameID", shortcut.dev_kit_game_id);
+        section.set_text(
+            "DevkitOverrideAppID",
+            &shortcut.dev_kit_overrite_app_id.to_string(),
+        );
+        section.set_text("LastPlayTime", &shortcut.last_play_time.to_string());
+        section.set_text("FlatpakAppID", shortcut.flatpak_app_id);
+        let mut tags = Section::new();
+        for (index, tag) in shortcut.tags.iter().enumerate() {
+            tags.set_text(&index.to_string(), tag);
+        }
+        section.set_section("tags", tags);
+        section
+    }
+}
+
+impl From<&ShortcutOwned> for Section {
+    fn from(shortcut: &ShortcutOwned) -> Self {
+        Section::from(&shortcut.borrow())
+    }
+}
+
+/// Read a shortcut from a section, the order is left empty.
+///
+/// Keys are matched case insensitively and missing keys get their default value.
+/// Fails if a number or boolean is not valid, or `tags` is not a section.
+impl TryFrom<&Section> for ShortcutOwned {
+    type Error = String;
+
+    fn try_from(section: &Section) -> Result<Self, Self::Error> {
+        let text = |key: &str| section.get_text(key).unwrap_or_default();
+        let number = |key: &str| -> Result<u32, String> {
+            match section.get_text(key) {
+                Some(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number for {}", value, key)),
+                None => Ok(0),
+            }
+        };
+        let boolean = |key: &str, default: bool| -> Result<bool, String> {
+            match section.get_text(key) {
+                Some(value) => match value.trim() {
+                    "1" => Ok(true),
+                    "0" => Ok(false),
+                    _ => Err(format!("'{}' is not 0 or 1 for {}", value, key)),
+                },
+                None => Ok(default),
+            }
+        };
+        let tags = match section.get("tags") {
+            Some(Value::Section(tags)) => tags
+                .entries
+                .iter()
+                .filter_map(|(_, tag)| tag.as_text())
+                .collect(),
+            Some(Value::Text(_)) => return Err("tags is not a section".to_string()),
+            None => Tags::new(),
+        };
+
+        let mut shortcut = Shortcut::new(
+            "",
+            text("AppName"),
+            text("Exe"),
+            text("StartDir"),
+            text("icon"),
+            text("ShortcutPath"),
+            text("LaunchOptions"),
+        );
+        if section.get_text("appid").is_some() {
+            shortcut.app_id = number("appid")?;
+        }
+        shortcut.is_hidden = boolean("IsHidden", false)?;
+        shortcut.allow_desktop_config = boolean("AllowDesktopConfig", true)?;
+        shortcut.allow_overlay = boolean("AllowOverlay", true)?;
+        shortcut.open_vr = number("openvr")?;
+        shortcut.dev_kit = number("Devkit")?;
+        shortcut.dev_kit_game_id = text("DevkitGameID");
+        shortcut.dev_kit_overrite_app_id = number("DevkitOverrideAppID")?;
+        shortcut.last_play_time = number("LastPlayTime")?;
+        shortcut.flatpak_app_id = text("FlatpakAppID");
+        shortcut.tags = tags;
+        Ok(shortcut.to_owned())
+    }
+}
+
+/// Convert shortcuts to a `shortcuts` section, like the root of shortcuts.vdf.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_text_vdf::{shortcuts_from_section, shortcuts_to_section};
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+/// let root = shortcuts_to_section(&[shortcut]);
+/// let text = root.to_text();
+/// assert!(text.contains("\"AppName\"\t\t\"Celeste\""));
+///
+/// let shortcuts = shortcuts_from_section(&root).unwrap();
+/// assert_eq!("Celeste", shortcuts[0].app_name);
+/// ```
+pub fn shortcuts_to_section(shortcuts: &[Shortcut]) -> Section {
+    let mut list = Section::new();
+    for (index, shortcut) in shortcuts.iter().enumerate() {
+        list.set_section(&index.to_string(), Section::from(shortcut));
+    }
+    let mut root = Section::new();
+    root.set_section("shortcuts", list);
+    root
+}
+
+/// Read shortcuts from a section made by [shortcuts_to_section].
+///
+/// The key of each shortcut in the `shortcuts` section becomes its order.
+pub fn shortcuts_from_section(root: &Section) -> Result<Vec<ShortcutOwned>, String> {
+    let list = root
+        .get_section("shortcuts")
+        .ok_or_else(|| "no shortcuts section".to_string())?;
+    list.entries
+        .iter()
+        .map(|(order, value)| {
+            let section = value
+                .as_section()
+                .ok_or_else(|| format!("shortcut {} is not a section", order))?;
+            let mut shortcut = ShortcutOwned::try_from(section)?;
+            shortcut.order = order.clone();
+            Ok(shortcut)
+        })
+        .collect()
+}
+
+fn bool_text(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::text_vdf;
+
+    #[test]
+    fn shortcuts_survive_text_vdf() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(&content).unwrap();
+        let text = shortcuts_to_section(&shortcuts).to_text();
+        let read = shortcuts_from_section(&text_vdf::parse(&text).unwrap()).unwrap();
+        assert_eq!(shortcuts.len(), read.len());
+        for (shortcut, read) in shortcuts.iter().zip(read.iter()) {
+            assert_eq!(&shortcut.to_owned(), read);
+        }
+    }
+
+    #[test]
+    fn invalid_values() {
+        let mut section = Section::new();
+        section.set_text("AppName", "Celeste");
+        section.set_text("IsHidden", "yes");
+        assert!(ShortcutOwned::try_from(&section).is_err());
+        section.set_text("IsHidden", "1");
+        section.set_text("tags", "Installed");
+        assert!(ShortcutOwned::try_from(&section).is_err());
+    }
+}
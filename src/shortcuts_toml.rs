@@ -0,0 +1,102 @@
+//! Exporting and importing shortcuts as TOML (requires the `toml` feature).
+//!
+//! This is meant for declarative setups, where the shortcuts are kept in a file next to
+//! other dotfiles and written to shortcuts.vdf from there.
+//!
+//! ## Schema (version 1)
+//!
+//! ```toml
+//! version = 1
+//!
+//! [[shortcuts]]
+//! AppName = "Celeste"
+//! Exe = '"/home/deck/Games/Celeste/Celeste"'
+//! StartDir = '"/home/deck/Games/Celeste/"'
+//! LaunchOptions = "gamemoderun %command%"
+//! AllowOverlay = true
+//! tags = ["Platformers"]
+//! ```
+//!
+//! This is the schema of [shortcuts_json](crate::shortcuts_json), with the same key names and
+//! the same defaults for the keys that are left out.
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+use crate::shortcut_list::{List, ListRef, Version};
+
+pub use crate::shortcut_list::SCHEMA_VERSION;
+
+/// Export shortcuts to TOML, in the schema described in the [module docs](self).
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_toml::{from_toml, to_toml};
+/// use steam_shortcuts_util::Shortcut;
+///
+/// let shortcuts = vec![Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "")];
+/// let toml = to_toml(&shortcuts);
+/// let imported = from_toml(&toml).unwrap();
+/// assert_eq!("Celeste", imported[0].app_name);
+/// ```
+pub fn to_toml(shortcuts: &[Shortcut]) -> String {
+    toml::to_string(&ListRef::new(shortcuts)).unwrap_or_default()
+}
+
+/// Import shortcuts from TOML in the schema described in the [module docs](self).
+///
+/// Returns an error if the TOML is invalid, is from a newer schema version,
+/// or a field has the wrong type.
+pub fn from_toml(toml: &str) -> Result<Vec<ShortcutOwned>, String> {
+    let version: Version = toml::from_str(toml).map_err(|e| e.to_string())?;
+    version.check()?;
+    let list: List = toml::from_str(toml).map_err(|e| e.to_string())?;
+    Ok(list.into_shortcuts())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip_file() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = crate::parse_shortcuts(content.as_slice()).unwrap();
+        let imported = from_toml(&to_toml(&shortcuts)).unwrap();
+        assert_eq!(shortcuts.len(), imported.len());
+        for (original, imported) in shortcuts.iter().zip(&imported) {
+            assert!(original.semantically_eq(&imported.borrow()));
+        }
+    }
+
+    #[test]
+    fn declarative_file() {
+        let toml = r#"
+version = 1
+
+[[shortcuts]]
+AppName = "Celeste"
+Exe = '"/home/deck/Games/Celeste/Celeste"'
+AllowOverlay = true
+tags = ["Platformers"]
+
+[[shortcuts]]
+AppName = "RetroArch"
+Exe = "retroarch"
+"#;
+        let imported = from_toml(toml).unwrap();
+        assert_eq!(2, imported.len());
+        assert_eq!("\"/home/deck/Games/Celeste/Celeste\"", imported[0].exe);
+        assert_eq!(["Platformers"], imported[0].tags[..]);
+        assert!(imported[0].allow_overlay);
+        assert!(imported[0].borrow().app_id_matches_calculation());
+        assert_eq!("1", imported[1].order);
+        assert!(!imported[1].allow_overlay);
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        assert!(from_toml("version = 2").is_err());
+        assert!(from_toml("[[shortcuts]]\nExe = \"a\"").is_err());
+        assert!(from_toml("version = 1\n[[shortcuts]]\nAppName = \"a\"\nopenvr = -1").is_err());
+    }
+}
@@ -1,6 +1,8 @@
-use crate::shortcut::Shortcut;
+use alloc::vec::Vec;
 
-use ascii::AsciiChar::*;
+use crate::shortcut::{KeySpelling, Shortcut};
+
+use crate::bytes::{BS, NULL, SOH, STX};
 
 /// Serializes shortcuts to bytes, in a format that Steam will accept.
 ///
@@ -20,148 +22,436 @@ use ascii::AsciiChar::*;
 /// }
 /// ```
 pub fn shortcuts_to_bytes(shortcut: &Vec<Shortcut>) -> Vec<u8> {
-    let null = Null.as_byte();
+    write_shortcuts(shortcut, &[], &WriteOptions::default())
+}
+
+/// Serializes shortcuts to the end of `buf`, like [shortcuts_to_bytes].
+///
+/// The bytes are appended to what is already in the buffer, so clear it to reuse it for
+/// the next file without allocating again.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{write_shortcuts_into, Shortcut};
+///
+/// let mut buf = Vec::new();
+/// for name in ["Celeste", "Hades"] {
+///     buf.clear();
+///     let shortcut = Shortcut::new("0", name, "game.exe", "", "", "", "");
+///     write_shortcuts_into(&mut buf, &[shortcut]);
+///     // Write buf to the shortcuts.vdf of a user
+/// }
+/// ```
+pub fn write_shortcuts_into(buf: &mut Vec<u8>, shortcuts: &[Shortcut]) {
+    append_shortcuts(buf, shortcuts, &[], &WriteOptions::default());
+}
+
+/// The number of bytes [shortcuts_to_bytes] writes for these shortcuts, without writing them.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{shortcuts_len, shortcuts_to_bytes, Shortcut};
+///
+/// let shortcuts = vec![Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "")];
+/// assert_eq!(shortcuts_to_bytes(&shortcuts).len(), shortcuts_len(&shortcuts));
+/// ```
+pub fn shortcuts_len(shortcuts: &[Shortcut]) -> usize {
+    let mut len = ByteCount(0);
+    write_file(&mut len, shortcuts, &[], &WriteOptions::default());
+    len.0
+}
+
+/// The number of bytes of the entry of a shortcut, written with its order as the index.
+pub(crate) fn shortcut_len(shortcut: &Shortcut) -> usize {
+    let mut len = ByteCount(0);
+    write_shortcut(
+        &mut len,
+        Key::Name(shortcut.order),
+        shortcut,
+        &KeySpelling::default(),
+        &WriteOptions::default(),
+    );
+    len.0
+}
+
+/// The generation of steam client a shortcuts.vdf file is written for.
+///
+/// Steam clients have changed which keys they write to shortcuts.vdf, and how they are cased.
+/// Older clients can get confused by keys they do not know, so write files with the profile
+/// of the client that will read them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientProfile {
+    /// Clients from before 2022, without the DevkitOverrideAppID and FlatpakAppID keys.
+    Legacy,
+    /// Desktop clients from 2023, which write `appname` and DevkitOverrideAppID, but no FlatpakAppID.
+    Desktop2023,
+    /// Current Steam Deck and desktop clients, which also write the FlatpakAppID key.
+    SteamDeck,
+}
+
+impl ClientProfile {
+    fn key_set(self) -> &'static KeySet {
+        match self {
+            ClientProfile::Legacy => &KeySet::LEGACY,
+            ClientProfile::Desktop2023 => &KeySet::DESKTOP_2023,
+            ClientProfile::SteamDeck => &KeySet::STEAM_DECK,
+        }
+    }
+}
+
+/// Serializes shortcuts to bytes, with the keys that the given steam client writes.
+///
+/// Fields the client does not know about are left out.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{parse_shortcuts, shortcuts_to_bytes_for, ClientProfile};
+///
+/// let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+/// let shortcuts = parse_shortcuts(content.as_slice()).unwrap();
+/// let bytes = shortcuts_to_bytes_for(&shortcuts, ClientProfile::Legacy);
+/// assert_eq!(content, bytes);
+/// ```
+pub fn shortcuts_to_bytes_for(shortcuts: &[Shortcut], profile: ClientProfile) -> Vec<u8> {
+    let options = WriteOptions {
+        profile: Some(profile),
+        ..WriteOptions::default()
+    };
+    write_shortcuts(shortcuts, &[], &options)
+}
+
+/// Options for how [shortcuts_to_bytes_with] writes the keys of shortcuts.
+///
+/// The default writes the same bytes as [shortcuts_to_bytes].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// The client to write the keys for, `None` writes all keys
+    pub profile: Option<ClientProfile>,
+    /// Write all keys in lowercase (`appname`, `exe`, `allowoverlay`, ...) like current clients do,
+    /// so the file can be diffed against one written by steam
+    pub lowercase_keys: bool,
+}
+
+impl WriteOptions {
+    fn key_set(&self) -> &'static KeySet {
+        self.profile
+            .map(ClientProfile::key_set)
+            .unwrap_or(&KeySet::DEFAULT)
+    }
+
+    fn key<'k>(&self, spelling: &'k KeySpelling, name: &'k str) -> Key<'k> {
+        if self.lowercase_keys {
+            return Key::Lowercase(name);
+        }
+        Key::Name(spelling.get(name).unwrap_or(name))
+    }
 
-    let bs = BackSpace.as_byte();
-    let mut result = vec![];
+    /// Should an optional key be written, leaving out empty keys the parsed file did not have.
+    fn writes_optional(&self, spelling: &KeySpelling, name: &str, is_empty: bool) -> bool {
+        !is_empty || spelling.is_empty() || spelling.get(name).is_some()
+    }
+}
 
-    result.push(null);
-    result.extend_from_slice("shortcuts".as_bytes());
-    result.push(null);
+/// Serializes shortcuts to bytes, with the given options for the keys.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::{shortcuts_to_bytes_with, Shortcut, WriteOptions};
+///
+/// let shortcut = Shortcut::new("0", "Celeste", "celeste.exe", "", "", "", "");
+/// let options = WriteOptions {
+///     lowercase_keys: true,
+///     ..WriteOptions::default()
+/// };
+/// let bytes = shortcuts_to_bytes_with(&[shortcut], &options);
+/// assert!(bytes.windows(8).any(|key| key == b"\x01appname"));
+/// ```
+pub fn shortcuts_to_bytes_with(shortcuts: &[Shortcut], options: &WriteOptions) -> Vec<u8> {
+    write_shortcuts(shortcuts, &[], options)
+}
 
-    let mut shortcut_bytes: Vec<u8> = shortcut
-        .iter()
-        .enumerate()
-        .flat_map(|(index, shortcut)| shortcut_to_bytes(index, shortcut))
-        .collect();
+/// Serializes shortcuts to bytes, with the key spelling of the file they were parsed from.
+///
+/// The spellings are the [key_spellings](crate::shortcuts_parser::Parsed::key_spellings) of
+/// [parse_shortcuts_with](crate::shortcuts_parser::parse_shortcuts_with), the shortcut at an
+/// index is written with the spelling at that index. Empty optional keys the file did not have
+/// are left out, so a file written by steam is written back byte for byte. Shortcuts without a
+/// spelling are written as [shortcuts_to_bytes_with] writes them.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::shortcuts_parser::{parse_shortcuts_with, ParseOptions};
+/// use steam_shortcuts_util::{shortcuts_to_bytes_with_spelling, WriteOptions};
+///
+/// let content = std::fs::read("src/testdata/shortcutsspore.vdf").unwrap();
+/// let parsed = parse_shortcuts_with(&content, &ParseOptions::default()).unwrap();
+/// let bytes = shortcuts_to_bytes_with_spelling(
+///     &parsed.shortcuts,
+///     &parsed.key_spellings,
+///     &WriteOptions::default(),
+/// );
+/// assert_eq!(content, bytes);
+/// ```
+pub fn shortcuts_to_bytes_with_spelling(
+    shortcuts: &[Shortcut],
+    key_spellings: &[KeySpelling],
+    options: &WriteOptions,
+) -> Vec<u8> {
+    write_shortcuts(shortcuts, key_spellings, options)
+}
 
-    result.append(&mut shortcut_bytes);
+/// The spelling of the keys that differ between steam clients, and which optional keys are written.
+struct KeySet {
+    app_name: &'static str,
+    dev_kit_override_app_id: bool,
+    flatpak_app_id: bool,
+}
 
-    result.push(bs);
-    result.push(bs);
+impl KeySet {
+    /// The keys [shortcuts_to_bytes] has always written.
+    const DEFAULT: KeySet = KeySet {
+        app_name: "AppName",
+        dev_kit_override_app_id: true,
+        flatpak_app_id: true,
+    };
+    const LEGACY: KeySet = KeySet {
+        app_name: "AppName",
+        dev_kit_override_app_id: false,
+        flatpak_app_id: false,
+    };
+    const DESKTOP_2023: KeySet = KeySet {
+        app_name: "appname",
+        dev_kit_override_app_id: true,
+        flatpak_app_id: false,
+    };
+    const STEAM_DECK: KeySet = KeySet {
+        app_name: "appname",
+        dev_kit_override_app_id: true,
+        flatpak_app_id: true,
+    };
+}
 
+fn write_shortcuts(
+    shortcuts: &[Shortcut],
+    spellings: &[KeySpelling],
+    options: &WriteOptions,
+) -> Vec<u8> {
+    let mut result = Vec::new();
+    append_shortcuts(&mut result, shortcuts, spellings, options);
     result
 }
 
-fn shortcut_to_bytes(order: usize, shortcut: &Shortcut) -> Vec<u8> {
-    let null = Null.as_byte();
-    let bs = BackSpace.as_byte();
-
-    let mut res = vec![];
-
-    res.push(null);
-
-    let order_string = format!("{}", order);
-    let order = order_string.as_bytes();
-    res.extend_from_slice(order);
-    res.push(null);
-
-    res.append(&mut stx_to_bytes("appid", shortcut.app_id));
-    res.append(&mut soh_to_bytes("AppName", shortcut.app_name));
-    res.append(&mut soh_to_bytes("Exe", shortcut.exe));
-    res.append(&mut soh_to_bytes("StartDir", shortcut.start_dir));
-    res.append(&mut soh_to_bytes("icon", shortcut.icon));
-    res.append(&mut soh_to_bytes("ShortcutPath", shortcut.shortcut_path));
-    res.append(&mut soh_to_bytes("LaunchOptions", shortcut.launch_options));
-    res.append(&mut stx_to_bytes("IsHidden", shortcut.is_hidden as u32));
-    res.append(&mut stx_single_to_bytes(
-        "AllowDesktopConfig",
-        shortcut.allow_desktop_config,
-    ));
-    res.append(&mut stx_single_to_bytes(
-        "AllowOverlay",
-        shortcut.allow_overlay,
-    ));
-    res.append(&mut stx_to_bytes("openvr", shortcut.open_vr));
-    res.append(&mut stx_to_bytes("Devkit", shortcut.dev_kit));
-    res.append(&mut soh_to_bytes("DevkitGameID", shortcut.dev_kit_game_id));
-    res.append(&mut stx_to_bytes(
-        "DevkitOverrideAppID",
-        shortcut.dev_kit_overrite_app_id,
-    ));
-
-    res.append(&mut stx_to_bytes("LastPlayTime", shortcut.last_play_time));
-
-    res.push(null);
-    res.extend_from_slice("tags".as_bytes());
-    res.push(null);
-
-    res.append(&mut tags_to_bytes(&shortcut.tags));
-
-    res.push(bs);
-    res.push(bs);
-
-    res
-}
-
-fn tags_to_bytes(input: &Vec<&str>) -> Vec<u8> {
-    input
-        .iter()
-        .enumerate()
-        .flat_map(|(index, tag)| tag_to_bytes(index, tag))
-        .collect()
-}
-
-fn tag_to_bytes(index: usize, tag: &str) -> Vec<u8> {
-    let soh = SOH.as_byte();
-    let null = Null.as_byte();
-
-    let mut res = vec![];
-    res.push(soh);
-    let order_string = format!("{}", index);
-    let order = order_string.as_bytes();
-    res.extend_from_slice(order);
-    res.push(null);
-    res.extend_from_slice(tag.as_bytes());
-    res.push(null);
-    res
-}
-
-fn soh_to_bytes(name: &str, input: &str) -> Vec<u8> {
-    let mut res = vec![];
-    let soh = SOH.as_byte();
-    let null = Null.as_byte();
-    res.push(soh);
-
-    res.extend_from_slice(name.as_bytes());
-
-    res.push(null);
-    res.extend_from_slice(&input.as_bytes());
-    res.push(null);
-    res
-}
-
-fn stx_single_to_bytes(name: &str, input: bool) -> Vec<u8> {
-    let mut res = vec![];
-    let soh = SOH.as_byte();
-    let stx = SOX.as_byte();
-    let null = Null.as_byte();
-    res.push(stx);
-
-    res.extend_from_slice(name.as_bytes());
-    res.push(null);
-    res.push(soh);
-    res.push(null);
-    res.push(null);
-    res.push(input as u8);
-    res
-}
-
-fn stx_to_bytes(name: &str, input: u32) -> Vec<u8> {
-    let mut res = vec![];
-    let stx = SOX.as_byte();
-    let null = Null.as_byte();
-    res.push(stx);
-
-    res.extend_from_slice(name.as_bytes());
-
-    res.push(null);
-    res.extend_from_slice(&input.to_le_bytes());
-    res
+fn append_shortcuts(
+    buf: &mut Vec<u8>,
+    shortcuts: &[Shortcut],
+    spellings: &[KeySpelling],
+    options: &WriteOptions,
+) {
+    let mut len = ByteCount(0);
+    write_file(&mut len, shortcuts, spellings, options);
+    buf.reserve(len.0);
+    write_file(buf, shortcuts, spellings, options);
+}
+
+/// Where the writer puts its bytes.
+///
+/// Writing to a [ByteCount] first gives the exact length of the output,
+/// so the buffer is allocated once instead of growing while it is written.
+trait Output {
+    fn put(&mut self, bytes: &[u8]);
+
+    fn put_byte(&mut self, byte: u8) {
+        self.put(&[byte]);
+    }
 }
+
+impl Output for Vec<u8> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    fn put_byte(&mut self, byte: u8) {
+        self.push(byte);
+    }
+}
+
+/// Counts the bytes that would be written.
+struct ByteCount(usize);
+
+impl Output for ByteCount {
+    fn put(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+/// A key as it is written, without allocating a string for it.
+#[derive(Debug, Clone, Copy)]
+enum Key<'k> {
+    Name(&'k str),
+    /// A key written in ASCII lowercase
+    Lowercase(&'k str),
+    /// The index of a shortcut or tag in its list
+    Index(usize),
+}
+
+impl Key<'_> {
+    fn write<O: Output>(self, out: &mut O) {
+        match self {
+            Key::Name(name) => out.put(name.as_bytes()),
+            Key::Lowercase(name) => {
+                for byte in name.bytes() {
+                    out.put_byte(byte.to_ascii_lowercase());
+                }
+            }
+            Key::Index(index) => {
+                // The decimal digits of a usize, from the back
+                let mut digits = [0u8; 20];
+                let mut start = digits.len();
+                let mut rest = index;
+                loop {
+                    start -= 1;
+                    digits[start] = b'0' + (rest % 10) as u8;
+                    rest /= 10;
+                    if rest == 0 {
+                        break;
+                    }
+                }
+                out.put(&digits[start..]);
+            }
+        }
+    }
+}
+
+fn write_file<O: Output>(
+    out: &mut O,
+    shortcuts: &[Shortcut],
+    spellings: &[KeySpelling],
+    options: &WriteOptions,
+) {
+    out.put_byte(NULL);
+    out.put(b"shortcuts");
+    out.put_byte(NULL);
+
+    let no_spelling = KeySpelling::default();
+    for (index, shortcut) in shortcuts.iter().enumerate() {
+        let spelling = spellings.get(index).unwrap_or(&no_spelling);
+        write_shortcut(out, Key::Index(index), shortcut, spelling, options);
+    }
+
+    out.put_byte(BS);
+    out.put_byte(BS);
+}
+
+fn write_shortcut<O: Output>(
+    out: &mut O,
+    order: Key,
+    shortcut: &Shortcut,
+    spelling: &KeySpelling,
+    options: &WriteOptions,
+) {
+    let keys = options.key_set();
+
+    out.put_byte(NULL);
+    order.write(out);
+    out.put_byte(NULL);
+
+    write_stx(out, options.key(spelling, "appid"), shortcut.app_id);
+    write_soh(out, options.key(spelling, keys.app_name), shortcut.app_name);
+    write_soh(out, options.key(spelling, "Exe"), shortcut.exe);
+    write_soh(out, options.key(spelling, "StartDir"), shortcut.start_dir);
+    write_soh(out, options.key(spelling, "icon"), shortcut.icon);
+    write_soh(
+        out,
+        options.key(spelling, "ShortcutPath"),
+        shortcut.shortcut_path,
+    );
+    write_soh(
+        out,
+        options.key(spelling, "LaunchOptions"),
+        shortcut.launch_options,
+    );
+    write_stx(
+        out,
+        options.key(spelling, "IsHidden"),
+        shortcut.is_hidden as u32,
+    );
+    write_stx(
+        out,
+        options.key(spelling, "AllowDesktopConfig"),
+        shortcut.allow_desktop_config as u32,
+    );
+    write_stx(
+        out,
+        options.key(spelling, "AllowOverlay"),
+        shortcut.allow_overlay as u32,
+    );
+    write_stx(out, options.key(spelling, "openvr"), shortcut.open_vr);
+    write_stx(out, options.key(spelling, "Devkit"), shortcut.dev_kit);
+    write_soh(
+        out,
+        options.key(spelling, "DevkitGameID"),
+        shortcut.dev_kit_game_id,
+    );
+    if keys.dev_kit_override_app_id
+        && options.writes_optional(
+            spelling,
+            "DevkitOverrideAppID",
+            shortcut.dev_kit_overrite_app_id == 0,
+        )
+    {
+        write_stx(
+            out,
+            options.key(spelling, "DevkitOverrideAppID"),
+            shortcut.dev_kit_overrite_app_id,
+        );
+    }
+
+    write_stx(
+        out,
+        options.key(spelling, "LastPlayTime"),
+        shortcut.last_play_time,
+    );
+    if keys.flatpak_app_id
+        && options.writes_optional(spelling, "FlatpakAppID", shortcut.flatpak_app_id.is_empty())
+    {
+        write_soh(
+            out,
+            options.key(spelling, "FlatpakAppID"),
+            shortcut.flatpak_app_id,
+        );
+    }
+
+    out.put_byte(NULL);
+    out.put(b"tags");
+    out.put_byte(NULL);
+
+    for (index, tag) in shortcut.tags.iter().enumerate() {
+        write_soh(out, Key::Index(index), tag);
+    }
+
+    out.put_byte(BS);
+    out.put_byte(BS);
+}
+
+fn write_soh<O: Output>(out: &mut O, key: Key, input: &str) {
+    out.put_byte(SOH);
+    key.write(out);
+    out.put_byte(NULL);
+    out.put(input.as_bytes());
+    out.put_byte(NULL);
+}
+
+fn write_stx<O: Output>(out: &mut O, key: Key, input: u32) {
+    out.put_byte(STX);
+    key.write(out);
+    out.put_byte(NULL);
+    out.put(&input.to_le_bytes());
+}
+
 #[cfg(test)]
 mod tests {
 
+    use super::*;
     use crate::{shortcuts_parser, shortcuts_to_bytes};
 
     #[test]
@@ -193,4 +483,136 @@ mod tests {
             shortcuts_parser::parse_shortcuts(shortcut_bytes_vec.as_slice()).unwrap();
         assert_eq!(shortcuts, shortcuts_again);
     }
+
+    #[test]
+    fn profiles_match_client_files() {
+        // Files written by each client, steam_deck.vdf is the first shortcut of failing.vdf,
+        // whose later shortcuts were added by other tools
+        let fixtures = vec![
+            ("src/testdata/shortcuts.vdf", ClientProfile::Legacy),
+            ("src/testdata/linux_shortcut.vdf", ClientProfile::Legacy),
+            (
+                "src/testdata/shortcutsfirefox.vdf",
+                ClientProfile::Desktop2023,
+            ),
+            ("src/testdata/steam_deck.vdf", ClientProfile::SteamDeck),
+        ];
+        for (path, profile) in fixtures {
+            let content = std::fs::read(path).unwrap();
+            let shortcuts = shortcuts_parser::parse_shortcuts(content.as_slice()).unwrap();
+            let bytes = shortcuts_to_bytes_for(&shortcuts, profile);
+            assert!(content == bytes, "{} written as {:?}", path, profile);
+        }
+    }
+
+    #[test]
+    fn numbers_starting_with_soh_parse_back() {
+        let mut shortcut = Shortcut::new("0", "Game", "game.exe", "", "", "", "");
+        shortcut.app_id = 12345601;
+        shortcut.is_hidden = true;
+        shortcut.allow_overlay = false;
+        shortcut.last_play_time = 1700000001;
+        let shortcuts = vec![shortcut];
+        let bytes = shortcuts_to_bytes(&shortcuts);
+        let parsed = shortcuts_parser::parse_shortcuts(bytes.as_slice()).unwrap();
+        assert_eq!(shortcuts, parsed);
+    }
+
+    #[test]
+    fn legacy_profile_leaves_out_new_keys() {
+        let mut shortcut = Shortcut::new("0", "Game", "game.exe", "", "", "", "");
+        shortcut.flatpak_app_id = "org.example.Game";
+        let bytes = shortcuts_to_bytes_for(&[shortcut], ClientProfile::Legacy);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(!text.contains("FlatpakAppID"));
+        assert!(!text.contains("DevkitOverrideAppID"));
+    }
+
+    #[test]
+    fn lowercase_keys_parse_back() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = shortcuts_parser::parse_shortcuts(content.as_slice()).unwrap();
+        let options = WriteOptions {
+            lowercase_keys: true,
+            ..WriteOptions::default()
+        };
+        let bytes = shortcuts_to_bytes_with(&shortcuts, &options);
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains("\u{1}exe\0"));
+        assert!(!text.contains("AllowOverlay"));
+        let shortcuts_again = shortcuts_parser::parse_shortcuts(bytes.as_slice()).unwrap();
+        assert_eq!(shortcuts.len(), shortcuts_again.len());
+        for (shortcut, again) in shortcuts.iter().zip(shortcuts_again.iter()) {
+            assert_eq!(shortcut.to_owned(), again.to_owned());
+        }
+    }
+
+    #[test]
+    fn keeps_key_spelling_of_parsed_file() {
+        let content = std::fs::read("src/testdata/shortcutsspore.vdf").unwrap();
+        let parsed = shortcuts_parser::parse_shortcuts_with(&content, &Default::default()).unwrap();
+        let options = WriteOptions::default();
+        let bytes =
+            shortcuts_to_bytes_with_spelling(&parsed.shortcuts, &parsed.key_spellings, &options);
+        assert_eq!(content, bytes);
+        let owned: Vec<_> = parsed
+            .key_spellings
+            .iter()
+            .map(|spelling| spelling.to_owned())
+            .collect();
+        let bytes = shortcuts_to_bytes_with_spelling(&parsed.shortcuts, &owned, &options);
+        assert_eq!(content, bytes);
+
+        let options = WriteOptions {
+            profile: Some(ClientProfile::SteamDeck),
+            ..WriteOptions::default()
+        };
+        let normalized = shortcuts_to_bytes_with(&parsed.shortcuts, &options);
+        let text = String::from_utf8_lossy(&normalized);
+        assert!(text.contains("openvr"));
+        assert!(!text.contains("OpenVR"));
+    }
+
+    #[test]
+    fn counts_the_bytes_it_writes() {
+        let content = std::fs::read("src/testdata/shortcutsfirefox.vdf").unwrap();
+        let shortcuts = shortcuts_parser::parse_shortcuts(content.as_slice()).unwrap();
+        let options = WriteOptions {
+            lowercase_keys: true,
+            ..WriteOptions::default()
+        };
+        let mut len = ByteCount(0);
+        write_file(&mut len, &shortcuts, &[], &options);
+        let bytes = shortcuts_to_bytes_with(&shortcuts, &options);
+        assert_eq!(bytes.len(), len.0);
+        assert_eq!(bytes.len(), bytes.capacity());
+    }
+
+    #[test]
+    fn lengths_match_written_bytes() {
+        let content = std::fs::read("src/testdata/shortcutsfirefox.vdf").unwrap();
+        let shortcuts = shortcuts_parser::parse_shortcuts(content.as_slice()).unwrap();
+        let bytes = shortcuts_to_bytes(&shortcuts);
+        assert_eq!(bytes.len(), shortcuts_len(&shortcuts));
+        // The header and the end of the list
+        let entries: usize = shortcuts.iter().map(Shortcut::serialized_len).sum();
+        assert_eq!(bytes.len(), entries + "\0shortcuts\0".len() + 2);
+    }
+
+    #[test]
+    fn writes_after_existing_bytes() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let shortcuts = shortcuts_parser::parse_shortcuts(content.as_slice()).unwrap();
+        let bytes = shortcuts_to_bytes(&shortcuts);
+        let mut buf = b"header".to_vec();
+        write_shortcuts_into(&mut buf, &shortcuts);
+        assert_eq!(b"header", &buf[..6]);
+        assert_eq!(bytes, &buf[6..]);
+
+        buf.clear();
+        let capacity = buf.capacity();
+        write_shortcuts_into(&mut buf, &shortcuts);
+        assert_eq!(bytes, buf);
+        assert_eq!(capacity, buf.capacity());
+    }
 }
@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use crate::shortcut::Shortcut;
+use crate::text_vdf;
+
+/// A steam app installed in a steam library, read from its appmanifest_*.acf file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledApp {
+    /// The steam app id
+    pub app_id: u32,
+    /// The name of the app
+    pub name: String,
+    /// The folder (inside steamapps/common) the app is installed in
+    pub install_dir: String,
+}
+
+/// What a shortcut has in common with an installed steam app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionKind {
+    /// The shortcut has the app id of the installed app
+    AppId,
+    /// The shortcut has the same name as the installed app
+    Name,
+}
+
+/// A shortcut that collides with an installed steam app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledAppCollision {
+    /// Index of the shortcut in the list that was checked
+    pub shortcut_index: usize,
+    /// The installed app the shortcut collides with
+    pub installed_app: InstalledApp,
+    /// What the shortcut and the installed app have in common
+    pub kind: CollisionKind,
+}
+
+/// Read the apps installed in a steam library.
+///
+/// The path can either be the library folder or the steamapps folder inside it.
+/// Manifests that can not be read or parsed are skipped.
+pub fn installed_apps(library: &Path) -> std::io::Result<Vec<InstalledApp>> {
+    let steamapps = library.join("steamapps");
+    let steamapps = if steamapps.is_dir() {
+        steamapps
+    } else {
+        library.to_path_buf()
+    };
+    let mut apps = vec![];
+    for entry in std::fs::read_dir(steamapps)? {
+        let path = entry?.path();
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if !(file_name.starts_with("appmanifest_") && file_name.ends_with(".acf")) {
+            continue;
+        }
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if let Some(app) = parse_app_manifest(&content) {
+            apps.push(app);
+        }
+    }
+    apps.sort_by_key(|app| app.app_id);
+    Ok(apps)
+}
+
+/// Parse the content of an appmanifest_*.acf file.
+pub fn parse_app_manifest(content: &str) -> Option<InstalledApp> {
+    let root = text_vdf::parse(content).ok()?;
+    let app_state = root.get_section("AppState")?;
+    let app_id = app_state.get_text("appid")?.parse().ok()?;
+    let name = app_state.get_text("name").unwrap_or_default().to_string();
+    let install_dir = app_state
+        .get_text("installdir")
+        .unwrap_or_default()
+        .to_string();
+    Some(InstalledApp {
+        app_id,
+        name,
+        install_dir,
+    })
+}
+
+/// Find shortcuts that have the app id or name of an app installed in the given steam library.
+///
+/// Such shortcuts confuse the steam overlay and the artwork caches.
+pub fn find_installed_app_collisions(
+    library: &Path,
+    shortcuts: &[Shortcut],
+) -> std::io::Result<Vec<InstalledAppCollision>> {
+    let apps = installed_apps(library)?;
+    Ok(find_collisions_with_apps(&apps, shortcuts))
+}
+
+/// Find shortcuts that have the app id or name of one of the given apps.
+pub fn find_collisions_with_apps(
+    apps: &[InstalledApp],
+    shortcuts: &[Shortcut],
+) -> Vec<InstalledAppCollision> {
+    let mut collisions = vec![];
+    for (shortcut_index, shortcut) in shortcuts.iter().enumerate() {
+        for app in apps {
+            let kind = if app.app_id == shortcut.app_id {
+                CollisionKind::AppId
+            } else if !app.name.is_empty()
+                && app.name.trim().to_lowercase() == shortcut.app_name.trim().to_lowercase()
+            {
+                CollisionKind::Name
+            } else {
+                continue;
+            };
+            collisions.push(InstalledAppCollision {
+                shortcut_index,
+                installed_app: app.clone(),
+                kind,
+            });
+        }
+    }
+    collisions
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn finds_collisions_in_library() {
+        let temp = temp_dir();
+        let library = temp.path();
+        let steamapps = library.join("steamapps");
+        std::fs::create_dir_all(&steamapps).unwrap();
+        std::fs::write(
+            steamapps.join("appmanifest_504230.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"504230\"\n\t\"name\"\t\t\"Celeste\"\n\t\"installdir\"\t\t\"Celeste\"\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            steamapps.join("libraryfolders.vdf"),
+            "\"libraryfolders\" {}",
+        )
+        .unwrap();
+
+        let celeste = Shortcut::new("0", "celeste ", "celeste.exe", "", "", "", "");
+        let mut other = Shortcut::new("1", "Other", "other.exe", "", "", "", "");
+        other.app_id = 504230;
+        let spore = Shortcut::new("2", "Spore", "spore.exe", "", "", "", "");
+        let collisions = find_installed_app_collisions(library, &[celeste, other, spore]).unwrap();
+
+        assert_eq!(2, collisions.len());
+        assert_eq!(0, collisions[0].shortcut_index);
+        assert_eq!(CollisionKind::Name, collisions[0].kind);
+        assert_eq!(1, collisions[1].shortcut_index);
+        assert_eq!(CollisionKind::AppId, collisions[1].kind);
+        assert_eq!("Celeste", collisions[1].installed_app.install_dir);
+    }
+
+    #[test]
+    fn skips_manifests_that_can_not_be_read() {
+        let temp = temp_dir();
+        let library = temp.path();
+        std::fs::write(
+            library.join("appmanifest_504230.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"504230\"\n\t\"name\"\t\t\"Celeste\"\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            library.join("appmanifest_1.acf"),
+            b"\"AppState\" { \xff\xfe }",
+        )
+        .unwrap();
+        let apps = installed_apps(library).unwrap();
+        assert_eq!(1, apps.len());
+        assert_eq!("Celeste", apps[0].name);
+    }
+}
@@ -0,0 +1,277 @@
+//! Finding the steam installation and the shortcuts.vdf files inside it.
+
+use std::path::{Path, PathBuf};
+
+use crate::{steam_id, text_vdf};
+
+/// A steam account that has logged in on this machine, read from config/loginusers.vdf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginUser {
+    /// The 64 bit steam id of the account
+    pub steam_id: u64,
+    /// The account id, which is also the name of the users folder in userdata
+    pub account_id: u32,
+    /// The account name used to log in
+    pub account_name: String,
+    /// Is this the account that logged in most recently
+    pub most_recent: bool,
+    /// The time of the last login in seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+/// Find the root folder of the steam installation.
+///
+/// On Windows the install path is read from the registry, on Linux the usual native
+/// and Flatpak locations are checked, and on macOS the Application Support folder is used.
+/// Returns the first of [candidates] that looks like a steam installation.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::steam_dirs;
+///
+/// if let Some(steam_root) = steam_dirs::locate() {
+///     for user_id in steam_dirs::user_ids(&steam_root) {
+///         println!("{:?}", steam_dirs::shortcuts_path(&steam_root, &user_id));
+///     }
+/// }
+/// ```
+pub fn locate() -> Option<PathBuf> {
+    candidates().into_iter().next()
+}
+
+/// All folders on this machine that look like a steam installation, most likely first.
+pub fn candidates() -> Vec<PathBuf> {
+    let mut paths = registry_paths();
+    if let Some(home) = home_dir() {
+        paths.extend(home_candidates(&home));
+    }
+    if cfg!(windows) {
+        paths.push(PathBuf::from("C:\\Program Files (x86)\\Steam"));
+        paths.push(PathBuf::from("C:\\Program Files\\Steam"));
+    }
+
+    let mut found: Vec<PathBuf> = vec![];
+    for path in paths {
+        if !is_steam_root(&path) {
+            continue;
+        }
+        let path = path.canonicalize().unwrap_or(path);
+        if !found.contains(&path) {
+            found.push(path);
+        }
+    }
+    found
+}
+
+/// Find the steam installation like [locate], as a `steamlocate` steam dir (requires the
+/// `steamlocate` feature).
+#[cfg(feature = "steamlocate")]
+pub fn locate_steam_dir() -> Option<steamlocate::SteamDir> {
+    candidates()
+        .iter()
+        .find_map(|path| steamlocate::SteamDir::from_dir(path).ok())
+}
+
+/// The ids of the users that have a folder in the userdata folder of the steam installation.
+pub fn user_ids(steam_root: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = std::fs::read_dir(steam_root.join("userdata"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+                .filter(|name| steam_id::from_userdata_folder(name).is_some())
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+/// The path of the shortcuts.vdf file for a user, whether or not it exists.
+///
+/// The user id is the account id, as a number or text, so ids and paths from other steam
+/// discovery crates (like `steamlocate`) can be passed without converting them.
+///
+/// ### Examples
+/// ```
+/// use std::path::Path;
+/// use steam_shortcuts_util::steam_dirs::shortcuts_path;
+///
+/// let root = Path::new("/home/deck/.steam/steam");
+/// assert_eq!(shortcuts_path(root, 12345u32), shortcuts_path(root, "12345"));
+/// ```
+pub fn shortcuts_path<P: AsRef<Path>, U: std::fmt::Display>(steam_root: P, user_id: U) -> PathBuf {
+    steam_root
+        .as_ref()
+        .join("userdata")
+        .join(user_id.to_string())
+        .join("config")
+        .join("shortcuts.vdf")
+}
+
+/// The accounts that have logged in to the steam installation.
+pub fn login_users(steam_root: &Path) -> std::io::Result<Vec<LoginUser>> {
+    let content = std::fs::read_to_string(steam_root.join("config").join("loginusers.vdf"))?;
+    Ok(parse_login_users(&content))
+}
+
+/// Parse the content of a loginusers.vdf file.
+pub fn parse_login_users(content: &str) -> Vec<LoginUser> {
+    let root = match text_vdf::parse(content) {
+        Ok(root) => root,
+        Err(_) => return vec![],
+    };
+    let users = match root.get_section("users") {
+        Some(users) => users,
+        None => return vec![],
+    };
+    users
+        .entries
+        .iter()
+        .filter_map(|(steam_id, user)| {
+            let steam_id: u64 = steam_id.parse().ok()?;
+            let user = user.as_section()?;
+            let text = |key: &str| user.get_text(key).unwrap_or_default();
+            Some(LoginUser {
+                steam_id,
+                account_id: steam_id::account_id(steam_id),
+                account_name: text("AccountName").to_string(),
+                most_recent: text("MostRecent") == "1",
+                timestamp: text("Timestamp").parse().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// The account that logged in to the steam installation most recently.
+///
+/// Uses the MostRecent flag steam sets, and falls back to the latest login timestamp.
+pub fn most_recent_user(steam_root: &Path) -> Option<LoginUser> {
+    let users = login_users(steam_root).ok()?;
+    pick_most_recent(users)
+}
+
+fn pick_most_recent(users: Vec<LoginUser>) -> Option<LoginUser> {
+    match users.iter().find(|user| user.most_recent) {
+        Some(user) => Some(user.clone()),
+        None => users.into_iter().max_by_key(|user| user.timestamp),
+    }
+}
+
+fn is_steam_root(path: &Path) -> bool {
+    path.join("steamapps").is_dir() || path.join("userdata").is_dir()
+}
+
+fn home_candidates(home: &Path) -> Vec<PathBuf> {
+    if cfg!(target_os = "macos") {
+        vec![home.join("Library/Application Support/Steam")]
+    } else if cfg!(windows) {
+        vec![]
+    } else {
+        vec![
+            home.join(".steam/steam"),
+            home.join(".local/share/Steam"),
+            home.join(".steam/root"),
+            home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+            home.join(".var/app/com.valvesoftware.Steam/.steam/steam"),
+        ]
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(var)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(windows)]
+fn registry_paths() -> Vec<PathBuf> {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use winreg::RegKey;
+
+    let lookups = [
+        (HKEY_CURRENT_USER, "Software\\Valve\\Steam", "SteamPath"),
+        (
+            HKEY_LOCAL_MACHINE,
+            "SOFTWARE\\Wow6432Node\\Valve\\Steam",
+            "InstallPath",
+        ),
+        (HKEY_LOCAL_MACHINE, "SOFTWARE\\Valve\\Steam", "InstallPath"),
+    ];
+    lookups
+        .iter()
+        .filter_map(|(hive, key, value)| {
+            RegKey::predef(*hive)
+                .open_subkey(key)
+                .and_then(|key| key.get_value::<String, _>(value))
+                .ok()
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn registry_paths() -> Vec<PathBuf> {
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn finds_flatpak_install() {
+        let temp = temp_dir();
+        let home = temp.path();
+        let flatpak_root = home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam");
+        std::fs::create_dir_all(flatpak_root.join("userdata/12345/config")).unwrap();
+        std::fs::create_dir_all(flatpak_root.join("userdata/not_a_user")).unwrap();
+
+        let found: Vec<PathBuf> = home_candidates(home)
+            .into_iter()
+            .filter(|path| is_steam_root(path))
+            .collect();
+        let user_ids = user_ids(&flatpak_root);
+
+        assert_eq!(vec![flatpak_root.clone()], found);
+        assert_eq!(vec!["12345".to_string()], user_ids);
+        assert!(shortcuts_path(&flatpak_root, "12345").ends_with("12345/config/shortcuts.vdf"));
+    }
+
+    #[test]
+    fn most_recent_login_user() {
+        let content = r#""users"
+{
+	"76561197960287930"
+	{
+		"AccountName"		"gaben"
+		"MostRecent"		"0"
+		"Timestamp"		"1650000000"
+	}
+	"76561198000000000"
+	{
+		"AccountName"		"player"
+		"MostRecent"		"1"
+		"Timestamp"		"1640000000"
+	}
+}
+"#;
+        let users = parse_login_users(content);
+        assert_eq!(2, users.len());
+        assert_eq!(22202, users[0].account_id);
+
+        let most_recent = pick_most_recent(users.clone()).unwrap();
+        assert_eq!("player", most_recent.account_name);
+        assert_eq!(39734272, most_recent.account_id);
+
+        let mut without_flag = users;
+        without_flag[1].most_recent = false;
+        let newest = pick_most_recent(without_flag).unwrap();
+        assert_eq!("gaben", newest.account_name);
+    }
+}
@@ -0,0 +1,107 @@
+//! Conversions between the ways steam identifies an account.
+//!
+//! The shortcuts of an account are stored in `userdata/<account id>`, where the account id is
+//! the lower 32 bits of the 64 bit steam id (`76561198000000000` ...) that loginusers.vdf and
+//! web APIs use. Steam also shows ids in the `[U:1:<account id>]` form (steam3).
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// The upper 32 bits of the steam id of every individual account in the public universe.
+const INDIVIDUAL_ACCOUNT: u64 = 0x0110_0001_0000_0000;
+
+/// The account id of a 64 bit steam id, which is also its folder name in userdata.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::steam_id;
+///
+/// assert_eq!(39734272, steam_id::account_id(76561198000000000));
+/// ```
+pub fn account_id(steam_id64: u64) -> u32 {
+    (steam_id64 & 0xFFFF_FFFF) as u32
+}
+
+/// The 64 bit steam id of an individual account.
+///
+/// This is the inverse of [account_id], e.g. `39734272` becomes `76561198000000000`.
+pub fn steam_id64(account_id: u32) -> u64 {
+    INDIVIDUAL_ACCOUNT | account_id as u64
+}
+
+/// The name of the folder in userdata for an account.
+pub fn userdata_folder(account_id: u32) -> String {
+    account_id.to_string()
+}
+
+/// The account id of a folder in userdata, `None` if the name is not an account id.
+pub fn from_userdata_folder(name: &str) -> Option<u32> {
+    name.parse().ok()
+}
+
+/// Format an account id as a steam3 id, e.g. `[U:1:39734272]`.
+pub fn to_steam3(account_id: u32) -> String {
+    format!("[U:1:{}]", account_id)
+}
+
+/// The account id of a steam3 id of an individual account, with or without the brackets.
+pub fn from_steam3(text: &str) -> Option<u32> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .unwrap_or(text);
+    text.strip_prefix("U:1:")?.parse().ok()
+}
+
+/// The account id of a user id in any of the forms steam uses.
+///
+/// Accepts an account id, a 64 bit steam id or a steam3 id. Returns `None` for anything else,
+/// including 64 bit ids of groups or other universes, which do not have a userdata folder.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::steam_id;
+///
+/// assert_eq!(Some(39734272), steam_id::parse("39734272"));
+/// assert_eq!(Some(39734272), steam_id::parse("76561198000000000"));
+/// assert_eq!(Some(39734272), steam_id::parse("[U:1:39734272]"));
+/// assert_eq!(None, steam_id::parse("gaben"));
+/// ```
+pub fn parse(text: &str) -> Option<u32> {
+    let text = text.trim();
+    if text.starts_with('[') || text.starts_with("U:") {
+        return from_steam3(text);
+    }
+    let id: u64 = text.parse().ok()?;
+    if id <= u32::MAX as u64 {
+        Some(id as u32)
+    } else if id & !0xFFFF_FFFF == INDIVIDUAL_ACCOUNT {
+        Some(account_id(id))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(76561198000000000, steam_id64(39734272));
+        assert_eq!(39734272, account_id(steam_id64(39734272)));
+        assert_eq!("[U:1:22202]", to_steam3(22202));
+        assert_eq!(Some(22202), from_steam3(&to_steam3(22202)));
+        assert_eq!(Some(22202), from_userdata_folder(&userdata_folder(22202)));
+    }
+
+    #[test]
+    fn rejects_other_ids() {
+        assert_eq!(None, from_steam3("[G:1:22202]"));
+        assert_eq!(None, from_userdata_folder("anonymous"));
+        // A group in the public universe
+        assert_eq!(None, parse("103582791429521412"));
+    }
+}
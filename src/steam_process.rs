@@ -0,0 +1,95 @@
+//! Detecting a running steam client.
+//!
+//! Steam keeps the shortcuts in memory while it runs, and writes them back when it exits.
+//! Changes written to shortcuts.vdf while steam is running are therefore lost.
+
+/// Check if the steam client is running on this machine.
+///
+/// On Windows the ActiveProcess pid steam writes to the registry is checked.
+/// On Linux the steam.pid file is checked, falling back to looking for a steam process.
+/// On macOS `pgrep` is used to look for the steam process.
+pub fn steam_is_running() -> bool {
+    platform::steam_is_running()
+}
+
+#[cfg(windows)]
+mod platform {
+    pub fn steam_is_running() -> bool {
+        use winreg::enums::HKEY_CURRENT_USER;
+        use winreg::RegKey;
+
+        RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey("Software\\Valve\\Steam\\ActiveProcess")
+            .and_then(|key| key.get_value::<u32, _>("pid"))
+            .map(|pid| pid != 0)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn steam_is_running() -> bool {
+        std::process::Command::new("pgrep")
+            .args(["-x", "steam_osx"])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use std::path::Path;
+
+    pub fn steam_is_running() -> bool {
+        let proc = Path::new("/proc");
+        let pid_file_running = std::env::var_os("HOME")
+            .map(|home| Path::new(&home).join(".steam").join("steam.pid"))
+            .and_then(|pid_file| std::fs::read_to_string(pid_file).ok())
+            .map(|pid| pid_is_steam(proc, pid.trim()))
+            .unwrap_or(false);
+        pid_file_running || any_steam_process(proc)
+    }
+
+    pub(super) fn pid_is_steam(proc: &Path, pid: &str) -> bool {
+        if pid.is_empty() || pid.parse::<u32>().is_err() {
+            return false;
+        }
+        std::fs::read_to_string(proc.join(pid).join("comm"))
+            .map(|comm| is_steam_process_name(comm.trim()))
+            .unwrap_or(false)
+    }
+
+    fn any_steam_process(proc: &Path) -> bool {
+        std::fs::read_dir(proc)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+                    .any(|pid| pid_is_steam(proc, &pid))
+            })
+            .unwrap_or(false)
+    }
+
+    fn is_steam_process_name(name: &str) -> bool {
+        name == "steam" || name == "steam.sh" || name == "steamwebhelper"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn own_process_is_not_steam() {
+        let own_pid = std::process::id().to_string();
+        assert!(!super::platform::pid_is_steam(
+            std::path::Path::new("/proc"),
+            &own_pid
+        ));
+        assert!(!super::platform::pid_is_steam(
+            std::path::Path::new("/proc"),
+            "not a pid"
+        ));
+    }
+}
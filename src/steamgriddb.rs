@@ -0,0 +1,250 @@
+//! A small client for [SteamGridDB](https://www.steamgriddb.com) (requires the `steamgriddb` feature).
+//!
+//! Searches for games by name and downloads their images into the grid folder,
+//! named so steam shows them for a shortcut. An api key can be created at
+//! <https://www.steamgriddb.com/profile/preferences/api>.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::artwork::{self, ArtworkKind};
+use crate::shortcut::Shortcut;
+
+const BASE_URL: &str = "https://www.steamgriddb.com/api/v2";
+
+/// Error from talking to SteamGridDB.
+#[derive(Debug)]
+pub enum SteamGridDbError {
+    /// The request failed or the response could not be read
+    Http(String),
+    /// The downloaded image could not be written to the grid folder
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for SteamGridDbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SteamGridDbError::Http(err) => write!(f, "SteamGridDB request failed: {}", err),
+            SteamGridDbError::Io(err) => write!(f, "Could not save image: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SteamGridDbError {}
+
+impl From<std::io::Error> for SteamGridDbError {
+    fn from(err: std::io::Error) -> Self {
+        SteamGridDbError::Io(err)
+    }
+}
+
+/// A game found on SteamGridDB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// The SteamGridDB id of the game
+    pub id: u64,
+    /// The name of the game
+    pub name: String,
+}
+
+/// A client for the SteamGridDB api.
+#[derive(Debug, Clone)]
+pub struct SteamGridDbClient {
+    api_key: String,
+    base_url: String,
+}
+
+impl SteamGridDbClient {
+    /// Create a client using the given api key.
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            base_url: BASE_URL.to_string(),
+        }
+    }
+
+    /// Search for games by name, best match first.
+    pub fn search(&self, name: &str) -> Result<Vec<GameResult>, SteamGridDbError> {
+        let url = format!(
+            "{}/search/autocomplete/{}",
+            self.base_url,
+            encode_path_segment(name)
+        );
+        let response = self.get_json(&url)?;
+        Ok(parse_search(&response))
+    }
+
+    /// The urls of the images of a kind SteamGridDB has for a game, best rated first.
+    pub fn image_urls(
+        &self,
+        game_id: u64,
+        kind: ArtworkKind,
+    ) -> Result<Vec<String>, SteamGridDbError> {
+        let url = format!("{}/{}", self.base_url, images_path(game_id, kind));
+        let response = self.get_json(&url)?;
+        Ok(parse_image_urls(&response))
+    }
+
+    /// Download the best image of a kind for a game, as the artwork for the shortcut.
+    ///
+    /// Returns the path the image was saved to, or `None` if SteamGridDB has no image of that kind.
+    pub fn download_artwork(
+        &self,
+        grid_dir: &Path,
+        shortcut: &Shortcut,
+        game_id: u64,
+        kind: ArtworkKind,
+    ) -> Result<Option<PathBuf>, SteamGridDbError> {
+        let url = match self.image_urls(game_id, kind)?.into_iter().next() {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+        let extension = if url.to_lowercase().ends_with(".png") {
+            "png"
+        } else {
+            "jpg"
+        };
+        let mut bytes = vec![];
+        ureq::get(&url)
+            .call()
+            .map_err(|err| SteamGridDbError::Http(err.to_string()))?
+            .into_reader()
+            .read_to_end(&mut bytes)?;
+
+        std::fs::create_dir_all(grid_dir)?;
+        artwork::remove_artwork(grid_dir, shortcut, kind)?;
+        let target = grid_dir.join(artwork::artwork_file_name(shortcut.app_id, kind, extension));
+        std::fs::write(&target, bytes)?;
+        Ok(Some(target))
+    }
+
+    /// Search for the app name of the shortcut and download every kind of artwork for the best match.
+    ///
+    /// Returns the paths of the downloaded images, which is empty if no game was found.
+    pub fn download_all_artwork(
+        &self,
+        grid_dir: &Path,
+        shortcut: &Shortcut,
+    ) -> Result<Vec<PathBuf>, SteamGridDbError> {
+        let game = match self.search(shortcut.app_name)?.into_iter().next() {
+            Some(game) => game,
+            None => return Ok(vec![]),
+        };
+        let mut downloaded = vec![];
+        for kind in ArtworkKind::ALL.iter() {
+            if let Some(path) = self.download_artwork(grid_dir, shortcut, game.id, *kind)? {
+                downloaded.push(path);
+            }
+        }
+        Ok(downloaded)
+    }
+
+    fn get_json(&self, url: &str) -> Result<Value, SteamGridDbError> {
+        ureq::get(url)
+            .set("Authorization", &format!("Bearer {}", self.api_key))
+            .call()
+            .map_err(|err| SteamGridDbError::Http(err.to_string()))?
+            .into_json()
+            .map_err(|err| SteamGridDbError::Http(err.to_string()))
+    }
+}
+
+fn images_path(game_id: u64, kind: ArtworkKind) -> String {
+    match kind {
+        ArtworkKind::Grid => format!(
+            "grids/game/{}?dimensions=600x900&mimes=image/png,image/jpeg",
+            game_id
+        ),
+        ArtworkKind::WideGrid => format!(
+            "grids/game/{}?dimensions=920x430,460x215&mimes=image/png,image/jpeg",
+            game_id
+        ),
+        ArtworkKind::Hero => format!("heroes/game/{}?mimes=image/png,image/jpeg", game_id),
+        ArtworkKind::Logo => format!("logos/game/{}?mimes=image/png", game_id),
+        ArtworkKind::Icon => format!("icons/game/{}?mimes=image/png", game_id),
+    }
+}
+
+fn parse_search(response: &Value) -> Vec<GameResult> {
+    data(response)
+        .iter()
+        .filter_map(|game| {
+            Some(GameResult {
+                id: game.get("id")?.as_u64()?,
+                name: game.get("name")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_image_urls(response: &Value) -> Vec<String> {
+    data(response)
+        .iter()
+        .filter_map(|image| Some(image.get("url")?.as_str()?.to_string()))
+        .collect()
+}
+
+fn data(response: &Value) -> &[Value] {
+    response
+        .get("data")
+        .and_then(|data| data.as_array())
+        .map(|data| data.as_slice())
+        .unwrap_or_default()
+}
+
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parse_search_response() {
+        let response: Value = serde_json::from_str(
+            r#"{"success":true,"data":[{"id":2254,"name":"Celeste","types":["steam"],"verified":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![GameResult {
+                id: 2254,
+                name: "Celeste".to_string()
+            }],
+            parse_search(&response)
+        );
+    }
+
+    #[test]
+    fn parse_images_response() {
+        let response: Value = serde_json::from_str(
+            r#"{"success":true,"data":[{"id":1,"url":"https://cdn2.steamgriddb.com/grid/a.png"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["https://cdn2.steamgriddb.com/grid/a.png".to_string()],
+            parse_image_urls(&response)
+        );
+        let failed: Value = serde_json::from_str(r#"{"success":false}"#).unwrap();
+        assert!(parse_image_urls(&failed).is_empty());
+    }
+
+    #[test]
+    fn encode_game_name() {
+        assert_eq!(
+            "Death%20Stranding%3A%20DC",
+            encode_path_segment("Death Stranding: DC")
+        );
+    }
+}
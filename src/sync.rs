@@ -0,0 +1,111 @@
+//! Keeping the shortcuts of several steam installs the same, e.g. the Windows and Linux installs
+//! of a dual-boot machine.
+
+use std::path::{Path, PathBuf};
+
+use crate::path_translation::{PathStyle, PathTranslator};
+use crate::shortcuts_file::{ShortcutsFile, ShortcutsFileError};
+
+/// A steam install [sync_installs] writes the shortcuts to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncTarget {
+    /// The shortcuts.vdf file of the install, e.g. from [shortcuts_path](crate::steam_dirs::shortcuts_path)
+    pub path: PathBuf,
+    /// The kind of paths the install uses
+    pub style: PathStyle,
+    /// Translates the paths of the source to those of the install
+    pub translator: PathTranslator,
+}
+
+impl SyncTarget {
+    /// A target that writes `path`, with paths translated to `style` by `translator`.
+    pub fn new<P: AsRef<Path>>(path: P, style: PathStyle, translator: PathTranslator) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            style,
+            translator,
+        }
+    }
+}
+
+/// Write the shortcuts of `source` to the shortcuts.vdf of every target, with their paths
+/// translated for the target.
+///
+/// The shortcuts the targets had are replaced. The app ids are kept as they are in the source,
+/// so artwork named after them can be copied to the grid folder of the targets as is. The
+/// shortcuts are written even if steam is running, as that is usually the steam of the source.
+///
+/// Returns the result of writing each target, in the order of the targets. A target that fails
+/// does not stop the others from being written.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::path_translation::{PathStyle, PathTranslator};
+/// use steam_shortcuts_util::sync::{sync_installs, SyncTarget};
+/// use steam_shortcuts_util::ShortcutsFile;
+///
+/// fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     let source = ShortcutsFile::load_for_most_recent_user()?;
+///     let windows = SyncTarget::new(
+///         "/mnt/c/Program Files (x86)/Steam/userdata/1/config/shortcuts.vdf",
+///         PathStyle::Windows,
+///         PathTranslator::new().map("D:\\Games", "/mnt/d/Games"),
+///     );
+///     for result in sync_installs(&source, &[windows]) {
+///         result?;
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn sync_installs(
+    source: &ShortcutsFile,
+    targets: &[SyncTarget],
+) -> Vec<Result<(), ShortcutsFileError>> {
+    targets
+        .iter()
+        .map(|target| {
+            let mut file = ShortcutsFile::new(&target.path);
+            let shortcuts = file.shortcuts_mut();
+            shortcuts.extend_from_slice(source.shortcuts());
+            for shortcut in shortcuts.iter_mut() {
+                target.translator.translate_shortcut(shortcut, target.style);
+            }
+            file.save()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn targets_get_translated_paths() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let mut source = ShortcutsFile::new(dir.join("linux.vdf"));
+        let exe = "\"/mnt/games/celeste.exe\"";
+        let celeste = Shortcut::new("0", "Celeste", exe, "", "", "", "").to_owned();
+        source.shortcuts_mut().push(celeste.clone());
+
+        let translator = PathTranslator::new().map("C:\\Games", "/mnt/games");
+        let targets = [
+            SyncTarget::new(dir.join("windows.vdf"), PathStyle::Windows, translator),
+            SyncTarget::new(
+                dir.join("missing").join("\0"),
+                PathStyle::Unix,
+                Default::default(),
+            ),
+        ];
+        let results = sync_installs(&source, &targets);
+        let windows = ShortcutsFile::load(dir.join("windows.vdf")).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!("\"C:\\Games\\celeste.exe\"", windows.shortcuts()[0].exe);
+        assert_eq!(celeste.app_id, windows.shortcuts()[0].app_id);
+    }
+}
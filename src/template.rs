@@ -0,0 +1,158 @@
+//! Creating many shortcuts that only differ in name and exe, e.g. one for every rom of an emulator.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::launch_options::{quote_if_needed, unquoted};
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// The fields shared by the shortcuts an importer creates.
+///
+/// The start dir and launch options can contain placeholders, which
+/// [instantiate](Self::instantiate) replaces for every shortcut:
+///
+/// * `{name}` - the name of the shortcut
+/// * `{exe}` - the exe of the shortcut
+///
+/// [instantiate_with](Self::instantiate_with) can replace more placeholders, like the path of
+/// a rom. Placeholders without a value are kept as they are.
+///
+/// Values filled into the launch options are quoted for the shell where needed, so placeholders
+/// are written without quotes around them. A value that already is in quotes, like the exe of
+/// most shortcuts, is quoted once.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::ShortcutTemplate;
+///
+/// let template = ShortcutTemplate {
+///     start_dir: "/emulators".to_string(),
+///     launch_options: "-L snes.so {rom}".to_string(),
+///     tags: vec!["SNES".to_string()],
+///     ..Default::default()
+/// };
+/// let mario = template.instantiate_with(
+///     "Super Mario World",
+///     "/emulators/retroarch",
+///     &[("rom", "/roms/Super Mario World.sfc")],
+/// );
+/// assert_eq!(
+///     "-L snes.so \"/roms/Super Mario World.sfc\"",
+///     mario.launch_options
+/// );
+/// assert_eq!("SNES", mario.tags[0]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShortcutTemplate {
+    /// The directory the shortcuts start in
+    pub start_dir: String,
+    /// The launch options of the shortcuts
+    pub launch_options: String,
+    /// The tags every shortcut gets
+    pub tags: Vec<String>,
+    /// Hide the shortcuts in the library
+    pub is_hidden: bool,
+}
+
+impl ShortcutTemplate {
+    /// Create a shortcut with this name and exe, with order `"0"` and the app id steam
+    /// calculates for it.
+    pub fn instantiate(&self, name: &str, exe: &str) -> ShortcutOwned {
+        self.instantiate_with(name, exe, &[])
+    }
+
+    /// Create a shortcut as [instantiate](Self::instantiate), also replacing `{key}` with the
+    /// value for every `(key, value)` in `values`.
+    pub fn instantiate_with(
+        &self,
+        name: &str,
+        exe: &str,
+        values: &[(&str, &str)],
+    ) -> ShortcutOwned {
+        let value = |key: &str| match key {
+            "name" => Some(name),
+            "exe" => Some(exe),
+            _ => values.iter().find(|(k, _)| *k == key).map(|(_, v)| *v),
+        };
+        let start_dir = fill(&self.start_dir, |key| value(key).map(String::from));
+        let launch_options = fill(&self.launch_options, |key| {
+            value(key).map(|value| quote_if_needed(unquoted(value)))
+        });
+        let mut shortcut =
+            Shortcut::new("0", name, exe, &start_dir, "", "", &launch_options).to_owned();
+        shortcut.tags.extend(self.tags.iter().cloned());
+        shortcut.is_hidden = self.is_hidden;
+        shortcut
+    }
+}
+
+/// Replace the placeholders in `pattern` that have a value.
+///
+/// Values are not searched for placeholders, so a name with `{exe}` in it stays as it is.
+fn fill<F: Fn(&str) -> Option<String>>(pattern: &str, value: F) -> String {
+    let mut filled = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after
+            .find('}')
+            .and_then(|end| Some((end, value(&after[..end])?)))
+        {
+            Some((end, value)) => {
+                filled.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_once() {
+        let template = ShortcutTemplate {
+            start_dir: "{dir".to_string(),
+            launch_options: "{name} {{exe}} {unknown}".to_string(),
+            is_hidden: true,
+            ..Default::default()
+        };
+        let shortcut = template.instantiate("{exe}", "run.exe");
+        assert_eq!("{dir", shortcut.start_dir);
+        assert_eq!("{exe} {run.exe} {unknown}", shortcut.launch_options);
+        assert!(shortcut.is_hidden);
+        assert_eq!(
+            crate::app_id_generator::calculate_app_id("run.exe", "{exe}"),
+            shortcut.app_id
+        );
+    }
+
+    #[test]
+    fn launch_option_values_are_quoted() {
+        let template = ShortcutTemplate {
+            start_dir: "{dir}".to_string(),
+            launch_options: "{exe} --save {save}".to_string(),
+            ..Default::default()
+        };
+        let save = r#"/saves/"best" $HOME"#;
+        let shortcut = template.instantiate_with(
+            "Game",
+            "\"/games/run.sh\"",
+            &[("save", save), ("dir", save)],
+        );
+        assert_eq!(
+            r#"/games/run.sh --save "/saves/\"best\" \$HOME""#,
+            shortcut.launch_options
+        );
+        assert_eq!(save, shortcut.start_dir);
+    }
+}
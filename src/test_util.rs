@@ -0,0 +1,9 @@
+//! Helpers shared by the tests.
+
+/// A new empty directory for one test, removed when it is dropped.
+pub(crate) fn temp_dir() -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix("steam_shortcuts_util_")
+        .tempdir()
+        .unwrap()
+}
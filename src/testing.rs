@@ -0,0 +1,127 @@
+//! Generators of valid shortcuts for property based tests (requires the `proptest` feature).
+//!
+//! The shortcuts have names in any script, empty fields and up to a dozen tags, so they cover
+//! much more than the files in a steam install. Use them to test code that reads or writes
+//! shortcuts:
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use steam_shortcuts_util::testing;
+//!
+//! proptest! {
+//!     fn names_are_kept(shortcuts in testing::shortcuts(10)) {
+//!         let names: Vec<&str> = shortcuts.iter().map(|s| s.app_name.as_str()).collect();
+//!         prop_assert_eq!(names.len(), shortcuts.len());
+//!     }
+//! }
+//! # names_are_kept();
+//! ```
+//!
+//! Texts never contain a null byte, as they are null terminated in shortcuts.vdf, and tags never
+//! contain a backspace (`\x08`), which ends the list of tags.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::shortcut::{ShortcutOwned, TagsOwned};
+
+/// A text for a field of a shortcut, empty about a quarter of the time.
+pub fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        1 => Just(String::new()),
+        3 => "[^\\x00]{1,40}",
+    ]
+}
+
+/// A tag of a shortcut.
+pub fn tag() -> impl Strategy<Value = String> {
+    "[^\\x00\\x08]{0,20}"
+}
+
+/// A number for a field of a shortcut.
+pub fn number() -> impl Strategy<Value = u32> {
+    any::<u32>()
+}
+
+/// A shortcut with order `"0"`.
+///
+/// The app id is a [number], so it does not always match the exe and app name.
+pub fn shortcut() -> impl Strategy<Value = ShortcutOwned> {
+    let texts = (
+        text(),
+        text(),
+        text(),
+        text(),
+        text(),
+        text(),
+        text(),
+        text(),
+    );
+    let numbers = (number(), number(), number(), number(), number());
+    let flags = (any::<bool>(), any::<bool>(), any::<bool>());
+    (texts, numbers, flags, vec(tag(), 0..12)).prop_map(|(texts, numbers, flags, tags)| {
+        let (app_name, exe, start_dir, icon, shortcut_path, launch_options, game_id, flatpak) =
+            texts;
+        let (app_id, open_vr, dev_kit, dev_kit_overrite_app_id, last_play_time) = numbers;
+        let (is_hidden, allow_desktop_config, allow_overlay) = flags;
+        ShortcutOwned {
+            order: "0".to_string(),
+            app_id,
+            app_name,
+            exe,
+            start_dir,
+            icon,
+            shortcut_path,
+            launch_options,
+            is_hidden,
+            allow_desktop_config,
+            allow_overlay,
+            open_vr,
+            dev_kit,
+            dev_kit_game_id: game_id,
+            dev_kit_overrite_app_id,
+            last_play_time,
+            flatpak_app_id: flatpak,
+            tags: tags.into_iter().collect::<TagsOwned>(),
+        }
+    })
+}
+
+/// Up to `max_len` shortcuts, with their index as their order.
+pub fn shortcuts(max_len: usize) -> impl Strategy<Value = Vec<ShortcutOwned>> {
+    vec(shortcut(), 0..=max_len).prop_map(|mut shortcuts| {
+        for (index, shortcut) in shortcuts.iter_mut().enumerate() {
+            shortcut.order = index.to_string();
+        }
+        shortcuts
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::shortcut::Shortcut;
+    use crate::{parse_shortcut_views, parse_shortcuts, shortcuts_to_bytes};
+
+    proptest! {
+        #[test]
+        fn shortcuts_round_trip(shortcuts in shortcuts(8)) {
+            let bytes = shortcuts_to_bytes(&shortcuts.iter().map(ShortcutOwned::borrow).collect());
+            let borrowed: Vec<Shortcut> = shortcuts.iter().map(ShortcutOwned::borrow).collect();
+            let parsed = parse_shortcuts(&bytes).unwrap();
+            prop_assert_eq!(borrowed, parsed);
+        }
+
+        #[test]
+        fn views_read_what_was_written(shortcuts in shortcuts(8)) {
+            let bytes = shortcuts_to_bytes(&shortcuts.iter().map(ShortcutOwned::borrow).collect());
+            let views = parse_shortcut_views(&bytes).unwrap();
+            prop_assert_eq!(shortcuts.len(), views.len());
+            for (shortcut, view) in shortcuts.iter().zip(views.iter()) {
+                prop_assert_eq!(Ok(shortcut.app_name.as_str()), view.app_name());
+                prop_assert_eq!(shortcut.last_play_time, view.last_play_time());
+            }
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Reading and writing of Valve's text KeyValues format.
+//!
+//! Steam keeps most of its configuration (appmanifest_*.acf, loginusers.vdf, config.vdf, ...)
+//! in this format, as opposed to the binary format used for shortcuts.vdf.
+//! Keys are compared case insensitively, like steam does.
+
+/// A value in a text vdf file, either a text or a nested section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    /// A plain text value
+    Text(String),
+    /// A nested section with its own keys
+    Section(Section),
+}
+
+impl Value {
+    /// Get the text of this value, if it is a text.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text),
+            Value::Section(_) => None,
+        }
+    }
+
+    /// Get this value as a section, if it is a section.
+    pub fn as_section(&self) -> Option<&Section> {
+        match self {
+            Value::Text(_) => None,
+            Value::Section(section) => Some(section),
+        }
+    }
+
+    /// Get this value as a mutable section, if it is a section.
+    pub fn as_section_mut(&mut self) -> Option<&mut Section> {
+        match self {
+            Value::Text(_) => None,
+            Value::Section(section) => Some(section),
+        }
+    }
+}
+
+/// A list of key value pairs, in the order they appear in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Section {
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Section {
+    /// Create an empty section.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the first value with the given key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Get the first value with the given key as mutable.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Get the text with the given key.
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_text)
+    }
+
+    /// Get the section with the given key.
+    pub fn get_section(&self, key: &str) -> Option<&Section> {
+        self.get(key).and_then(Value::as_section)
+    }
+
+    /// Get the section with the given key as mutable.
+    pub fn get_section_mut(&mut self, key: &str) -> Option<&mut Section> {
+        self.get_mut(key).and_then(Value::as_section_mut)
+    }
+
+    /// Follow a path of section keys, e.g. `["InstallConfigStore", "Software", "Valve"]`.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Section> {
+        path.iter()
+            .try_fold(self, |section, key| section.get_section(key))
+    }
+
+    /// Follow a path of section keys, for editing.
+    pub fn get_path_mut(&mut self, path: &[&str]) -> Option<&mut Section> {
+        path.iter()
+            .try_fold(self, |section, key| section.get_section_mut(key))
+    }
+
+    /// Get the section with the given key, inserting an empty one if it is missing.
+    ///
+    /// If the key exists but holds a text, the text is replaced by an empty section.
+    pub fn section_or_insert(&mut self, key: &str) -> &mut Section {
+        let position = self
+            .entries
+            .iter()
+            .position(|(k, _)| k.eq_ignore_ascii_case(key));
+        let index = match position {
+            Some(index) => {
+                if self.entries[index].1.as_section().is_none() {
+                    self.entries[index].1 = Value::Section(Section::new());
+                }
+                index
+            }
+            None => {
+                self.entries
+                    .push((key.to_string(), Value::Section(Section::new())));
+                self.entries.len() - 1
+            }
+        };
+        match &mut self.entries[index].1 {
+            Value::Section(section) => section,
+            Value::Text(_) => unreachable!("entry was just made a section"),
+        }
+    }
+
+    /// Follow a path of section keys, inserting empty sections where they are missing.
+    pub fn path_or_insert(&mut self, path: &[&str]) -> &mut Section {
+        path.iter()
+            .fold(self, |section, key| section.section_or_insert(key))
+    }
+
+    /// Set the text for the given key, replacing the existing value if there is one.
+    pub fn set_text(&mut self, key: &str, value: &str) {
+        match self.get_mut(key) {
+            Some(existing) => *existing = Value::Text(value.to_string()),
+            None => self
+                .entries
+                .push((key.to_string(), Value::Text(value.to_string()))),
+        }
+    }
+
+    /// Set the section for the given key, replacing the existing value if there is one.
+    pub fn set_section(&mut self, key: &str, value: Section) {
+        match self.get_mut(key) {
+            Some(existing) => *existing = Value::Section(value),
+            None => self.entries.push((key.to_string(), Value::Section(value))),
+        }
+    }
+
+    /// Remove all values with the given key, returning the first of them.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let mut removed = None;
+        let mut index = 0;
+        while index < self.entries.len() {
+            if self.entries[index].0.eq_ignore_ascii_case(key) {
+                let (_, value) = self.entries.remove(index);
+                if removed.is_none() {
+                    removed = Some(value);
+                }
+            } else {
+                index += 1;
+            }
+        }
+        removed
+    }
+
+    /// Serialize this section (as the root of a file) to text, the way steam writes it.
+    pub fn to_text(&self) -> String {
+        let mut res = String::new();
+        write_entries(&mut res, self, 0);
+        res
+    }
+}
+
+/// Parse the content of a text vdf file.
+///
+/// ### Examples
+/// ```
+/// use steam_shortcuts_util::text_vdf;
+///
+/// let root = text_vdf::parse("\"AppState\" { \"appid\" \"228980\" }").unwrap();
+/// let app_id = root.get_path(&["AppState"]).unwrap().get_text("appid");
+/// assert_eq!(Some("228980"), app_id);
+/// ```
+pub fn parse(input: &str) -> Result<Section, String> {
+    let mut tokenizer = Tokenizer {
+        chars: input.char_indices().peekable(),
+    };
+    let section = parse_section(&mut tokenizer, false)?;
+    Ok(section)
+}
+
+/// Read and parse a text vdf file.
+///
+/// A missing file is read as an empty section, so it can be filled and written with [write_file].
+pub fn read_file(path: &std::path::Path) -> std::io::Result<Section> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            parse(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Section::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Write a section as the root of a text vdf file, creating the parent folder if needed.
+pub fn write_file(path: &std::path::Path, root: &Section) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    std::fs::write(path, root.to_text())
+}
+
+fn parse_section(tokenizer: &mut Tokenizer, nested: bool) -> Result<Section, String> {
+    let mut section = Section::new();
+    loop {
+        let key = match tokenizer.next_token()? {
+            None if nested => return Err("Unexpected end of file, missing '}'".to_string()),
+            None => return Ok(section),
+            Some(Token::Close) if nested => return Ok(section),
+            Some(Token::Close) => return Err("Unexpected '}'".to_string()),
+            Some(Token::Open) => return Err("Expected a key but found '{'".to_string()),
+            Some(Token::Condition) => continue,
+            Some(Token::Text(key)) => key,
+        };
+        let value = match tokenizer.next_token()? {
+            Some(Token::Text(text)) => Value::Text(text),
+            Some(Token::Open) => Value::Section(parse_section(tokenizer, true)?),
+            _ => return Err(format!("Missing value for key '{}'", key)),
+        };
+        section.entries.push((key, value));
+    }
+}
+
+enum Token {
+    Text(String),
+    Open,
+    Close,
+    // Conditionals like [$WIN32] are ignored
+    Condition,
+}
+
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn next_token(&mut self) -> Result<Option<Token>, String> {
+        loop {
+            let (position, c) = match self.chars.next() {
+                Some(next) => next,
+                None => return Ok(None),
+            };
+            match c {
+                c if c.is_whitespace() => continue,
+                '{' => return Ok(Some(Token::Open)),
+                '}' => return Ok(Some(Token::Close)),
+                '"' => return self.quoted(position).map(|text| Some(Token::Text(text))),
+                '/' if matches!(self.chars.peek(), Some((_, '/'))) => {
+                    for (_, c) in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                '[' => {
+                    for (_, c) in self.chars.by_ref() {
+                        if c == ']' {
+                            return Ok(Some(Token::Condition));
+                        }
+                    }
+                    return Err(format!("Unterminated condition at offset {}", position));
+                }
+                c => {
+                    let mut text = c.to_string();
+                    while let Some((_, c)) = self.chars.peek() {
+                        if c.is_whitespace() || matches!(c, '{' | '}' | '"') {
+                            break;
+                        }
+                        text.push(*c);
+                        self.chars.next();
+                    }
+                    return Ok(Some(Token::Text(text)));
+                }
+            }
+        }
+    }
+
+    fn quoted(&mut self, start: usize) -> Result<String, String> {
+        let mut text = String::new();
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '"' => return Ok(text),
+                '\\' => match self.chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, 'r')) => text.push('\r'),
+                    Some((_, escaped)) => text.push(escaped),
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        Err(format!("Unterminated string starting at offset {}", start))
+    }
+}
+
+fn write_entries(res: &mut String, section: &Section, depth: usize) {
+    let indent = "\t".repeat(depth);
+    for (key, value) in &section.entries {
+        match value {
+            Value::Text(text) => {
+                res.push_str(&format!(
+                    "{}\"{}\"\t\t\"{}\"\n",
+                    indent,
+                    escape(key),
+                    escape(text)
+                ));
+            }
+            Value::Section(nested) => {
+                res.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+                write_entries(res, nested, depth + 1);
+                res.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const MANIFEST: &str = r#""AppState"
+{
+	"appid"		"228980"
+	"name"		"Steamworks Common Redistributables"
+	// comments are ignored
+	"UserConfig"
+	{
+		"language"		"english"
+	}
+}
+"#;
+
+    #[test]
+    fn parse_manifest() {
+        let root = parse(MANIFEST).unwrap();
+        let app_state = root.get_section("appstate").unwrap();
+        assert_eq!(Some("228980"), app_state.get_text("AppID"));
+        assert_eq!(
+            Some("english"),
+            app_state
+                .get_section("UserConfig")
+                .unwrap()
+                .get_text("language")
+        );
+    }
+
+    #[test]
+    fn parse_and_write_back() {
+        let root = parse(MANIFEST).unwrap();
+        let text = root.to_text();
+        assert_eq!(root, parse(&text).unwrap());
+        assert!(text.starts_with("\"AppState\"\n{\n\t\"appid\"\t\t\"228980\"\n"));
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let mut root = Section::new();
+        root.path_or_insert(&["a", "b"])
+            .set_text("path", "C:\\Games\\\"quoted\"");
+        root.set_text("lines", "one\ntwo\r\nthree\tfour");
+        let text = root.to_text();
+        assert!(!text.contains("one\n"));
+        let parsed = parse(&text).unwrap();
+        assert_eq!(
+            Some("C:\\Games\\\"quoted\""),
+            parsed.get_path(&["a", "b"]).unwrap().get_text("path")
+        );
+        assert_eq!(Some("one\ntwo\r\nthree\tfour"), parsed.get_text("lines"));
+    }
+
+    #[test]
+    fn missing_close_is_an_error() {
+        assert!(parse("\"a\" { \"b\" \"c\"").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Kotlin and Swift bindings made with [UniFFI](https://mozilla.github.io/uniffi-rs/)
+//! (requires the `uniffi` feature).
+//!
+//! Build the crate as a `cdylib` and generate the bindings from it:
+//!
+//! ```text
+//! cargo rustc --release --features uniffi --crate-type cdylib
+//! uniffi-bindgen generate --library target/release/libsteam_shortcuts_util.so \
+//!     --language kotlin --out-dir bindings
+//! ```
+//!
+//! The functions work on the bytes of shortcuts.vdf, reading and writing the file is left
+//! to the app, so the same format implementation is used on every platform.
+
+use crate::shortcut::{Shortcut, ShortcutOwned};
+
+/// A shortcut as it is passed to Kotlin and Swift.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct ShortcutRecord {
+    pub app_id: u32,
+    pub app_name: String,
+    pub exe: String,
+    pub start_dir: String,
+    pub icon: String,
+    pub shortcut_path: String,
+    pub launch_options: String,
+    pub is_hidden: bool,
+    pub allow_desktop_config: bool,
+    pub allow_overlay: bool,
+    pub open_vr: u32,
+    pub dev_kit: u32,
+    pub dev_kit_game_id: String,
+    pub dev_kit_override_app_id: u32,
+    pub last_play_time: u32,
+    pub flatpak_app_id: String,
+    pub tags: Vec<String>,
+}
+
+/// Error from [parse_shortcuts].
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Error)]
+pub enum ShortcutsError {
+    /// The bytes are not a valid shortcuts.vdf file
+    Parse { message: String },
+}
+
+impl std::fmt::Display for ShortcutsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutsError::Parse { message } => {
+                write!(f, "Could not parse shortcuts: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShortcutsError {}
+
+/// Parse the content of a shortcuts.vdf file.
+#[uniffi::export]
+pub fn parse_shortcuts(data: Vec<u8>) -> Result<Vec<ShortcutRecord>, ShortcutsError> {
+    let shortcuts =
+        crate::parse_shortcuts(&data).map_err(|message| ShortcutsError::Parse { message })?;
+    Ok(shortcuts.iter().map(ShortcutRecord::from).collect())
+}
+
+/// Serialize shortcuts to the content of a shortcuts.vdf file.
+#[uniffi::export]
+pub fn shortcuts_to_bytes(shortcuts: Vec<ShortcutRecord>) -> Vec<u8> {
+    let owned: Vec<ShortcutOwned> = shortcuts
+        .iter()
+        .enumerate()
+        .map(|(index, record)| record.to_shortcut(index))
+        .collect();
+    let borrowed: Vec<Shortcut> = owned.iter().map(|s| s.borrow()).collect();
+    crate::shortcuts_to_bytes(&borrowed)
+}
+
+/// Calculate the app id steam uses for a shortcut with this exe and app name.
+#[uniffi::export]
+pub fn calculate_app_id(exe: String, app_name: String) -> u32 {
+    crate::app_id_generator::calculate_app_id(&exe, &app_name)
+}
+
+impl From<&Shortcut<'_>> for ShortcutRecord {
+    fn from(shortcut: &Shortcut<'_>) -> Self {
+        Self {
+            app_id: shortcut.app_id,
+            app_name: shortcut.app_name.to_string(),
+            exe: shortcut.exe.to_string(),
+            start_dir: shortcut.start_dir.to_string(),
+            icon: shortcut.icon.to_string(),
+            shortcut_path: shortcut.shortcut_path.to_string(),
+            launch_options: shortcut.launch_options.to_string(),
+            is_hidden: shortcut.is_hidden,
+            allow_desktop_config: shortcut.allow_desktop_config,
+            allow_overlay: shortcut.allow_overlay,
+            open_vr: shortcut.open_vr,
+            dev_kit: shortcut.dev_kit,
+            dev_kit_game_id: shortcut.dev_kit_game_id.to_string(),
+            dev_kit_override_app_id: shortcut.dev_kit_overrite_app_id,
+            last_play_time: shortcut.last_play_time,
+            flatpak_app_id: shortcut.flatpak_app_id.to_string(),
+            tags: shortcut.tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+}
+
+impl ShortcutRecord {
+    fn to_shortcut(&self, index: usize) -> ShortcutOwned {
+        let order = index.to_string();
+        let mut shortcut = Shortcut::new(
+            &order,
+            &self.app_name,
+            &self.exe,
+            &self.start_dir,
+            &self.icon,
+            &self.shortcut_path,
+            &self.launch_options,
+        );
+        shortcut.app_id = self.app_id;
+        shortcut.is_hidden = self.is_hidden;
+        shortcut.allow_desktop_config = self.allow_desktop_config;
+        shortcut.allow_overlay = self.allow_overlay;
+        shortcut.open_vr = self.open_vr;
+        shortcut.dev_kit = self.dev_kit;
+        shortcut.dev_kit_game_id = &self.dev_kit_game_id;
+        shortcut.dev_kit_overrite_app_id = self.dev_kit_override_app_id;
+        shortcut.last_play_time = self.last_play_time;
+        shortcut.flatpak_app_id = &self.flatpak_app_id;
+        shortcut.tags = self.tags.iter().map(|tag| tag.as_str()).collect();
+        shortcut.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn records_write_the_same_shortcuts() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let records = parse_shortcuts(content.clone()).unwrap();
+        assert_eq!("Celeste", records[0].app_name);
+        let written = shortcuts_to_bytes(records);
+        assert_eq!(
+            crate::parse_shortcuts(&content).unwrap(),
+            crate::parse_shortcuts(&written).unwrap()
+        );
+        assert!(parse_shortcuts(b"nope".to_vec()).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! JavaScript bindings for web based shortcut editors (requires the `wasm` feature).
+//!
+//! Build with `wasm-pack build --target web -- --features wasm` (the crate must be built as a
+//! `cdylib` for that). Shortcuts are passed to JavaScript as JSON in the schema of
+//! [shortcuts_json](crate::shortcuts_json), so they can be used with `JSON.parse`:
+//!
+//! ```js
+//! const bytes = new Uint8Array(await file.arrayBuffer());
+//! const { shortcuts } = JSON.parse(parseShortcuts(bytes));
+//! shortcuts[0].launch_options = "-windowed";
+//! const written = shortcutsToBytes(JSON.stringify({ version: 1, shortcuts }));
+//! ```
+//!
+//! Nothing here touches the file system, the bytes are read and written by the caller.
+
+use wasm_bindgen::prelude::*;
+
+use crate::shortcut::Shortcut;
+
+/// Parse the content of a shortcuts.vdf file to JSON.
+#[wasm_bindgen(js_name = parseShortcuts)]
+pub fn parse_shortcuts(bytes: &[u8]) -> Result<String, JsValue> {
+    parse_to_json(bytes).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Serialize shortcuts in JSON to the content of a shortcuts.vdf file.
+#[wasm_bindgen(js_name = shortcutsToBytes)]
+pub fn shortcuts_to_bytes(json: &str) -> Result<Vec<u8>, JsValue> {
+    json_to_bytes(json).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Calculate the app id steam uses for a shortcut with this exe and app name.
+#[wasm_bindgen(js_name = calculateAppId)]
+pub fn calculate_app_id(exe: &str, app_name: &str) -> u32 {
+    crate::app_id_generator::calculate_app_id(exe, app_name)
+}
+
+fn parse_to_json(bytes: &[u8]) -> Result<String, String> {
+    let shortcuts = crate::parse_shortcuts(bytes)?;
+    Ok(crate::to_json(&shortcuts))
+}
+
+fn json_to_bytes(json: &str) -> Result<Vec<u8>, String> {
+    let shortcuts = crate::from_json(json)?;
+    let borrowed: Vec<Shortcut> = shortcuts.iter().map(|s| s.borrow()).collect();
+    Ok(crate::shortcuts_to_bytes(&borrowed))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn bytes_to_json_and_back() {
+        let content = std::fs::read("src/testdata/shortcuts.vdf").unwrap();
+        let json = parse_to_json(&content).unwrap();
+        let bytes = json_to_bytes(&json).unwrap();
+        assert_eq!(
+            crate::parse_shortcuts(&content).unwrap().len(),
+            crate::parse_shortcuts(&bytes).unwrap().len()
+        );
+        assert!(parse_to_json(b"not a shortcuts file").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Watching a shortcuts file for changes (requires the `watch` feature).
+
+use std::path::{Path, PathBuf};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::shortcut::ShortcutOwned;
+use crate::shortcuts_file::{ShortcutsFile, ShortcutsFileError};
+
+/// Keeps watching a shortcuts file until it is dropped.
+pub struct ShortcutsWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Call `callback` with the reparsed shortcuts every time the file is changed.
+///
+/// The parent folder is watched instead of the file itself, so changes are also
+/// seen when the file is replaced or created. A change can be seen while the file
+/// is still being written, in which case the callback gets an error and is called
+/// again when the write continues. The returned watcher must be kept alive for as
+/// long as the file should be watched.
+///
+/// ### Examples
+/// ```no_run
+/// use steam_shortcuts_util::watch::watch_shortcuts;
+///
+/// let _watcher = watch_shortcuts("path/to/shortcuts.vdf", |shortcuts| match shortcuts {
+///     Ok(shortcuts) => println!("{} shortcuts", shortcuts.len()),
+///     Err(err) => println!("{}", err),
+/// })
+/// .unwrap();
+/// ```
+pub fn watch_shortcuts<P, F>(path: P, mut callback: F) -> notify::Result<ShortcutsWatcher>
+where
+    P: AsRef<Path>,
+    F: FnMut(Result<Vec<ShortcutOwned>, ShortcutsFileError>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let watched_dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(_) => return,
+        };
+        if event.kind.is_access() || event.kind.is_remove() {
+            return;
+        }
+        let is_our_file = event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name().map(|n| n.to_os_string()) == file_name);
+        if is_our_file {
+            callback(ShortcutsFile::load(&path).map(|file| file.shortcuts().to_vec()));
+        }
+    })?;
+    watcher.watch(&watched_dir, RecursiveMode::NonRecursive)?;
+    Ok(ShortcutsWatcher { _watcher: watcher })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_changed_shortcuts() {
+        let temp = temp_dir();
+        let dir = temp.path();
+        let path = dir.join("shortcuts.vdf");
+
+        let (sender, receiver) = channel();
+        let _watcher = watch_shortcuts(&path, move |shortcuts| {
+            let _ = sender.send(shortcuts.map(|s| s.len()));
+        })
+        .unwrap();
+        std::fs::copy("src/testdata/shortcuts.vdf", &path).unwrap();
+
+        // The file can be seen while it is only partially written, so wait for a parse that succeeds
+        let mut count = None;
+        while let Ok(shortcuts) = receiver.recv_timeout(Duration::from_secs(5)) {
+            if let Ok(len) = shortcuts {
+                count = Some(len);
+                break;
+            }
+        }
+        assert_eq!(Some(42), count);
+    }
+}